@@ -0,0 +1,942 @@
+use crate::{
+    fields::FieldIdent,
+    generics::{fresh_ident, fresh_lifetime, with_const, with_lifetime},
+    raw::{array_as_chunk, parse_align, parse_nested, raw_inherent, raw_storage, Column},
+    serde, SoaDerive,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, TokenStreamExt};
+use syn::{
+    ext::IdentExt, parse_quote, punctuated::Punctuated, token::Comma, Fields, Generics, Ident,
+    Type, Variant, Visibility,
+};
+
+pub fn enum_soa(
+    ident: Ident,
+    vis: Visibility,
+    generics: Generics,
+    variants: Punctuated<Variant, Comma>,
+    soa_derive: SoaDerive,
+) -> Result<TokenStream, syn::Error> {
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "Soars requires enums to have at least one variant",
+        ));
+    }
+
+    let variants = variants
+        .into_iter()
+        .map(EnumVariant::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The per-variant methods are named after the snake case variant name,
+    // which can be the same for variants that differ only in case.
+    for (i, variant) in variants.iter().enumerate() {
+        if let Some(other) = variants[..i]
+            .iter()
+            .find(|other| other.snake == variant.snake)
+        {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "variant name collides with {} in snake case: {}",
+                    other.ident, variant.snake
+                ),
+            ));
+        }
+    }
+
+    let tag = format_ident!("{ident}Tag");
+    let deref = format_ident!("{ident}Deref");
+    let item_ref = format_ident!("{ident}Ref");
+    let item_ref_mut = format_ident!("{ident}RefMut");
+    let slices = format_ident!("{ident}Slices");
+    let array = format_ident!("{ident}Array");
+    let chunk = format_ident!("{ident}Chunk");
+    let raw = format_ident!("{ident}SoaRaw");
+
+    let mut columns = vec![Column {
+        ident: FieldIdent::Named(format_ident!("tag")),
+        ty: parse_quote! { #tag },
+        align: None,
+        nested: false,
+    }];
+    for variant in variants.iter() {
+        for field in variant.fields.iter() {
+            let ident = FieldIdent::Named(field.column.clone());
+            if columns.iter().any(|column| column.ident == ident) {
+                return Err(syn::Error::new_spanned(
+                    &field.column,
+                    format!(
+                        "field name collides with another variant's field: {}",
+                        field.column
+                    ),
+                ));
+            }
+            columns.push(Column {
+                ident,
+                ty: field.ty.clone(),
+                align: field.align,
+                nested: false,
+            });
+        }
+    }
+
+    let lt = fresh_lifetime(&generics);
+    let n = fresh_ident(&generics, "N");
+    let generics_lt = with_lifetime(&generics, &lt);
+    let generics_n = with_const(&generics, &n);
+    let generics_lt_n = with_const(&generics_lt, &n);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics_lt, ty_generics_lt, _) = generics_lt.split_for_impl();
+    let (impl_generics_n, ty_generics_n, _) = generics_n.split_for_impl();
+    let (impl_generics_lt_n, ty_generics_lt_n, _) = generics_lt_n.split_for_impl();
+    let item = quote! { #ident #ty_generics };
+
+    // The generated reference types need to use their lifetime somewhere, even
+    // when none of the variants have fields to borrow.
+    let needs_phantom = variants.iter().all(|variant| variant.fields.is_empty());
+    let (phantom_variant, phantom_arm) = if needs_phantom {
+        (
+            quote! {
+                #[doc(hidden)]
+                __Phantom(
+                    ::core::marker::PhantomData<&#lt ()>,
+                    ::core::convert::Infallible,
+                ),
+            },
+            quote! {
+                Self::__Phantom(_, never) => match *never {},
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let variant_all: Vec<_> = variants.iter().map(|variant| &variant.ident).collect();
+    let column_all: Vec<_> = variants
+        .iter()
+        .flat_map(|variant| variant.fields.iter().map(|field| &field.column))
+        .collect();
+    let ty_all: Vec<_> = variants
+        .iter()
+        .flat_map(|variant| variant.fields.iter().map(|field| &field.ty))
+        .collect();
+    let with_fields: Vec<_> = variants
+        .iter()
+        .filter(|variant| !variant.fields.is_empty())
+        .collect();
+
+    let mut out = TokenStream::new();
+
+    out.append_all(quote! {
+        #[automatically_derived]
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::cmp::PartialOrd,
+            ::core::cmp::Ord,
+            ::core::hash::Hash,
+        )]
+        #vis enum #tag {
+            #(#variant_all),*
+        }
+    });
+
+    let as_variant_slices = with_fields.iter().map(|variant| {
+        let EnumVariant {
+            ident: variant_ident,
+            snake,
+            ..
+        } = variant;
+        let getter = format_ident!("as_{snake}");
+        let getter_mut = format_ident!("as_{snake}_mut");
+        let columns = variant.columns();
+        let tys = variant.tys();
+        let (ty_ref, ty_mut) = variant.payload(
+            &tys.iter().map(|ty| quote! { &[#ty] }).collect::<Vec<_>>(),
+            &tys.iter()
+                .map(|ty| quote! { &mut [#ty] })
+                .collect::<Vec<_>>(),
+        );
+        let (get_ref, get_mut) = variant.payload(
+            &columns
+                .iter()
+                .map(|column| {
+                    quote! {
+                        ::core::slice::from_raw_parts(self.0.raw().#column.as_ptr(), len)
+                    }
+                })
+                .collect::<Vec<_>>(),
+            &columns
+                .iter()
+                .map(|column| {
+                    quote! {
+                        ::core::slice::from_raw_parts_mut(self.0.raw().#column.as_ptr(), len)
+                    }
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        quote! {
+            #vis fn #getter(&self) -> ::core::option::Option<#ty_ref> {
+                if self.tags().iter().all(|tag| *tag == #tag::#variant_ident) {
+                    let len = self.0.len();
+                    // SAFETY: Every element holds this variant, so the columns
+                    // are initialized up to the length.
+                    ::core::option::Option::Some(unsafe { #get_ref })
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+
+            #vis fn #getter_mut(&mut self) -> ::core::option::Option<#ty_mut> {
+                if self.tags().iter().all(|tag| *tag == #tag::#variant_ident) {
+                    let len = self.0.len();
+                    // SAFETY: Every element holds this variant, so the columns
+                    // are initialized up to the length.
+                    ::core::option::Option::Some(unsafe { #get_mut })
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+        }
+    });
+
+    out.append_all(quote! {
+        #[automatically_derived]
+        #[repr(transparent)]
+        #vis struct #deref #impl_generics (::soa_rs::Slice<#item>) #where_clause;
+
+        impl #impl_generics ::soa_rs::SoaDeref for #deref #ty_generics #where_clause {
+            type Item = #item;
+
+            fn from_slice(slice: &::soa_rs::Slice<Self::Item>) -> &Self {
+                let slice = ::core::ptr::from_ref(slice) as *const Self;
+                unsafe { &*slice }
+            }
+
+            fn from_slice_mut(slice: &mut ::soa_rs::Slice<Self::Item>) -> &mut Self {
+                let slice = ::core::ptr::from_mut(slice) as *mut Self;
+                unsafe { &mut *slice }
+            }
+        }
+
+        impl #impl_generics #deref #ty_generics #where_clause {
+            #vis fn tags(&self) -> &[#tag] {
+                let ptr = self.0.raw().tag.as_ptr();
+                let len = self.0.len();
+                unsafe { ::core::slice::from_raw_parts(ptr, len) }
+            }
+
+            #(#as_variant_slices)*
+        }
+    });
+
+    // Copy and Clone are implemented by hand for the types that require them
+    // so that they don't pick up bounds on the generic parameters.
+    let mut extra_minus_copy = soa_derive.clone();
+    extra_minus_copy.remove("Copy");
+    extra_minus_copy.remove("Clone");
+    let extra_minus_copy = extra_minus_copy.into_derive();
+    let with_serde = soa_derive.serde;
+    let extra = soa_derive.into_derive();
+
+    let impl_copy = |ty: &Ident, impl_generics, ty_generics| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::clone::Clone for #ty #ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics ::core::marker::Copy for #ty #ty_generics #where_clause {}
+        }
+    };
+
+    let define_variants = |type_mapper: &dyn Fn(&Type) -> TokenStream| {
+        variants
+            .iter()
+            .map(|variant| {
+                let variant_ident = &variant.ident;
+                let tys = variant.tys().into_iter().map(type_mapper);
+                match variant.kind {
+                    VariantKind::Named => {
+                        let fields = variant.fields.iter().map(|field| &field.ident);
+                        quote! { #variant_ident { #(#fields: #tys),* } }
+                    }
+                    VariantKind::Unnamed => quote! { #variant_ident ( #(#tys),* ) },
+                    VariantKind::Unit => quote! { #variant_ident },
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let tag_arms = variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        quote! { Self::#variant_ident { .. } => #tag::#variant_ident, }
+    });
+    let is_variant = variants.iter().map(|variant| {
+        let EnumVariant {
+            ident: variant_ident,
+            snake,
+            ..
+        } = variant;
+        let getter = format_ident!("is_{snake}");
+        quote! {
+            #vis fn #getter(&self) -> bool {
+                ::core::matches!(self, Self::#variant_ident { .. })
+            }
+        }
+    });
+    let tag_methods = quote! {
+        #vis fn tag(&self) -> #tag {
+            match self {
+                #(#tag_arms)*
+                #phantom_arm
+            }
+        }
+
+        #(#is_variant)*
+    };
+
+    let item_ref_variants = define_variants(&|ty| quote! { &#lt #ty });
+    let item_ref_copy = impl_copy(&item_ref, &impl_generics_lt, &ty_generics_lt);
+    let as_variant_ref = with_fields.iter().map(|variant| {
+        let getter = format_ident!("as_{}", variant.snake);
+        let pattern = variant.pattern(quote! { Self });
+        let bindings = variant.bindings();
+        let tys = variant.tys();
+        let (ty, value) = variant.payload(
+            &tys.iter().map(|ty| quote! { &#lt #ty }).collect::<Vec<_>>(),
+            &bindings
+                .iter()
+                .map(|binding| quote! { *#binding })
+                .collect::<Vec<_>>(),
+        );
+        quote! {
+            #[allow(unreachable_patterns)]
+            #vis fn #getter(&self) -> ::core::option::Option<#ty> {
+                match self {
+                    #pattern => ::core::option::Option::Some(#value),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    });
+    let item_ref_from_ref_mut = variants.iter().map(|variant| {
+        let pattern = variant.pattern(quote! { Self });
+        let construct = variant.construct(
+            quote! { #item_ref },
+            &variant
+                .bindings()
+                .iter()
+                .map(|binding| quote! { &**#binding })
+                .collect::<Vec<_>>(),
+        );
+        quote! { #pattern => #construct, }
+    });
+    out.append_all(quote! {
+        #extra_minus_copy
+        #[automatically_derived]
+        #vis enum #item_ref #impl_generics_lt #where_clause {
+            #(#item_ref_variants,)*
+            #phantom_variant
+        }
+
+        #item_ref_copy
+
+        impl #impl_generics_lt #item_ref #ty_generics_lt #where_clause {
+            #tag_methods
+
+            #(#as_variant_ref)*
+        }
+
+        impl #impl_generics_lt ::soa_rs::AsSoaRef for #item_ref #ty_generics_lt #where_clause {
+            type Item = #item;
+
+            fn as_soa_ref(&self) -> <Self::Item as ::soa_rs::Soars>::Ref<'_> {
+                *self
+            }
+        }
+    });
+
+    let item_ref_mut_variants = define_variants(&|ty| quote! { &#lt mut #ty });
+    let as_variant_mut = with_fields.iter().map(|variant| {
+        let getter = format_ident!("as_{}", variant.snake);
+        let getter_into = format_ident!("into_{}", variant.snake);
+        let pattern = variant.pattern(quote! { Self });
+        let bindings = variant.bindings();
+        let tys = variant.tys();
+        let (ty_borrowed, ty_owned) = variant.payload(
+            &tys.iter().map(|ty| quote! { &mut #ty }).collect::<Vec<_>>(),
+            &tys.iter()
+                .map(|ty| quote! { &#lt mut #ty })
+                .collect::<Vec<_>>(),
+        );
+        let (value_borrowed, value_owned) = variant.payload(
+            &bindings
+                .iter()
+                .map(|binding| quote! { &mut **#binding })
+                .collect::<Vec<_>>(),
+            &bindings
+                .iter()
+                .map(|binding| quote! { #binding })
+                .collect::<Vec<_>>(),
+        );
+        quote! {
+            #[allow(unreachable_patterns)]
+            #vis fn #getter(&mut self) -> ::core::option::Option<#ty_borrowed> {
+                match self {
+                    #pattern => ::core::option::Option::Some(#value_borrowed),
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            #[allow(unreachable_patterns)]
+            #vis fn #getter_into(self) -> ::core::option::Option<#ty_owned> {
+                match self {
+                    #pattern => ::core::option::Option::Some(#value_owned),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    });
+    out.append_all(quote! {
+        #extra
+        #[automatically_derived]
+        #vis enum #item_ref_mut #impl_generics_lt #where_clause {
+            #(#item_ref_mut_variants,)*
+            #phantom_variant
+        }
+
+        impl #impl_generics_lt #item_ref_mut #ty_generics_lt #where_clause {
+            #tag_methods
+
+            #(#as_variant_mut)*
+        }
+
+        impl #impl_generics_lt ::soa_rs::AsSoaRef for #item_ref_mut #ty_generics_lt #where_clause {
+            type Item = #item;
+
+            fn as_soa_ref(&self) -> <Self::Item as ::soa_rs::Soars>::Ref<'_> {
+                match self {
+                    #(#item_ref_from_ref_mut)*
+                    #phantom_arm
+                }
+            }
+        }
+    });
+
+    // Payload columns are only initialized where the tag selects their
+    // variant, so they are exposed as MaybeUninit. Handing out mutable access
+    // would allow safe code to deinitialize an active field, so the same type
+    // serves as both the shared and the mutable slices.
+    let slices_copy = impl_copy(&slices, &impl_generics_lt, &ty_generics_lt);
+    out.append_all(quote! {
+        #[automatically_derived]
+        #vis struct #slices #impl_generics_lt #where_clause {
+            #vis tag: &#lt [#tag],
+            #(#vis #column_all: &#lt [::core::mem::MaybeUninit<#ty_all>],)*
+        }
+
+        #slices_copy
+    });
+
+    // For the same reason, the chunks are also shared between both uses.
+    let chunk_copy = impl_copy(&chunk, &impl_generics_lt_n, &ty_generics_lt_n);
+    out.append_all(quote! {
+        #[automatically_derived]
+        #vis struct #chunk #impl_generics_lt_n #where_clause {
+            #vis tag: &#lt [#tag; #n],
+            #(#vis #column_all: &#lt [::core::mem::MaybeUninit<#ty_all>; #n],)*
+        }
+
+        #chunk_copy
+    });
+
+    let first_variant = variant_all[0];
+    let array_arms = variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let pattern = variant.pattern(quote! { #ident });
+        let columns = variant.columns();
+        let bindings = variant.bindings();
+        quote! {
+            #pattern => {
+                out.tag[i] = #tag::#variant_ident;
+                #(
+                let src = ::core::ptr::from_ref(#bindings);
+                unsafe {
+                    out.#columns[i] = ::core::mem::MaybeUninit::new(src.read());
+                }
+                )*
+            }
+        }
+    });
+    let array_as_chunk = array_as_chunk(&vis, &item, &raw, &ty_generics, &n);
+    out.append_all(quote! {
+        #[automatically_derived]
+        #vis struct #array #impl_generics_n #where_clause {
+            tag: [#tag; #n],
+            #(#column_all: [::core::mem::MaybeUninit<#ty_all>; #n],)*
+        }
+
+        impl #impl_generics_n #array #ty_generics_n #where_clause {
+            #vis const fn from_array(array: [#item; #n]) -> Self {
+                let array = ::core::mem::ManuallyDrop::new(array);
+                let array = ::core::ptr::from_ref::<::core::mem::ManuallyDrop<[#item; #n]>>(&array);
+                let array = array.cast::<[#item; #n]>();
+                let array = unsafe { &*array };
+
+                let mut out = Self {
+                    tag: [#tag::#first_variant; #n],
+                    #(
+                    // https://doc.rust-lang.org/std/mem/union.MaybeUninit.html#initializing-an-array-element-by-element
+                    #column_all: unsafe { ::core::mem::MaybeUninit::uninit().assume_init() },
+                    )*
+                };
+
+                let mut i = 0;
+                while i < #n {
+                    match &array[i] {
+                        #(#array_arms)*
+                    }
+
+                    i += 1;
+                }
+
+                out
+            }
+
+            #array_as_chunk
+        }
+
+        impl #impl_generics_n ::soa_rs::SoaArray for #array #ty_generics_n #where_clause {
+            type Item = #item;
+
+            fn as_slice(&self) -> ::soa_rs::SliceRef<'_, Self::Item> {
+                let raw = #raw {
+                    tag: {
+                        let ptr = self.tag.as_slice().as_ptr().cast_mut();
+                        unsafe { ::core::ptr::NonNull::new_unchecked(ptr) }
+                    },
+                    #(
+                        #column_all: {
+                            let ptr = self.#column_all.as_slice().as_ptr().cast_mut().cast();
+                            unsafe { ::core::ptr::NonNull::new_unchecked(ptr) }
+                        },
+                    )*
+                };
+                let slice = ::soa_rs::Slice::with_raw(raw);
+                unsafe { ::soa_rs::SliceRef::from_slice(slice, #n) }
+            }
+
+            fn as_mut_slice(&mut self) -> ::soa_rs::SliceMut<'_, Self::Item> {
+                let raw = #raw {
+                    tag: {
+                        let ptr = self.tag.as_mut_slice().as_mut_ptr();
+                        unsafe { ::core::ptr::NonNull::new_unchecked(ptr) }
+                    },
+                    #(
+                        #column_all: {
+                            let ptr = self.#column_all.as_mut_slice().as_mut_ptr().cast();
+                            unsafe { ::core::ptr::NonNull::new_unchecked(ptr) }
+                        },
+                    )*
+                };
+                let slice = ::soa_rs::Slice::with_raw(raw);
+                unsafe { ::soa_rs::SliceMut::from_slice(slice, #n) }
+            }
+        }
+
+        impl #impl_generics_n ::core::ops::Drop for #array #ty_generics_n #where_clause {
+            fn drop(&mut self) {
+                if ::core::mem::needs_drop::<#item>() {
+                    let raw = ::soa_rs::SoaArray::as_mut_slice(self).raw();
+                    for i in 0..#n {
+                        // SAFETY: Each element is read out and dropped once
+                        unsafe {
+                            let element = ::soa_rs::SoaRaw::offset(raw, i);
+                            let _: #item = ::soa_rs::SoaRaw::get(element);
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    let set_arms = variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let pattern = variant.pattern(quote! { #ident });
+        let columns = variant.columns();
+        let bindings = variant.bindings();
+        quote! {
+            #pattern => {
+                self.tag.as_ptr().write(#tag::#variant_ident);
+                #(self.#columns.as_ptr().write(#bindings);)*
+            }
+        }
+    });
+    let read_arms = |path: TokenStream, read: &dyn Fn(&Ident) -> TokenStream| {
+        variants
+            .iter()
+            .map(|variant| {
+                let variant_ident = &variant.ident;
+                let construct = variant.construct(
+                    path.clone(),
+                    &variant
+                        .columns()
+                        .iter()
+                        .map(|column| read(column))
+                        .collect::<Vec<_>>(),
+                );
+                quote! { #tag::#variant_ident => #construct, }
+            })
+            .collect::<Vec<_>>()
+    };
+    let get_arms = read_arms(quote! { #ident }, &|column| {
+        quote! { self.#column.as_ptr().read() }
+    });
+    let get_ref_arms = read_arms(quote! { #item_ref }, &|column| {
+        quote! { self.#column.as_ptr().as_ref().unwrap_unchecked() }
+    });
+    let get_mut_arms = read_arms(quote! { #item_ref_mut }, &|column| {
+        quote! { self.#column.as_ptr().as_mut().unwrap_unchecked() }
+    });
+
+    let clone_arms = variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let columns = variant.columns();
+        quote! {
+            #tag::#variant_ident => {
+                #(
+                dst.#columns
+                    .as_ptr()
+                    .write(::core::clone::Clone::clone(self.#columns.as_ref()));
+                )*
+            }
+        }
+    });
+    // The bounds are placed under a binder so that they aren't rejected as
+    // trivially false when a field type doesn't implement Clone.
+    let mut clone_where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| parse_quote! { where });
+    for ty in ty_all.iter() {
+        clone_where_clause
+            .predicates
+            .push(parse_quote! { for<#lt> #ty: ::core::clone::Clone });
+    }
+
+    let raw_copy = impl_copy(&raw, &impl_generics, &ty_generics);
+    let raw_inherent = raw_inherent(&columns);
+    let raw_storage = raw_storage(&columns, &n);
+
+    let mut partial_eq_where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| parse_quote! { where });
+    let lt_other = fresh_lifetime(&generics_lt);
+    let generics_other = with_lifetime(&generics, &lt_other);
+    let (_, ty_generics_other, _) = generics_other.split_for_impl();
+    partial_eq_where_clause.predicates.push(parse_quote! {
+        for<#lt_other> #item_ref #ty_generics_other: ::core::cmp::PartialEq
+    });
+
+    let as_soa_ref_arms = variants.iter().map(|variant| {
+        let pattern = variant.pattern(quote! { #ident });
+        let construct = variant.construct(
+            quote! { #item_ref },
+            &variant
+                .bindings()
+                .iter()
+                .map(|binding| quote! { #binding })
+                .collect::<Vec<_>>(),
+        );
+        quote! { #pattern => #construct, }
+    });
+
+    out.append_all(quote! {
+        #[automatically_derived]
+        #vis struct #raw #impl_generics #where_clause {
+            tag: ::core::ptr::NonNull<#tag>,
+            #(#column_all: ::core::ptr::NonNull<#ty_all>,)*
+        }
+
+        #raw_copy
+
+        #[automatically_derived]
+        unsafe impl #impl_generics ::soa_rs::Soars for #item #where_clause {
+            type Raw = #raw #ty_generics;
+            type Deref = #deref #ty_generics;
+            type Ref<#lt> = #item_ref #ty_generics_lt where Self: #lt;
+            type RefMut<#lt> = #item_ref_mut #ty_generics_lt where Self: #lt;
+            type Array<const #n: usize> = #array #ty_generics_n;
+            type Slices<#lt> = #slices #ty_generics_lt where Self: #lt;
+            type SlicesMut<#lt> = #slices #ty_generics_lt where Self: #lt;
+            type Chunk<#lt, const #n: usize> = #chunk #ty_generics_lt_n where Self: #lt;
+            type ChunkMut<#lt, const #n: usize> = #chunk #ty_generics_lt_n where Self: #lt;
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #raw #ty_generics #where_clause {
+            #raw_inherent
+        }
+
+        #[automatically_derived]
+        unsafe impl #impl_generics ::soa_rs::SoaRaw for #raw #ty_generics #where_clause {
+            type Item = #item;
+
+            #raw_storage
+
+            #[inline]
+            unsafe fn set(self, element: #item) {
+                match element {
+                    #(#set_arms)*
+                }
+            }
+
+            #[inline]
+            unsafe fn get(self) -> #item {
+                match self.tag.as_ptr().read() {
+                    #(#get_arms)*
+                }
+            }
+
+            #[inline]
+            unsafe fn get_ref<#lt>(self) -> #item_ref #ty_generics_lt {
+                match self.tag.as_ptr().read() {
+                    #(#get_ref_arms)*
+                }
+            }
+
+            #[inline]
+            unsafe fn get_mut<#lt>(self) -> #item_ref_mut #ty_generics_lt {
+                match self.tag.as_ptr().read() {
+                    #(#get_mut_arms)*
+                }
+            }
+
+            #[inline]
+            unsafe fn slices<#lt>(self, len: usize) -> #slices #ty_generics_lt {
+                #slices {
+                    tag: unsafe { ::core::slice::from_raw_parts(self.tag.as_ptr(), len) },
+                    #(
+                        #column_all: unsafe {
+                            ::core::slice::from_raw_parts(self.#column_all.as_ptr().cast(), len)
+                        },
+                    )*
+                }
+            }
+
+            #[inline]
+            unsafe fn slices_mut<#lt>(self, len: usize) -> #slices #ty_generics_lt {
+                self.slices(len)
+            }
+
+            #[inline]
+            unsafe fn chunk<#lt, const #n: usize>(self) -> #chunk #ty_generics_lt_n {
+                #chunk {
+                    tag: unsafe { &*self.tag.as_ptr().cast::<[#tag; #n]>() },
+                    #(
+                        #column_all: unsafe {
+                            &*self.#column_all.as_ptr().cast::<[::core::mem::MaybeUninit<#ty_all>; #n]>()
+                        },
+                    )*
+                }
+            }
+
+            #[inline]
+            unsafe fn chunk_mut<#lt, const #n: usize>(self) -> #chunk #ty_generics_lt_n {
+                self.chunk::<#n>()
+            }
+        }
+
+        #[automatically_derived]
+        unsafe impl #impl_generics ::soa_rs::SoaRawClone for #raw #ty_generics
+        #clone_where_clause
+        {
+            #[inline]
+            unsafe fn clone_to(self, dst: Self) {
+                let tag = self.tag.as_ptr().read();
+                dst.tag.as_ptr().write(tag);
+                match tag {
+                    #(#clone_arms)*
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::soa_rs::AsSoaRef for #item #where_clause {
+            type Item = #item;
+
+            fn as_soa_ref(&self) -> <Self::Item as ::soa_rs::Soars>::Ref<'_> {
+                match self {
+                    #(#as_soa_ref_arms)*
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics_lt ::core::cmp::PartialEq<#item> for #item_ref #ty_generics_lt
+        #partial_eq_where_clause
+        {
+            fn eq(&self, other: &#item) -> bool {
+                self == &<#item as ::soa_rs::AsSoaRef>::as_soa_ref(other)
+            }
+        }
+    });
+
+    if with_serde {
+        out.append_all(serde::enum_impls(
+            &ident,
+            &generics,
+            &variants,
+            &phantom_arm,
+        ));
+    }
+
+    Ok(out)
+}
+
+pub struct EnumVariant {
+    pub ident: Ident,
+    snake: String,
+    pub kind: VariantKind,
+    pub fields: Vec<VariantField>,
+}
+
+pub struct VariantField {
+    /// The field name within the variant
+    pub ident: FieldIdent,
+    /// The field name within the raw type, prefixed with the variant name
+    column: Ident,
+    pub ty: Type,
+    align: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VariantKind {
+    Named,
+    Unnamed,
+    Unit,
+}
+
+impl TryFrom<Variant> for EnumVariant {
+    type Error = syn::Error;
+
+    fn try_from(variant: Variant) -> Result<Self, Self::Error> {
+        let Variant { ident, fields, .. } = variant;
+        let snake = snake_case(&ident.unraw().to_string());
+        let kind = match fields {
+            Fields::Named(_) => VariantKind::Named,
+            Fields::Unnamed(_) => VariantKind::Unnamed,
+            Fields::Unit => VariantKind::Unit,
+        };
+        let fields = fields
+            .into_iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let column = match &field.ident {
+                    Some(ident) => format_ident!("{snake}_{}", ident.unraw()),
+                    None => format_ident!("{snake}_{i}"),
+                };
+                if parse_nested(&field.attrs)? {
+                    return Err(syn::Error::new_spanned(
+                        &field.ty,
+                        "soa(nested) is not supported for enum variant fields",
+                    ));
+                }
+                Ok(VariantField {
+                    align: parse_align(&field.attrs)?,
+                    ident: (i, field.ident).into(),
+                    column,
+                    ty: field.ty,
+                })
+            })
+            .collect::<Result<_, syn::Error>>()?;
+        Ok(Self {
+            ident,
+            snake,
+            kind,
+            fields,
+        })
+    }
+}
+
+impl EnumVariant {
+    fn columns(&self) -> Vec<&Ident> {
+        self.fields.iter().map(|field| &field.column).collect()
+    }
+
+    fn tys(&self) -> Vec<&Type> {
+        self.fields.iter().map(|field| &field.ty).collect()
+    }
+
+    pub fn bindings(&self) -> Vec<Ident> {
+        (0..self.fields.len())
+            .map(|i| format_ident!("f{i}"))
+            .collect()
+    }
+
+    /// A pattern that binds each field of the variant to the names from
+    /// [`EnumVariant::bindings`].
+    pub fn pattern(&self, path: TokenStream) -> TokenStream {
+        self.construct(
+            path,
+            &self
+                .bindings()
+                .iter()
+                .map(|binding| quote! { #binding })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Constructs the variant under the given enum path. Brace syntax works
+    /// for every variant kind, including tuple and unit variants.
+    fn construct(&self, path: TokenStream, values: &[TokenStream]) -> TokenStream {
+        let variant_ident = &self.ident;
+        let fields = self.fields.iter().map(|field| &field.ident);
+        quote! { #path::#variant_ident { #(#fields: #values),* } }
+    }
+
+    /// Combines per-field tokens into the single value or tuple used for
+    /// per-variant accessors, for both of the given sets of tokens.
+    fn payload(&self, a: &[TokenStream], b: &[TokenStream]) -> (TokenStream, TokenStream) {
+        let combine = |tokens: &[TokenStream]| match tokens {
+            [single] => single.clone(),
+            many => quote! { (#(#many),*) },
+        };
+        (combine(a), combine(b))
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let chars: Vec<_> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let boundary = match i.checked_sub(1).map(|j| chars[j]) {
+                Some(prev) => {
+                    prev.is_lowercase()
+                        || prev.is_ascii_digit()
+                        || (prev.is_uppercase()
+                            && chars.get(i + 1).is_some_and(|next| next.is_lowercase()))
+                }
+                None => false,
+            };
+            if boundary {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -1,14 +1,23 @@
 use crate::{
+    generics::{fresh_ident, fresh_lifetime, with_const, with_lifetime},
+    raw::{
+        array_as_chunk, nested_raw, parse_align, parse_nested, raw_inherent, raw_storage, Column,
+    },
+    serde,
     zst::{zst_struct, ZstKind},
     SoaDerive,
 };
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens, TokenStreamExt};
-use syn::{punctuated::Punctuated, token::Comma, Field, Ident, Index, LitInt, Visibility};
+use syn::{
+    ext::IdentExt, parse_quote, punctuated::Punctuated, token::Comma, Field, Generics, Ident,
+    Index, PathArguments, Type, TypePath, Visibility, WhereClause,
+};
 
 pub fn fields_struct(
     ident: Ident,
     vis: Visibility,
+    generics: Generics,
     fields: Punctuated<Field, Comma>,
     kind: FieldKind,
     soa_derive: SoaDerive,
@@ -31,205 +40,443 @@ pub fn fields_struct(
         })
         .unzip();
 
-    let align_all: Result<Vec<_>, syn::Error> = attrs_all
-        .into_iter()
-        .map(|attrs| {
-            for attr in attrs {
-                if attr.path().is_ident("align") {
-                    let align_literal: LitInt = attr.parse_args()?;
-                    let align: usize = align_literal.base10_parse()?;
-                    if !align.is_power_of_two() {
-                        return Err(syn::Error::new_spanned(
-                            align_literal,
-                            "align should be a power of two",
-                        ));
-                    }
-                    return Ok(Some(align));
-                }
-            }
-            Ok(None)
-        })
-        .collect();
+    let align_all = attrs_all
+        .iter()
+        .map(|attrs| parse_align(attrs))
+        .collect::<Result<Vec<_>, _>>()?;
+    let nested_all = attrs_all
+        .iter()
+        .map(|attrs| parse_nested(attrs))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let align_all = align_all?;
+    if fields_len == 0 {
+        let zst_kind = match kind {
+            FieldKind::Named => ZstKind::Empty,
+            FieldKind::Unnamed => ZstKind::EmptyTuple,
+        };
+        return Ok(zst_struct(ident, vis, generics, zst_kind, soa_derive));
+    }
 
-    let ident_rev: Vec<_> = ident_all.iter().cloned().rev().collect();
+    let columns: Vec<_> = ident_all
+        .iter()
+        .zip(ty_all.iter())
+        .zip(align_all)
+        .zip(nested_all.iter())
+        .map(|(((ident, ty), align), &nested)| Column {
+            ident: ident.clone(),
+            ty: ty.clone(),
+            align,
+            nested,
+        })
+        .collect();
 
-    let (_vis_head, ident_head, ty_head) = match (
-        vis_all.first().cloned(),
-        ty_all.first().cloned(),
-        ident_all.first().cloned(),
-    ) {
-        (Some(vis), Some(ty), Some(ident)) => (vis, ident, ty),
-        _ => {
-            let zst_kind = match kind {
-                FieldKind::Named => ZstKind::Empty,
-                FieldKind::Unnamed => ZstKind::EmptyTuple,
-            };
-            return Ok(zst_struct(ident, vis, zst_kind));
-        }
+    // Tokens for each field that differ depending on whether the field is
+    // nested, given a function from the field to the tokens for each case.
+    let per_field = |f: &dyn Fn(&Visibility, &FieldIdent, &Type, bool) -> TokenStream| {
+        vis_all
+            .iter()
+            .zip(ident_all.iter())
+            .zip(ty_all.iter())
+            .zip(nested_all.iter())
+            .map(|(((vis, ident), ty), &nested)| f(vis, ident, ty, nested))
+            .collect::<Vec<_>>()
     };
 
-    let _vis_tail: Vec<_> = vis_all.iter().skip(1).cloned().collect();
-    let ty_tail: Vec<_> = ty_all.iter().skip(1).cloned().collect();
-    let ident_tail: Vec<_> = ident_all.iter().skip(1).cloned().collect();
-
     let deref = format_ident!("{ident}Deref");
     let item_ref = format_ident!("{ident}Ref");
     let item_ref_mut = format_ident!("{ident}RefMut");
     let slices = format_ident!("{ident}Slices");
     let slices_mut = format_ident!("{ident}SlicesMut");
     let array = format_ident!("{ident}Array");
+    let chunk = format_ident!("{ident}Chunk");
+    let chunk_mut = format_ident!("{ident}ChunkMut");
     let raw = format_ident!("{ident}SoaRaw");
 
+    // Generic parameters for the generated types. The lifetime and the array
+    // length are added to the derived type's own parameters, so they are
+    // renamed as needed to avoid collisions.
+    let lt = fresh_lifetime(&generics);
+    let n = fresh_ident(&generics, "N");
+    let k = fresh_ident(&generics, "K");
+    let generics_lt = with_lifetime(&generics, &lt);
+    let generics_n = with_const(&generics, &n);
+    let generics_k = with_const(&generics, &k);
+    let generics_lt_n = with_const(&generics_lt, &n);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics_lt, ty_generics_lt, _) = generics_lt.split_for_impl();
+    let (impl_generics_n, ty_generics_n, _) = generics_n.split_for_impl();
+    let (impl_generics_k, _, _) = generics_k.split_for_impl();
+    let (impl_generics_lt_n, ty_generics_lt_n, _) = generics_lt_n.split_for_impl();
+    let item = quote! { #ident #ty_generics };
+
+    // The types with a lifetime borrow from nested fields through their
+    // associated types, which requires the nested types to outlive it.
+    let mut where_clause_lt = where_clause.cloned();
+    for (ty, _) in ty_all
+        .iter()
+        .zip(nested_all.iter())
+        .filter(|(_, &nested)| nested)
+    {
+        where_clause_lt
+            .get_or_insert_with(|| parse_quote! { where })
+            .predicates
+            .push(parse_quote! { #ty: #lt });
+    }
+
     let mut out = TokenStream::new();
 
-    let (slice_getters_ref, slice_getters_mut): (Vec<_>, Vec<_>) = ident_all
-        .iter()
-        .map(|ident| match ident {
+    let slice_getters = per_field(&|vis, ident, ty, nested| {
+        let (getter_ref, getter_mut) = match ident {
             FieldIdent::Named(named) => (named.clone(), format_ident!("{named}_mut")),
             FieldIdent::Unnamed(unnamed) => {
                 (format_ident!("f{unnamed}"), format_ident!("f{unnamed}_mut"))
             }
-        })
-        .unzip();
+        };
+        if nested {
+            quote! {
+                #vis fn #getter_ref(&self) -> ::soa_rs::SliceRef<'_, #ty> {
+                    let slice = ::soa_rs::Slice::with_raw(self.0.raw().#ident);
+                    let len = self.0.len();
+                    unsafe { ::soa_rs::SliceRef::from_slice(slice, len) }
+                }
+
+                #vis fn #getter_mut(&mut self) -> ::soa_rs::SliceMut<'_, #ty> {
+                    let slice = ::soa_rs::Slice::with_raw(self.0.raw().#ident);
+                    let len = self.0.len();
+                    unsafe { ::soa_rs::SliceMut::from_slice(slice, len) }
+                }
+            }
+        } else {
+            quote! {
+                #vis fn #getter_ref(&self) -> &[#ty] {
+                    let ptr = self.0.raw().#ident.as_ptr();
+                    let len = self.0.len();
+                    unsafe {
+                        ::core::slice::from_raw_parts(ptr, len)
+                    }
+                }
+
+                #vis fn #getter_mut(&mut self) -> &mut [#ty] {
+                    let ptr = self.0.raw().#ident.as_ptr();
+                    let len = self.0.len();
+                    unsafe {
+                        ::core::slice::from_raw_parts_mut(ptr, len)
+                    }
+                }
+            }
+        }
+    });
 
     out.append_all(quote! {
         #[automatically_derived]
         #[repr(transparent)]
-        #vis struct #deref(::soa_rs::Slice<#ident>);
+        #vis struct #deref #impl_generics (::soa_rs::Slice<#item>) #where_clause;
 
-        impl ::soa_rs::SoaDeref for #deref {
-            type Item = #ident;
+        impl #impl_generics ::soa_rs::SoaDeref for #deref #ty_generics #where_clause {
+            type Item = #item;
 
             fn from_slice(slice: &::soa_rs::Slice<Self::Item>) -> &Self {
-                unsafe { ::std::mem::transmute(slice) }
+                let slice = ::core::ptr::from_ref(slice) as *const Self;
+                unsafe { &*slice }
             }
 
             fn from_slice_mut(slice: &mut ::soa_rs::Slice<Self::Item>) -> &mut Self {
-                unsafe { ::std::mem::transmute(slice) }
+                let slice = ::core::ptr::from_mut(slice) as *mut Self;
+                unsafe { &mut *slice }
             }
         }
 
-        impl #deref {
-            #(
-            #vis_all fn #slice_getters_ref(&self) -> &[#ty_all] {
-                let ptr = self.0.raw().#ident_all.as_ptr();
-                let len = self.0.len();
-                unsafe {
-                    ::std::slice::from_raw_parts(ptr, len)
-                }
-            }
-
-            #vis_all fn #slice_getters_mut(&mut self) -> &mut [#ty_all] {
-                let ptr = self.0.raw().#ident_all.as_ptr();
-                let len = self.0.len();
-                unsafe {
-                    ::std::slice::from_raw_parts_mut(ptr, len)
-                }
-            }
-            )*
+        impl #impl_generics #deref #ty_generics #where_clause {
+            #(#slice_getters)*
         }
     });
 
-    let define = |type_mapper: &dyn Fn(&syn::Type) -> TokenStream| {
-        let ty_mapped = ty_all.iter().map(type_mapper);
+    let define = |where_clause: Option<&WhereClause>,
+                  type_mapper: &dyn Fn(&Type, bool) -> TokenStream| {
+        let ty_mapped = ty_all
+            .iter()
+            .zip(nested_all.iter())
+            .map(|(ty, &nested)| type_mapper(ty, nested));
         match kind {
             FieldKind::Named => quote! {
-                { #(#[automatically_derived] #vis_all #ident_all: #ty_mapped),* }
+                #where_clause { #(#[automatically_derived] #vis_all #ident_all: #ty_mapped),* }
             },
             FieldKind::Unnamed => quote! {
-                ( #(#[automatically_derived] #vis_all #ty_mapped),* );
+                ( #(#[automatically_derived] #vis_all #ty_mapped),* ) #where_clause;
             },
         }
     };
 
-    let mut extra_plus_copy = soa_derive.clone();
-    extra_plus_copy.insert("Copy");
-    extra_plus_copy.insert("Clone");
-    let extra_plus_copy = extra_plus_copy.into_derive();
+    // Copy and Clone are implemented by hand for the types that require them
+    // so that they don't pick up bounds on the generic parameters.
+    let mut extra_minus_copy = soa_derive.clone();
+    extra_minus_copy.remove("Copy");
+    extra_minus_copy.remove("Clone");
+    let extra_minus_copy = extra_minus_copy.into_derive();
+    let with_serde = soa_derive.serde;
     let extra = soa_derive.into_derive();
 
-    let item_ref_def = define(&|ty| quote! { &'a #ty });
+    let impl_copy = |ty: &Ident, impl_generics, ty_generics, where_clause: Option<&WhereClause>| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::clone::Clone for #ty #ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics ::core::marker::Copy for #ty #ty_generics #where_clause {}
+        }
+    };
+
+    let item_ref_def = define(where_clause_lt.as_ref(), &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::Ref<#lt> }
+        } else {
+            quote! { &#lt #ty }
+        }
+    });
+    let item_ref_copy = impl_copy(
+        &item_ref,
+        &impl_generics_lt,
+        &ty_generics_lt,
+        where_clause_lt.as_ref(),
+    );
+    // Nested references are invariant over the lifetime, so the fields are
+    // reborrowed individually rather than by copying the whole reference.
+    let ref_from_ref = per_field(&|_, ident, _, nested| {
+        if nested {
+            quote! { ::soa_rs::AsSoaRef::as_soa_ref(&self.#ident) }
+        } else {
+            quote! { self.#ident }
+        }
+    });
     out.append_all(quote! {
-        #extra_plus_copy
+        #extra_minus_copy
         #[automatically_derived]
-        #vis struct #item_ref<'a> #item_ref_def
+        #vis struct #item_ref #impl_generics_lt #item_ref_def
+
+        #item_ref_copy
 
-        impl<'a> ::soa_rs::AsSoaRef for #item_ref<'a> {
-            type Item = #ident;
+        impl #impl_generics_lt ::soa_rs::AsSoaRef for #item_ref #ty_generics_lt #where_clause_lt {
+            type Item = #item;
 
-            fn as_soa_ref(&self) -> <Self::Item as Soars>::Ref<'_> {
-                *self
+            fn as_soa_ref(&self) -> <Self::Item as ::soa_rs::Soars>::Ref<'_> {
+                #item_ref {
+                    #(
+                        #ident_all: #ref_from_ref,
+                    )*
+                }
             }
         }
     });
 
-    let item_ref_mut_def = define(&|ty| quote! { &'a mut #ty });
+    let item_ref_mut_def = define(where_clause_lt.as_ref(), &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::RefMut<#lt> }
+        } else {
+            quote! { &#lt mut #ty }
+        }
+    });
     out.append_all(quote! {
         #extra
         #[automatically_derived]
-        #vis struct #item_ref_mut<'a> #item_ref_mut_def
+        #vis struct #item_ref_mut #impl_generics_lt #item_ref_mut_def
 
-        impl<'a> ::soa_rs::AsSoaRef for #item_ref_mut<'a> {
-            type Item = #ident;
+        impl #impl_generics_lt ::soa_rs::AsSoaRef for #item_ref_mut #ty_generics_lt #where_clause_lt {
+            type Item = #item;
 
-            fn as_soa_ref(&self) -> <Self::Item as Soars>::Ref<'_> {
+            fn as_soa_ref(&self) -> <Self::Item as ::soa_rs::Soars>::Ref<'_> {
                 #item_ref {
                     #(
-                        #ident_all: self.#ident_all,
+                        #ident_all: #ref_from_ref,
                     )*
                 }
             }
         }
     });
 
-    let slices_def = define(&|ty| quote! { &'a [#ty] });
+    let slices_def = define(where_clause_lt.as_ref(), &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::Slices<#lt> }
+        } else {
+            quote! { &#lt [#ty] }
+        }
+    });
+    // Nested slices and chunks are only Copy if the nested type's are
+    let impl_nested_copy = |ty_ident: &Ident, impl_generics, ty_generics, assoc: TokenStream| {
+        let mut where_clause = where_clause_lt
+            .clone()
+            .unwrap_or_else(|| parse_quote! { where });
+        for (ty, _) in ty_all
+            .iter()
+            .zip(nested_all.iter())
+            .filter(|(_, &nested)| nested)
+        {
+            where_clause.predicates.push(parse_quote! {
+                <#ty as ::soa_rs::Soars>::#assoc: ::core::marker::Copy
+            });
+        }
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::clone::Clone for #ty_ident #ty_generics
+            #where_clause
+            {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics ::core::marker::Copy for #ty_ident #ty_generics
+            #where_clause
+            {}
+        }
+    };
+    let slices_copy = impl_nested_copy(
+        &slices,
+        &impl_generics_lt,
+        &ty_generics_lt,
+        quote! { Slices<#lt> },
+    );
+    out.append_all(quote! {
+        #extra_minus_copy
+        #[automatically_derived]
+        #vis struct #slices #impl_generics_lt #slices_def
+
+        #slices_copy
+    });
+
+    let slices_mut_def = define(where_clause_lt.as_ref(), &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::SlicesMut<#lt> }
+        } else {
+            quote! { &#lt mut [#ty] }
+        }
+    });
+    out.append_all(quote! {
+        #extra
+        #[automatically_derived]
+        #vis struct #slices_mut #impl_generics_lt #slices_mut_def
+    });
+
+    let chunk_def = define(where_clause_lt.as_ref(), &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::Chunk<#lt, #n> }
+        } else {
+            quote! { &#lt [#ty; #n] }
+        }
+    });
+    let chunk_copy = impl_nested_copy(
+        &chunk,
+        &impl_generics_lt_n,
+        &ty_generics_lt_n,
+        quote! { Chunk<#lt, #n> },
+    );
     out.append_all(quote! {
-        #extra_plus_copy
+        #extra_minus_copy
         #[automatically_derived]
-        #vis struct #slices<'a> #slices_def
+        #vis struct #chunk #impl_generics_lt_n #chunk_def
+
+        #chunk_copy
     });
 
-    let slices_mut_def = define(&|ty| quote! { &'a mut [#ty] });
+    let chunk_mut_def = define(where_clause_lt.as_ref(), &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::ChunkMut<#lt, #n> }
+        } else {
+            quote! { &#lt mut [#ty; #n] }
+        }
+    });
     out.append_all(quote! {
         #extra
         #[automatically_derived]
-        #vis struct #slices_mut<'a> #slices_mut_def
+        #vis struct #chunk_mut #impl_generics_lt_n #chunk_mut_def
     });
 
-    let array_def = define(&|ty| quote! { [#ty; N] });
-    let uninit_def = define(&|ty| quote! { [::std::mem::MaybeUninit<#ty>; K] });
+    let array_def = define(where_clause, &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::Array<#n> }
+        } else {
+            quote! { [#ty; #n] }
+        }
+    });
+    let uninit_def = define(
+        where_clause,
+        &|ty, _| quote! { [::core::mem::MaybeUninit<#ty>; #k] },
+    );
+    let array_from_uninit = ident_all
+        .iter()
+        .zip(ty_all.iter())
+        .zip(nested_all.iter())
+        .map(|((ident, ty), &nested)| {
+            let array = quote! {
+                // TODO: Prefer when stabilized:
+                // https://doc.rust-lang.org/std/primitive.array.html#method.transpose
+                unsafe {
+                    ::core::mem::transmute_copy(&::core::mem::ManuallyDrop::new(uninit.#ident))
+                }
+            };
+            if nested {
+                let nested_array = nested_array(ty, &n)?;
+                Ok(quote! { #nested_array::from_array(#array) })
+            } else {
+                Ok(array)
+            }
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+    let array_raw_ref = per_field(&|_, ident, _, nested| {
+        if nested {
+            quote! { ::soa_rs::SoaArray::as_slice(&self.#ident).raw() }
+        } else {
+            quote! {{
+                let ptr = self.#ident.as_slice().as_ptr().cast_mut();
+                unsafe { ::core::ptr::NonNull::new_unchecked(ptr) }
+            }}
+        }
+    });
+    let array_raw_mut = per_field(&|_, ident, _, nested| {
+        if nested {
+            quote! { ::soa_rs::SoaArray::as_mut_slice(&mut self.#ident).raw() }
+        } else {
+            quote! {{
+                let ptr = self.#ident.as_mut_slice().as_mut_ptr();
+                unsafe { ::core::ptr::NonNull::new_unchecked(ptr) }
+            }}
+        }
+    });
+    let array_as_chunk = array_as_chunk(&vis, &item, &raw, &ty_generics, &n);
     out.append_all(quote! {
         #extra
         #[automatically_derived]
-        #vis struct #array<const N: usize> #array_def
+        #vis struct #array #impl_generics_n #array_def
 
-        impl<const N: usize> #array<N> {
-            #vis const fn from_array(array: [#ident; N]) -> Self {
-                let array = ::std::mem::ManuallyDrop::new(array);
-                let array = ::std::ptr::from_ref::<::std::mem::ManuallyDrop<[#ident; N]>>(&array);
-                let array = array.cast::<[#ident; N]>();
+        impl #impl_generics_n #array #ty_generics_n #where_clause {
+            #vis const fn from_array(array: [#item; #n]) -> Self {
+                let array = ::core::mem::ManuallyDrop::new(array);
+                let array = ::core::ptr::from_ref::<::core::mem::ManuallyDrop<[#item; #n]>>(&array);
+                let array = array.cast::<[#item; #n]>();
                 let array = unsafe { &*array };
 
-                struct Uninit<const K: usize> #uninit_def;
+                struct Uninit #impl_generics_k #uninit_def
 
-                let mut uninit: Uninit<N> = Uninit {
+                let mut uninit: Uninit #ty_generics_n = Uninit {
                     #(
                     // https://doc.rust-lang.org/std/mem/union.MaybeUninit.html#initializing-an-array-element-by-element
                     //
                     // TODO: Prefer when stablized:
                     // https://doc.rust-lang.org/std/mem/union.MaybeUninit.html#method.uninit_array
-                    #ident_all: unsafe { ::std::mem::MaybeUninit::uninit().assume_init() },
+                    #ident_all: unsafe { ::core::mem::MaybeUninit::uninit().assume_init() },
                     )*
                 };
 
                 let mut i = 0;
-                while i < N {
+                while i < #n {
                     #(
-                    let src = ::std::ptr::from_ref(&array[i].#ident_all);
+                    let src = ::core::ptr::from_ref(&array[i].#ident_all);
                     unsafe {
-                        uninit.#ident_all[i] = ::std::mem::MaybeUninit::new(src.read());
+                        uninit.#ident_all[i] = ::core::mem::MaybeUninit::new(src.read());
                     }
                     )*
 
@@ -237,337 +484,306 @@ pub fn fields_struct(
                 }
 
                 Self {
-                    #(
-                    // TODO: Prefer when stabilized:
-                    // https://doc.rust-lang.org/std/primitive.array.html#method.transpose
-                    #ident_all: unsafe {
-                        ::std::mem::transmute_copy(&::std::mem::ManuallyDrop::new(uninit.#ident_all))
-                    },
-                    )*
+                    #(#ident_all: #array_from_uninit,)*
                 }
             }
+
+            #array_as_chunk
         }
 
-        impl<const N: usize> ::soa_rs::SoaArray for #array<N> {
-            type Item = #ident;
+        impl #impl_generics_n ::soa_rs::SoaArray for #array #ty_generics_n #where_clause {
+            type Item = #item;
 
             fn as_slice(&self) -> ::soa_rs::SliceRef<'_, Self::Item> {
                 let raw = #raw {
-                    #(
-                        #ident_all: {
-                            let ptr = self.#ident_all.as_slice().as_ptr().cast_mut();
-                            unsafe { ::std::ptr::NonNull::new_unchecked(ptr) }
-                        },
-                    )*
+                    #(#ident_all: #array_raw_ref,)*
                 };
                 let slice = ::soa_rs::Slice::with_raw(raw);
-                unsafe { ::soa_rs::SliceRef::from_slice(slice, N) }
+                unsafe { ::soa_rs::SliceRef::from_slice(slice, #n) }
             }
 
             fn as_mut_slice(&mut self) -> ::soa_rs::SliceMut<'_, Self::Item> {
                 let raw = #raw {
-                    #(
-                        #ident_all: {
-                            let ptr = self.#ident_all.as_mut_slice().as_mut_ptr();
-                            unsafe { ::std::ptr::NonNull::new_unchecked(ptr) }
-                        },
-                    )*
+                    #(#ident_all: #array_raw_mut,)*
                 };
                 let slice = ::soa_rs::Slice::with_raw(raw);
-                unsafe { ::soa_rs::SliceMut::from_slice(slice, N) }
+                unsafe { ::soa_rs::SliceMut::from_slice(slice, #n) }
             }
         }
     });
 
-    let indices = std::iter::repeat(()).enumerate().map(|(i, ())| i);
-    let offsets_len = fields_len - 1;
-    let raw_body = define(&|ty| quote! { ::std::ptr::NonNull<#ty> });
+    let raw_body = define(where_clause, &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::Raw }
+        } else {
+            quote! { ::core::ptr::NonNull<#ty> }
+        }
+    });
+    let raw_copy = impl_copy(&raw, &impl_generics, &ty_generics, where_clause);
+    let raw_inherent = raw_inherent(&columns);
+    let raw_storage = raw_storage(&columns, &n);
 
-    let layout_and_offsets_body = |checked: bool| {
-        let check = if checked {
+    let raw_set = per_field(&|_, ident, ty, nested| {
+        if nested {
+            nested_raw(ty, "set", quote! { self.#ident, element.#ident })
+        } else {
+            quote! { self.#ident.as_ptr().write(element.#ident) }
+        }
+    });
+    let raw_get = per_field(&|_, ident, ty, nested| {
+        if nested {
+            nested_raw(ty, "get", quote! { self.#ident })
+        } else {
+            quote! { self.#ident.as_ptr().read() }
+        }
+    });
+    let raw_get_ref = per_field(&|_, ident, ty, nested| {
+        if nested {
+            nested_raw(ty, "get_ref", quote! { self.#ident })
+        } else {
+            quote! { self.#ident.as_ptr().as_ref().unwrap_unchecked() }
+        }
+    });
+    let raw_get_mut = per_field(&|_, ident, ty, nested| {
+        if nested {
+            nested_raw(ty, "get_mut", quote! { self.#ident })
+        } else {
+            quote! { self.#ident.as_ptr().as_mut().unwrap_unchecked() }
+        }
+    });
+    let raw_slices = per_field(&|_, ident, ty, nested| {
+        if nested {
+            nested_raw(ty, "slices", quote! { self.#ident, len })
+        } else {
+            quote! { ::core::slice::from_raw_parts(self.#ident.as_ptr(), len) }
+        }
+    });
+    let raw_slices_mut = per_field(&|_, ident, ty, nested| {
+        if nested {
+            nested_raw(ty, "slices_mut", quote! { self.#ident, len })
+        } else {
+            quote! { ::core::slice::from_raw_parts_mut(self.#ident.as_ptr(), len) }
+        }
+    });
+    let raw_chunk = per_field(&|_, ident, ty, nested| {
+        if nested {
+            quote! { <<#ty as ::soa_rs::Soars>::Raw as ::soa_rs::SoaRaw>::chunk::<#n>(self.#ident) }
+        } else {
+            quote! { &*self.#ident.as_ptr().cast::<[#ty; #n]>() }
+        }
+    });
+    let raw_chunk_mut = per_field(&|_, ident, ty, nested| {
+        if nested {
+            quote! { <<#ty as ::soa_rs::Soars>::Raw as ::soa_rs::SoaRaw>::chunk_mut::<#n>(self.#ident) }
+        } else {
+            quote! { &mut *self.#ident.as_ptr().cast::<[#ty; #n]>() }
+        }
+    });
+    let raw_clone = per_field(&|_, ident, ty, nested| {
+        if nested {
             quote! {
-                ?
+                <<#ty as ::soa_rs::Soars>::Raw as ::soa_rs::SoaRawClone>::clone_to(
+                    self.#ident,
+                    dst.#ident,
+                )
             }
         } else {
             quote! {
-                .unwrap_unchecked()
+                dst.#ident.as_ptr().write(::core::clone::Clone::clone(self.#ident.as_ref()))
             }
-        };
-
-        let mut raise_align = align_all.iter().map(|align| {
-            align.as_ref().map(|align| {
-                quote! {
-                    let array = array.align_to(#align)#check;
-                }
-            })
+        }
+    });
+    // The bounds are placed under a binder so that they aren't rejected as
+    // trivially false when a field type doesn't implement Clone.
+    let mut clone_where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| parse_quote! { where });
+    for (ty, &nested) in ty_all.iter().zip(nested_all.iter()) {
+        clone_where_clause.predicates.push(if nested {
+            parse_quote! { for<#lt> <#ty as ::soa_rs::Soars>::Raw: ::soa_rs::SoaRawClone }
+        } else {
+            parse_quote! { for<#lt> #ty: ::core::clone::Clone }
         });
+    }
 
-        let raise_align_head = raise_align.next().flatten();
-        let raise_align_tail: Vec<_> = raise_align.collect();
-
-        let indices = indices.clone();
-        quote! {
-            let array = ::std::alloc::Layout::array::<#ty_head>(cap)#check;
-            #raise_align_head
-            let layout = array;
-            let mut offsets = [0usize; #offsets_len];
-            #(
-                let array = ::std::alloc::Layout::array::<#ty_tail>(cap)#check;
-                #raise_align_tail
-                let (layout, offset) = layout.extend(array)#check;
-                offsets[#indices] = offset;
-            )*
+    let as_soa_ref = per_field(&|_, ident, _, nested| {
+        if nested {
+            quote! { ::soa_rs::AsSoaRef::as_soa_ref(&self.#ident) }
+        } else {
+            quote! { &self.#ident }
         }
-    };
+    });
 
-    let layout_and_offsets_checked_body = layout_and_offsets_body(true);
-    let layout_and_offsets_unchecked_body = layout_and_offsets_body(false);
+    let mut partial_eq_where_clause = where_clause_lt
+        .clone()
+        .unwrap_or_else(|| parse_quote! { where });
+    let lt_other = fresh_lifetime(&generics_lt);
+    let generics_other = with_lifetime(&generics, &lt_other);
+    let (_, ty_generics_other, _) = generics_other.split_for_impl();
+    partial_eq_where_clause.predicates.push(parse_quote! {
+        for<#lt_other> #item_ref #ty_generics_other: ::core::cmp::PartialEq
+    });
 
     out.append_all(quote! {
         #[automatically_derived]
-        #[derive(Copy, Clone)]
-        #vis struct #raw #raw_body
+        #vis struct #raw #impl_generics #raw_body
+
+        #raw_copy
 
         #[automatically_derived]
-        unsafe impl ::soa_rs::Soars for #ident {
-            type Raw = #raw;
-            type Deref = #deref;
-            type Ref<'a> = #item_ref<'a> where Self: 'a;
-            type RefMut<'a> = #item_ref_mut<'a> where Self: 'a;
-            type Array<const N: usize> = #array<N>;
-            type Slices<'a> = #slices<'a> where Self: 'a;
-            type SlicesMut<'a> = #slices_mut<'a> where Self: 'a;
+        unsafe impl #impl_generics ::soa_rs::Soars for #item #where_clause {
+            type Raw = #raw #ty_generics;
+            type Deref = #deref #ty_generics;
+            type Ref<#lt> = #item_ref #ty_generics_lt where Self: #lt;
+            type RefMut<#lt> = #item_ref_mut #ty_generics_lt where Self: #lt;
+            type Array<const #n: usize> = #array #ty_generics_n;
+            type Slices<#lt> = #slices #ty_generics_lt where Self: #lt;
+            type SlicesMut<#lt> = #slices_mut #ty_generics_lt where Self: #lt;
+            type Chunk<#lt, const #n: usize> = #chunk #ty_generics_lt_n where Self: #lt;
+            type ChunkMut<#lt, const #n: usize> = #chunk_mut #ty_generics_lt_n where Self: #lt;
         }
 
         #[automatically_derived]
-        impl #raw {
-            #[inline]
-            fn layout_and_offsets(cap: usize)
-                -> Result<(::std::alloc::Layout, [usize; #offsets_len]), ::std::alloc::LayoutError>
-            {
-                #layout_and_offsets_checked_body
-                Ok((layout, offsets))
-            }
-
-            #[inline]
-            unsafe fn layout_and_offsets_unchecked(cap: usize)
-                -> (::std::alloc::Layout, [usize; #offsets_len])
-            {
-                #layout_and_offsets_unchecked_body
-                (layout, offsets)
-            }
-
-            #[inline]
-            unsafe fn with_offsets(ptr: *mut u8, offsets: [usize; #offsets_len]) -> Self {
-                Self {
-                    #ident_head: ::std::ptr::NonNull::new_unchecked(ptr.cast()),
-                    #(
-                    #ident_tail: ::std::ptr::NonNull::new_unchecked(
-                        ptr.add(offsets[#indices]).cast(),
-                    )
-                    ),*
-                }
-            }
+        impl #impl_generics #raw #ty_generics #where_clause {
+            #raw_inherent
         }
 
         #[automatically_derived]
-        unsafe impl ::soa_rs::SoaRaw for #raw {
-            type Item = #ident;
+        unsafe impl #impl_generics ::soa_rs::SoaRaw for #raw #ty_generics #where_clause {
+            type Item = #item;
 
-            #[inline]
-            fn dangling() -> Self {
-                Self {
-                    #(#ident_all: ::std::ptr::NonNull::dangling(),)*
-                }
-            }
-
-            #[inline]
-            unsafe fn from_parts(ptr: *mut u8, capacity: usize) -> Self {
-                // SAFETY: This should have come from a previous allocation
-                let (_, offsets) = Self::layout_and_offsets_unchecked(capacity);
-                Self::with_offsets(ptr, offsets)
-            }
+            #raw_storage
 
             #[inline]
-            fn into_parts(self) -> *mut u8 {
-                self.#ident_head.as_ptr().cast()
+            unsafe fn set(self, element: #item) {
+                #(#raw_set;)*
             }
 
             #[inline]
-            unsafe fn alloc(capacity: usize) -> Self {
-                let (new_layout, new_offsets) = Self::layout_and_offsets(capacity)
-                    .expect("capacity overflow");
-
-                let ptr = ::std::alloc::alloc(new_layout);
-                if ptr.is_null() {
-                    ::std::alloc::handle_alloc_error(new_layout);
-                }
-
-                Self::with_offsets(ptr, new_offsets)
-            }
-
-            #[inline]
-            unsafe fn realloc_grow(
-                &mut self,
-                old_capacity: usize,
-                new_capacity: usize,
-                length: usize,
-            ) -> Self {
-                // SAFETY: We already constructed this layout for a previous allocation
-                let (old_layout, old_offsets) = Self::layout_and_offsets_unchecked(old_capacity);
-                let (new_layout, new_offsets) = Self::layout_and_offsets(new_capacity)
-                    .expect("capacity overflow");
-
-                // Grow allocation first
-                let ptr = self.#ident_head.as_ptr().cast();
-                let ptr = ::std::alloc::realloc(ptr, old_layout, new_layout.size());
-                if ptr.is_null() {
-                    ::std::alloc::handle_alloc_error(new_layout);
+            unsafe fn get(self) -> #item {
+                #ident {
+                    #(#ident_all: #raw_get,)*
                 }
-
-                // Pointer may have moved, can't reuse self
-                let old = Self::with_offsets(ptr, old_offsets);
-                let new = Self::with_offsets(ptr, new_offsets);
-
-                // Copy do destination in reverse order to avoid
-                // overwriting data
-                #(
-                    ::std::ptr::copy(old.#ident_rev.as_ptr(), new.#ident_rev.as_ptr(), length);
-                )*
-
-                new
             }
 
             #[inline]
-            unsafe fn realloc_shrink(
-                &mut self,
-                old_capacity: usize,
-                new_capacity: usize,
-                length: usize,
-            ) -> Self {
-                // SAFETY: We already constructed this layout for a previous allocation
-                let (old_layout, _) = Self::layout_and_offsets_unchecked(old_capacity);
-                let (new_layout, new_offsets) = Self::layout_and_offsets(new_capacity)
-                    .expect("capacity overflow");
-
-                // Move data before reallocating as some data
-                // may be past the end of the new allocation.
-                // Copy from front to back to avoid overwriting data.
-                let ptr = self.#ident_head.as_ptr().cast();
-                let dst = Self::with_offsets(ptr, new_offsets);
-                #(
-                    ::std::ptr::copy(self.#ident_all.as_ptr(), dst.#ident_all.as_ptr(), length);
-                )*
-
-                let ptr = ::std::alloc::realloc(ptr, old_layout, new_layout.size());
-                if ptr.is_null() {
-                    ::std::alloc::handle_alloc_error(new_layout);
+            unsafe fn get_ref<#lt>(self) -> #item_ref #ty_generics_lt {
+                #item_ref {
+                    #(#ident_all: #raw_get_ref,)*
                 }
-
-                // Pointer may have moved, can't reuse dst
-                Self::with_offsets(ptr, new_offsets)
-            }
-
-            #[inline]
-            unsafe fn dealloc(self, old_capacity: usize) {
-                // SAFETY: We already constructed this layout for a previous allocation
-                let (layout, _) = Self::layout_and_offsets_unchecked(old_capacity);
-                ::std::alloc::dealloc(self.#ident_head.as_ptr().cast(), layout);
-            }
-
-            #[inline]
-            unsafe fn copy_to(self, dst: Self, count: usize) {
-                #(
-                    ::std::ptr::copy(self.#ident_all.as_ptr(), dst.#ident_all.as_ptr(), count);
-                )*
             }
 
             #[inline]
-            unsafe fn set(self, element: #ident) {
-                #(self.#ident_all.as_ptr().write(element.#ident_all);)*
-            }
-
-            #[inline]
-            unsafe fn get(self) -> #ident {
-                #ident {
-                    #(#ident_all: self.#ident_all.as_ptr().read(),)*
+            unsafe fn get_mut<#lt>(self) -> #item_ref_mut #ty_generics_lt {
+                #item_ref_mut {
+                    #(#ident_all: #raw_get_mut,)*
                 }
             }
 
             #[inline]
-            unsafe fn get_ref<'a>(self) -> #item_ref<'a> {
-                #item_ref {
-                    #(#ident_all: self.#ident_all.as_ptr().as_ref().unwrap_unchecked(),)*
+            unsafe fn slices<#lt>(self, len: usize) -> #slices #ty_generics_lt {
+                #slices {
+                    #(#ident_all: unsafe { #raw_slices },)*
                 }
             }
 
             #[inline]
-            unsafe fn get_mut<'a>(self) -> #item_ref_mut<'a> {
-                #item_ref_mut {
-                    #(#ident_all: self.#ident_all.as_ptr().as_mut().unwrap_unchecked(),)*
+            unsafe fn slices_mut<#lt>(self, len: usize) -> #slices_mut #ty_generics_lt {
+                #slices_mut {
+                    #(#ident_all: unsafe { #raw_slices_mut },)*
                 }
             }
 
             #[inline]
-            unsafe fn offset(self, count: usize) -> Self {
-                Self {
-                    #(
-                    #ident_all: ::std::ptr::NonNull::new_unchecked(
-                        self.#ident_all.as_ptr().add(count)
-                    ),
-                    )*
+            unsafe fn chunk<#lt, const #n: usize>(self) -> #chunk #ty_generics_lt_n {
+                #chunk {
+                    #(#ident_all: unsafe { #raw_chunk },)*
                 }
             }
 
             #[inline]
-            unsafe fn slices<'a>(self, len: usize) -> #slices<'a> {
-                #slices {
-                    #(
-                        #ident_all: unsafe {
-                            ::std::slice::from_raw_parts(self.#ident_all.as_ptr(), len)
-                        },
-                    )*
+            unsafe fn chunk_mut<#lt, const #n: usize>(self) -> #chunk_mut #ty_generics_lt_n {
+                #chunk_mut {
+                    #(#ident_all: unsafe { #raw_chunk_mut },)*
                 }
             }
+        }
 
+        #[automatically_derived]
+        unsafe impl #impl_generics ::soa_rs::SoaRawClone for #raw #ty_generics
+        #clone_where_clause
+        {
             #[inline]
-            unsafe fn slices_mut<'a>(self, len: usize) -> #slices_mut<'a> {
-                #slices_mut {
-                    #(
-                        #ident_all: unsafe {
-                            ::std::slice::from_raw_parts_mut(self.#ident_all.as_ptr(), len)
-                        },
-                    )*
-                }
+            unsafe fn clone_to(self, dst: Self) {
+                #(#raw_clone;)*
             }
         }
 
         #[automatically_derived]
-        impl ::soa_rs::AsSoaRef for #ident {
-            type Item = #ident;
+        impl #impl_generics ::soa_rs::AsSoaRef for #item #where_clause {
+            type Item = #item;
 
             fn as_soa_ref(&self) -> <Self::Item as ::soa_rs::Soars>::Ref<'_> {
                 #item_ref {
-                    #(
-                        #ident_all: &self.#ident_all,
-                    )*
+                    #(#ident_all: #as_soa_ref,)*
                 }
             }
         }
 
         #[automatically_derived]
-        impl<'a> ::std::cmp::PartialEq<#ident> for #item_ref<'a> {
-            fn eq(&self, other: &#ident) -> bool {
-                self == &<#ident as ::soa_rs::AsSoaRef>::as_soa_ref(other)
+        impl #impl_generics_lt ::core::cmp::PartialEq<#item> for #item_ref #ty_generics_lt
+        #partial_eq_where_clause
+        {
+            fn eq(&self, other: &#item) -> bool {
+                ::soa_rs::AsSoaRef::as_soa_ref(self) == ::soa_rs::AsSoaRef::as_soa_ref(other)
             }
         }
     });
 
+    if with_serde {
+        out.append_all(serde::struct_impls(
+            &ident,
+            &generics,
+            kind,
+            &ident_all,
+            &ty_all,
+            &nested_all,
+            where_clause_lt.as_ref(),
+        ));
+    }
+
     Ok(out)
 }
 
+/// Returns the path to the array type generated for a nested field's type,
+/// such as `Vec3Array::<N>` for `Vec3`.
+fn nested_array(ty: &Type, n: &Ident) -> Result<TokenStream, syn::Error> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "soa(nested) requires a path to a type that derives Soars",
+        ));
+    };
+    let mut path = path.clone();
+    let last = path.segments.last_mut().expect("path without segments");
+    last.ident = format_ident!("{}Array", last.ident);
+    let mut args = match std::mem::replace(&mut last.arguments, PathArguments::None) {
+        PathArguments::None => Punctuated::new(),
+        PathArguments::AngleBracketed(args) => args.args,
+        PathArguments::Parenthesized(_) => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "soa(nested) requires a path to a type that derives Soars",
+            ))
+        }
+    };
+    args.push(parse_quote! { #n });
+    last.arguments = PathArguments::AngleBracketed(parse_quote! { ::<#args> });
+    Ok(quote! { #path })
+}
+
 #[derive(Clone, PartialEq, Eq)]
-enum FieldIdent {
+pub enum FieldIdent {
     Named(Ident),
     Unnamed(usize),
 }
@@ -581,6 +797,17 @@ impl From<(usize, Option<Ident>)> for FieldIdent {
     }
 }
 
+impl FieldIdent {
+    /// Returns the field name as written in the source, without any raw
+    /// identifier prefix. Unnamed fields are named by their index.
+    pub fn name(&self) -> String {
+        match self {
+            FieldIdent::Named(ident) => ident.unraw().to_string(),
+            FieldIdent::Unnamed(i) => i.to_string(),
+        }
+    }
+}
+
 impl ToTokens for FieldIdent {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
@@ -0,0 +1,51 @@
+use proc_macro2::Span;
+use syn::{parse_quote, GenericParam, Generics, Ident, Lifetime};
+
+/// Returns a lifetime for use in the generated types that does not collide with
+/// any of the generic parameters of the derived type.
+pub fn fresh_lifetime(generics: &Generics) -> Lifetime {
+    fresh_lifetime_named(generics, "a")
+}
+
+/// Like [`fresh_lifetime`], but starting from the given name, such as `de` for
+/// the lifetime of a serde deserializer.
+pub fn fresh_lifetime_named(generics: &Generics, base: &str) -> Lifetime {
+    Lifetime::new(
+        &format!("'{}", fresh_name(generics, base)),
+        Span::call_site(),
+    )
+}
+
+/// Returns an identifier for use as a generic parameter in the generated types
+/// that does not collide with any of the generic parameters of the derived
+/// type.
+pub fn fresh_ident(generics: &Generics, base: &str) -> Ident {
+    Ident::new(&fresh_name(generics, base), Span::call_site())
+}
+
+/// Returns a copy of the generics with the given lifetime prepended.
+pub fn with_lifetime(generics: &Generics, lifetime: &Lifetime) -> Generics {
+    let mut out = generics.clone();
+    out.params.insert(0, parse_quote! { #lifetime });
+    out
+}
+
+/// Returns a copy of the generics with a `const #ident: usize` parameter
+/// appended.
+pub fn with_const(generics: &Generics, ident: &Ident) -> Generics {
+    let mut out = generics.clone();
+    out.params.push(parse_quote! { const #ident: usize });
+    out
+}
+
+fn fresh_name(generics: &Generics, base: &str) -> String {
+    let mut name = base.to_string();
+    while generics.params.iter().any(|param| match param {
+        GenericParam::Lifetime(param) => param.lifetime.ident == name,
+        GenericParam::Type(param) => param.ident == name,
+        GenericParam::Const(param) => param.ident == name,
+    }) {
+        name.push('_');
+    }
+    name
+}
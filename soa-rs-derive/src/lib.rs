@@ -1,8 +1,13 @@
 //! This crate provides the derive macro for Soars.
 
+mod enums;
 mod fields;
+mod generics;
+mod raw;
+mod serde;
 mod zst;
 
+use enums::enum_soa;
 use fields::{fields_struct, FieldKind};
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
@@ -11,15 +16,15 @@ use std::collections::HashSet;
 use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields};
 use zst::{zst_struct, ZstKind};
 
-#[proc_macro_derive(Soars, attributes(align, soa_derive))]
+#[proc_macro_derive(Soars, attributes(align, soa, soa_derive))]
 pub fn soa(input: TokenStream) -> TokenStream {
     let input: DeriveInput = parse_macro_input!(input);
     let span = input.ident.span();
     match soa_inner(input) {
         Ok(tokens) => tokens,
         Err(e) => match e {
-            SoarsError::NotAStruct => quote_spanned! {
-                span => compile_error!("Soars only applies to structs");
+            SoarsError::Union => quote_spanned! {
+                span => compile_error!("Soars does not support unions");
             },
             SoarsError::Syn(e) => e.into_compile_error(),
         },
@@ -33,7 +38,7 @@ fn soa_inner(input: DeriveInput) -> Result<TokenStream2, SoarsError> {
         vis,
         data,
         attrs,
-        generics: _,
+        generics,
     } = input;
 
     let soa_derive = SoaDerive::try_from(attrs)?;
@@ -42,6 +47,7 @@ fn soa_inner(input: DeriveInput) -> Result<TokenStream2, SoarsError> {
             Fields::Named(fields) => Ok(fields_struct(
                 ident,
                 vis,
+                generics,
                 fields.named,
                 FieldKind::Named,
                 soa_derive,
@@ -49,19 +55,21 @@ fn soa_inner(input: DeriveInput) -> Result<TokenStream2, SoarsError> {
             Fields::Unnamed(fields) => Ok(fields_struct(
                 ident,
                 vis,
+                generics,
                 fields.unnamed,
                 FieldKind::Unnamed,
                 soa_derive,
             )?),
-            Fields::Unit => Ok(zst_struct(ident, vis, ZstKind::Unit)),
+            Fields::Unit => Ok(zst_struct(ident, vis, generics, ZstKind::Unit, soa_derive)),
         },
-        Data::Enum(_) | Data::Union(_) => Err(SoarsError::NotAStruct),
+        Data::Enum(data) => Ok(enum_soa(ident, vis, generics, data.variants, soa_derive)?),
+        Data::Union(_) => Err(SoarsError::Union),
     }
 }
 
 #[derive(Debug, Clone)]
 enum SoarsError {
-    NotAStruct,
+    Union,
     Syn(syn::Error),
 }
 
@@ -74,19 +82,21 @@ impl From<syn::Error> for SoarsError {
 #[derive(Debug, Clone, Default)]
 struct SoaDerive {
     derives: HashSet<syn::Path>,
+    /// Whether `#[soa(serde)]` requested the serde implementations.
+    serde: bool,
 }
 
 impl SoaDerive {
     fn into_derive(self) -> TokenStream2 {
-        let Self { derives } = self;
+        let Self { derives, .. } = self;
         let derives = derives.into_iter();
         quote! {
             #[derive(#(#derives),*)]
         }
     }
 
-    fn insert(&mut self, derive: &str) {
-        self.derives.insert(syn::Path::from(syn::PathSegment {
+    fn remove(&mut self, derive: &str) {
+        self.derives.remove(&syn::Path::from(syn::PathSegment {
             ident: Ident::new(derive, Span::call_site()),
             arguments: syn::PathArguments::None,
         }));
@@ -104,6 +114,20 @@ impl TryFrom<Vec<Attribute>> for SoaDerive {
                     out.derives.insert(meta.path);
                     Ok(())
                 });
+            } else if attr.path().is_ident("soa") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("serde") {
+                        if !cfg!(feature = "serde") {
+                            return Err(
+                                meta.error("soa(serde) requires the serde feature of soa-rs")
+                            );
+                        }
+                        out.serde = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unrecognized soa attribute"))
+                    }
+                })?;
             }
         }
         Ok(out)
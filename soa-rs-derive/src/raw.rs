@@ -0,0 +1,422 @@
+use crate::fields::FieldIdent;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Attribute, Ident, LitInt, Type, TypeGenerics, Visibility};
+
+/// One of the arrays managed by the generated `SoaRaw` type.
+pub struct Column {
+    pub ident: FieldIdent,
+    pub ty: Type,
+    pub align: Option<usize>,
+    /// Whether the column stores the `SoaRaw` of a type that derives `Soars`
+    /// rather than a pointer to an array.
+    pub nested: bool,
+}
+
+impl Column {
+    fn nested_call(&self, method: &str, args: TokenStream) -> TokenStream {
+        nested_raw(&self.ty, method, args)
+    }
+
+    /// Copies `count` elements of this column from `src` to `dst`.
+    fn copy(
+        &self,
+        src: TokenStream,
+        dst: TokenStream,
+        count: TokenStream,
+        rev: bool,
+    ) -> TokenStream {
+        let ident = &self.ident;
+        if self.nested {
+            let method = if rev { "copy_to_rev" } else { "copy_to" };
+            self.nested_call(method, quote! { #src.#ident, #dst.#ident, #count })
+        } else {
+            quote! { ::core::ptr::copy(#src.#ident.as_ptr(), #dst.#ident.as_ptr(), #count) }
+        }
+    }
+}
+
+/// Calls a method of the `SoaRaw` trait for the `SoaRaw` of a nested field's
+/// type.
+pub fn nested_raw(ty: &Type, method: &str, args: TokenStream) -> TokenStream {
+    let method = Ident::new(method, Span::call_site());
+    quote! { <<#ty as ::soa_rs::Soars>::Raw as ::soa_rs::SoaRaw>::#method(#args) }
+}
+
+/// Parses the `align` attribute for a field, if present.
+pub fn parse_align(attrs: &[Attribute]) -> Result<Option<usize>, syn::Error> {
+    for attr in attrs {
+        if attr.path().is_ident("align") {
+            let align_literal: LitInt = attr.parse_args()?;
+            let align: usize = align_literal.base10_parse()?;
+            if !align.is_power_of_two() {
+                return Err(syn::Error::new_spanned(
+                    align_literal,
+                    "align should be a power of two",
+                ));
+            }
+            return Ok(Some(align));
+        }
+    }
+    Ok(None)
+}
+
+/// Parses the `soa` attribute for a field, returning whether it contains
+/// `nested`.
+pub fn parse_nested(attrs: &[Attribute]) -> Result<bool, syn::Error> {
+    let mut nested = false;
+    for attr in attrs {
+        if attr.path().is_ident("soa") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("nested") {
+                    nested = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unrecognized soa attribute"))
+                }
+            })?;
+        }
+    }
+    Ok(nested)
+}
+
+/// Generates the inherent methods of the `SoaRaw` type that compute the
+/// allocation layout for the given columns.
+///
+/// `columns` must not be empty.
+pub fn raw_inherent(columns: &[Column]) -> TokenStream {
+    let (column_head, column_tail) = columns.split_first().expect("no columns");
+    let ident_head = &column_head.ident;
+    let ident_tail: Vec<_> = column_tail.iter().map(|column| &column.ident).collect();
+    let array = |column: &Column| {
+        let ty = &column.ty;
+        if column.nested {
+            column.nested_call("layout", quote! { cap })
+        } else {
+            quote! { ::core::alloc::Layout::array::<#ty>(cap) }
+        }
+    };
+    let array_head = array(column_head);
+    let array_tail: Vec<_> = column_tail.iter().map(array).collect();
+    let with_offset = |column: &Column, ptr: TokenStream| {
+        if column.nested {
+            column.nested_call("from_parts", quote! { #ptr, cap })
+        } else {
+            quote! { ::core::ptr::NonNull::new_unchecked(#ptr.cast()) }
+        }
+    };
+    let with_offset_head = with_offset(column_head, quote! { ptr });
+    let with_offset_tail: Vec<_> = column_tail
+        .iter()
+        .enumerate()
+        .map(|(i, column)| with_offset(column, quote! { ptr.add(offsets[#i]) }))
+        .collect();
+
+    let indices = std::iter::repeat(()).enumerate().map(|(i, ())| i);
+    let offsets_len = columns.len() - 1;
+
+    let layout_and_offsets_body = |checked: bool| {
+        let check = if checked {
+            quote! {
+                ?
+            }
+        } else {
+            quote! {
+                .unwrap_unchecked()
+            }
+        };
+
+        let mut raise_align = columns.iter().map(|column| {
+            column.align.as_ref().map(|align| {
+                quote! {
+                    let array = array.align_to(#align)#check;
+                }
+            })
+        });
+
+        let raise_align_head = raise_align.next().flatten();
+        let raise_align_tail: Vec<_> = raise_align.collect();
+
+        let indices = indices.clone();
+        quote! {
+            let array = #array_head #check;
+            #raise_align_head
+            let layout = array;
+            let mut offsets = [0usize; #offsets_len];
+            #(
+                let array = #array_tail #check;
+                #raise_align_tail
+                let (layout, offset) = layout.extend(array)#check;
+                offsets[#indices] = offset;
+            )*
+        }
+    };
+
+    let layout_and_offsets_checked_body = layout_and_offsets_body(true);
+    let layout_and_offsets_unchecked_body = layout_and_offsets_body(false);
+
+    quote! {
+        #[inline]
+        fn layout_and_offsets(cap: usize)
+            -> Result<(::core::alloc::Layout, [usize; #offsets_len]), ::core::alloc::LayoutError>
+        {
+            #layout_and_offsets_checked_body
+            Ok((layout, offsets))
+        }
+
+        #[inline]
+        unsafe fn layout_and_offsets_unchecked(cap: usize)
+            -> (::core::alloc::Layout, [usize; #offsets_len])
+        {
+            #layout_and_offsets_unchecked_body
+            (layout, offsets)
+        }
+
+        #[inline]
+        #[allow(unused_variables)]
+        unsafe fn with_offsets(ptr: *mut u8, offsets: [usize; #offsets_len], cap: usize) -> Self {
+            Self {
+                #ident_head: #with_offset_head,
+                #(#ident_tail: #with_offset_tail,)*
+            }
+        }
+    }
+}
+
+/// Generates the `SoaRaw` methods that manage the allocation and move array
+/// elements around without regard for what the columns contain.
+///
+/// `columns` must not be empty. `n` names the length parameter of the SoA
+/// array type.
+pub fn raw_storage(columns: &[Column], n: &Ident) -> TokenStream {
+    let ident_all: Vec<_> = columns.iter().map(|column| &column.ident).collect();
+    let dangling = columns.iter().map(|column| {
+        if column.nested {
+            column.nested_call("dangling", quote! {})
+        } else {
+            quote! { ::core::ptr::NonNull::dangling() }
+        }
+    });
+    let offset = columns.iter().map(|column| {
+        let ident = &column.ident;
+        if column.nested {
+            column.nested_call("offset", quote! { self.#ident, count })
+        } else {
+            quote! { ::core::ptr::NonNull::new_unchecked(self.#ident.as_ptr().add(count)) }
+        }
+    });
+    let from_array = columns.iter().map(|column| {
+        let ident = &column.ident;
+        let ty = &column.ty;
+        let ptr = quote! { ::core::ptr::addr_of_mut!((*array).#ident) };
+        if column.nested {
+            quote! { <<#ty as ::soa_rs::Soars>::Raw as ::soa_rs::SoaRaw>::from_array::<#n>(#ptr) }
+        } else {
+            quote! { ::core::ptr::NonNull::new_unchecked(#ptr.cast()) }
+        }
+    });
+    let copy = |src, dst, count, rev: bool| {
+        let copy = columns
+            .iter()
+            .map(|column| column.copy(quote! { #src }, quote! { #dst }, quote! { #count }, rev));
+        if rev {
+            copy.rev().collect::<Vec<_>>()
+        } else {
+            copy.collect()
+        }
+    };
+    let copy_grow = copy(quote! { old }, quote! { new }, quote! { length }, true);
+    let copy_shrink = copy(quote! { self }, quote! { dst }, quote! { length }, false);
+    let copy_to = copy(quote! { self }, quote! { dst }, quote! { count }, false);
+    let copy_to_rev = copy(quote! { self }, quote! { dst }, quote! { count }, true);
+
+    // The first column starts at the beginning of the allocation. A nested
+    // zero-sized type doesn't keep a pointer into the allocation, so the next
+    // column is used instead, which also starts at the beginning.
+    let into_parts = columns.iter().rev().fold(
+        quote! { ::core::ptr::NonNull::dangling().as_ptr() },
+        |fallback, column| {
+            let ident = &column.ident;
+            let ty = &column.ty;
+            if column.nested {
+                let into_parts = column.nested_call("into_parts", quote! { self.#ident });
+                quote! {
+                    if ::core::mem::size_of::<#ty>() > 0 {
+                        #into_parts
+                    } else {
+                        #fallback
+                    }
+                }
+            } else {
+                quote! { self.#ident.as_ptr().cast() }
+            }
+        },
+    );
+
+    quote! {
+        #[inline]
+        fn dangling() -> Self {
+            Self {
+                #(#ident_all: #dangling,)*
+            }
+        }
+
+        #[inline]
+        fn layout(capacity: usize) -> Result<::core::alloc::Layout, ::core::alloc::LayoutError> {
+            Self::layout_and_offsets(capacity).map(|(layout, _)| layout)
+        }
+
+        #[inline]
+        unsafe fn from_parts(ptr: *mut u8, capacity: usize) -> Self {
+            // SAFETY: This should have come from a previous allocation
+            let (_, offsets) = Self::layout_and_offsets_unchecked(capacity);
+            Self::with_offsets(ptr, offsets, capacity)
+        }
+
+        #[inline]
+        fn into_parts(self) -> *mut u8 {
+            #into_parts
+        }
+
+        #[inline]
+        unsafe fn from_array<const #n: usize>(
+            array: *mut <Self::Item as ::soa_rs::Soars>::Array<#n>,
+        ) -> Self {
+            Self {
+                #(#ident_all: #from_array,)*
+            }
+        }
+
+        #[inline]
+        unsafe fn try_alloc<A: ::soa_rs::Allocator>(
+            capacity: usize,
+            allocator: &A,
+        ) -> Result<Self, ::soa_rs::TryReserveError> {
+            let (new_layout, new_offsets) = Self::layout_and_offsets(capacity)?;
+
+            let ptr = allocator
+                .allocate(new_layout)
+                .map_err(|_| ::soa_rs::TryReserveErrorKind::AllocError { layout: new_layout })?;
+
+            Ok(Self::with_offsets(ptr.as_ptr().cast(), new_offsets, capacity))
+        }
+
+        #[inline]
+        unsafe fn try_realloc_grow<A: ::soa_rs::Allocator>(
+            &mut self,
+            old_capacity: usize,
+            new_capacity: usize,
+            length: usize,
+            allocator: &A,
+        ) -> Result<Self, ::soa_rs::TryReserveError> {
+            // SAFETY: We already constructed this layout for a previous allocation
+            let (old_layout, old_offsets) = Self::layout_and_offsets_unchecked(old_capacity);
+            let (new_layout, new_offsets) = Self::layout_and_offsets(new_capacity)?;
+
+            // Grow allocation first. On failure, the original allocation is
+            // left untouched.
+            let ptr = ::core::ptr::NonNull::new_unchecked(self.into_parts());
+            let ptr = allocator
+                .grow(ptr, old_layout, new_layout)
+                .map_err(|_| ::soa_rs::TryReserveErrorKind::AllocError { layout: new_layout })?;
+            let ptr = ptr.as_ptr().cast();
+
+            // Pointer may have moved, can't reuse self
+            let old = Self::with_offsets(ptr, old_offsets, old_capacity);
+            let new = Self::with_offsets(ptr, new_offsets, new_capacity);
+
+            // Copy do destination in reverse order to avoid
+            // overwriting data
+            #(#copy_grow;)*
+
+            Ok(new)
+        }
+
+        #[inline]
+        unsafe fn realloc_shrink<A: ::soa_rs::Allocator>(
+            &mut self,
+            old_capacity: usize,
+            new_capacity: usize,
+            length: usize,
+            allocator: &A,
+        ) -> Self {
+            // SAFETY: We already constructed this layout for a previous allocation
+            let (old_layout, _) = Self::layout_and_offsets_unchecked(old_capacity);
+            let (new_layout, new_offsets) = Self::layout_and_offsets(new_capacity)
+                .expect("capacity overflow");
+
+            // Move data before reallocating as some data
+            // may be past the end of the new allocation.
+            // Copy from front to back to avoid overwriting data.
+            let ptr = self.into_parts();
+            let dst = Self::with_offsets(ptr, new_offsets, new_capacity);
+            #(#copy_shrink;)*
+
+            let ptr = ::core::ptr::NonNull::new_unchecked(ptr);
+            let ptr = match allocator.shrink(ptr, old_layout, new_layout) {
+                Ok(ptr) => ptr.as_ptr().cast(),
+                Err(_) => ::soa_rs::handle_alloc_error(new_layout),
+            };
+
+            // Pointer may have moved, can't reuse dst
+            Self::with_offsets(ptr, new_offsets, new_capacity)
+        }
+
+        #[inline]
+        unsafe fn dealloc<A: ::soa_rs::Allocator>(self, old_capacity: usize, allocator: &A) {
+            // SAFETY: We already constructed this layout for a previous allocation
+            let (layout, _) = Self::layout_and_offsets_unchecked(old_capacity);
+            allocator.deallocate(::core::ptr::NonNull::new_unchecked(self.into_parts()), layout);
+        }
+
+        #[inline]
+        unsafe fn copy_to(self, dst: Self, count: usize) {
+            #(#copy_to;)*
+        }
+
+        #[inline]
+        unsafe fn copy_to_rev(self, dst: Self, count: usize) {
+            #(#copy_to_rev;)*
+        }
+
+        #[inline]
+        unsafe fn offset(self, count: usize) -> Self {
+            Self {
+                #(#ident_all: #offset,)*
+            }
+        }
+    }
+}
+
+/// Generates the inherent methods of the SoA array type that borrow the whole
+/// array as a chunk.
+pub fn array_as_chunk(
+    vis: &Visibility,
+    item: &TokenStream,
+    raw: &Ident,
+    ty_generics: &TypeGenerics,
+    n: &Ident,
+) -> TokenStream {
+    let from_array = quote! {
+        <#raw #ty_generics as ::soa_rs::SoaRaw>::from_array::<#n>
+    };
+    quote! {
+        /// Returns the array as a chunk, which has the same type as the
+        /// chunks yielded by `Slice::chunks_lanes`.
+        #vis fn as_chunk(&self) -> <#item as ::soa_rs::Soars>::Chunk<'_, #n> {
+            unsafe {
+                let raw = #from_array(::core::ptr::from_ref(self).cast_mut());
+                ::soa_rs::SoaRaw::chunk::<#n>(raw)
+            }
+        }
+
+        /// Returns the array as a mutable chunk, which has the same type as
+        /// the chunks yielded by `Slice::chunks_lanes_mut`.
+        #vis fn as_chunk_mut(&mut self) -> <#item as ::soa_rs::Soars>::ChunkMut<'_, #n> {
+            unsafe {
+                let raw = #from_array(::core::ptr::from_mut(self));
+                ::soa_rs::SoaRaw::chunk_mut::<#n>(raw)
+            }
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! The serde implementations emitted for types marked with `#[soa(serde)]`. The
+//! impls name serde through the re-export in `soa_rs::serde::__private` so
+//! that users don't need serde as a direct dependency of their own.
+
+use crate::{
+    enums::{EnumVariant, VariantKind},
+    fields::{FieldIdent, FieldKind},
+    generics::{fresh_ident, fresh_lifetime, fresh_lifetime_named, with_const, with_lifetime},
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, parse_quote, Generics, Ident, Index, Type, WhereClause};
+
+/// Implements row serialization for the reference type, columnar
+/// serialization for the slices type, and both modes for the array type of a
+/// struct with fields.
+pub fn struct_impls(
+    ident: &Ident,
+    generics: &Generics,
+    kind: FieldKind,
+    ident_all: &[FieldIdent],
+    ty_all: &[Type],
+    nested_all: &[bool],
+    where_clause_lt: Option<&WhereClause>,
+) -> TokenStream {
+    let item_ref = format_ident!("{ident}Ref");
+    let slices = format_ident!("{ident}Slices");
+    let array = format_ident!("{ident}Array");
+    let lt = fresh_lifetime(generics);
+    let generics_lt = with_lifetime(generics, &lt);
+    // Bounds on the field types go under a binder so that they aren't
+    // rejected as trivially false when a field type doesn't implement the
+    // serde traits.
+    let lt_bound = fresh_lifetime(&generics_lt);
+    let s = fresh_ident(generics, "S");
+    let e = fresh_ident(generics, "E");
+    let de = fresh_lifetime_named(generics, "de");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics_lt, ty_generics_lt, _) = generics_lt.split_for_impl();
+    let item = quote! { #ident #ty_generics };
+    let name = ident.unraw().to_string();
+    let names: Vec<_> = ident_all.iter().map(FieldIdent::name).collect();
+    let fields_len = ident_all.len();
+    let serde = quote! { ::soa_rs::serde::__private::serde };
+    let private = quote! { ::soa_rs::serde::__private };
+
+    let where_with = |base: Option<&WhereClause>, bound: &dyn Fn(&Type, bool) -> TokenStream| {
+        let mut out = base.cloned().unwrap_or_else(|| parse_quote! { where });
+        for (ty, &nested) in ty_all.iter().zip(nested_all.iter()) {
+            let bound = bound(ty, nested);
+            out.predicates.push(parse_quote! { for<#lt_bound> #bound });
+        }
+        out
+    };
+
+    let ref_where_clause = where_with(where_clause_lt, &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::Ref<#lt>: #serde::Serialize }
+        } else {
+            quote! { #ty: #serde::Serialize }
+        }
+    });
+    // Rows serialize the same way serde's derive would serialize the item.
+    let ref_body = match (kind, fields_len) {
+        (FieldKind::Named, _) => quote! {
+            let mut state = #serde::Serializer::serialize_struct(serializer, #name, #fields_len)?;
+            #(
+                #serde::ser::SerializeStruct::serialize_field(&mut state, #names, &self.#ident_all)?;
+            )*
+            #serde::ser::SerializeStruct::end(state)
+        },
+        (FieldKind::Unnamed, 1) => quote! {
+            #serde::Serializer::serialize_newtype_struct(serializer, #name, &self.0)
+        },
+        (FieldKind::Unnamed, _) => quote! {
+            let mut state =
+                #serde::Serializer::serialize_tuple_struct(serializer, #name, #fields_len)?;
+            #(
+                #serde::ser::SerializeTupleStruct::serialize_field(&mut state, &self.#ident_all)?;
+            )*
+            #serde::ser::SerializeTupleStruct::end(state)
+        },
+    };
+
+    let slices_where_clause = where_with(where_clause_lt, &|ty, nested| {
+        if nested {
+            quote! { <#ty as ::soa_rs::Soars>::Slices<#lt>: #serde::Serialize }
+        } else {
+            quote! { #ty: #serde::Serialize }
+        }
+    });
+
+    let columns_where_clause = where_with(where_clause, &|ty, nested| {
+        if nested {
+            quote! { #ty: #private::SoarsColumns }
+        } else {
+            quote! { #ty: #serde::de::DeserializeOwned }
+        }
+    });
+    let column_ty: Vec<_> = ty_all
+        .iter()
+        .zip(nested_all.iter())
+        .map(|(ty, &nested)| {
+            if nested {
+                quote! { ::soa_rs::Soa<#ty> }
+            } else {
+                quote! { #private::Vec<#ty> }
+            }
+        })
+        .collect();
+    let next_column_arms =
+        ty_all
+            .iter()
+            .zip(nested_all.iter())
+            .enumerate()
+            .map(|(i, (ty, &nested))| {
+                let index = Index::from(i);
+                let (source_ty, unwrap) = if nested {
+                    (
+                        quote! { ::soa_rs::serde::columnar::Columnar<::soa_rs::Soa<#ty>> },
+                        quote! { column.0 },
+                    )
+                } else {
+                    (quote! { #private::Vec<#ty> }, quote! { column })
+                };
+                quote! {
+                    #i => match #private::ColumnSource::next_column::<#source_ty>(source)? {
+                        ::core::option::Option::Some(column) => {
+                            columns.#index = ::core::option::Option::Some(#unwrap);
+                            ::core::result::Result::Ok(true)
+                        }
+                        ::core::option::Option::None => ::core::result::Result::Ok(false),
+                    },
+                }
+            });
+    let index_all = (0..fields_len).map(Index::from);
+    let column_all: Vec<_> = (0..fields_len).map(|i| format_ident!("c{i}")).collect();
+    let column_first = &column_all[0];
+    let column_rest = &column_all[1..];
+
+    let array_rows = array_rows(&array, generics, &item, Some(&item_ref));
+    let array_columns = array_columns(&array, generics, &item, &slices, &columns_where_clause);
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics_lt #serde::Serialize for #item_ref #ty_generics_lt
+        #ref_where_clause
+        {
+            fn serialize<#s>(
+                &self,
+                serializer: #s,
+            ) -> ::core::result::Result<#s::Ok, #s::Error>
+            where
+                #s: #serde::Serializer,
+            {
+                #ref_body
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics_lt #serde::Serialize for #slices #ty_generics_lt
+        #slices_where_clause
+        {
+            fn serialize<#s>(
+                &self,
+                serializer: #s,
+            ) -> ::core::result::Result<#s::Ok, #s::Error>
+            where
+                #s: #serde::Serializer,
+            {
+                let mut state = #serde::Serializer::serialize_struct(serializer, #name, #fields_len)?;
+                #(
+                    #serde::ser::SerializeStruct::serialize_field(&mut state, #names, &self.#ident_all)?;
+                )*
+                #serde::ser::SerializeStruct::end(state)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #private::SoarsColumns for #item #columns_where_clause {
+            const NAME: &'static str = #name;
+            const FIELDS: &'static [&'static str] = &[#(#names),*];
+            type Columns = (#(::core::option::Option<#column_ty>,)*);
+
+            fn columns() -> Self::Columns {
+                (#(::core::option::Option::<#column_ty>::None,)*)
+            }
+
+            fn next_column<#de, #s>(
+                columns: &mut Self::Columns,
+                index: usize,
+                source: &mut #s,
+            ) -> ::core::result::Result<bool, #s::Error>
+            where
+                #s: #private::ColumnSource<#de>,
+            {
+                match index {
+                    #(#next_column_arms)*
+                    _ => ::core::result::Result::Ok(false),
+                }
+            }
+
+            fn assemble<#e>(
+                columns: Self::Columns,
+            ) -> ::core::result::Result<::soa_rs::Soa<Self>, #e>
+            where
+                #e: #serde::de::Error,
+            {
+                #(
+                    let #column_all = columns.#index_all.ok_or_else(|| {
+                        <#e as #serde::de::Error>::missing_field(#names)
+                    })?;
+                )*
+                let len = #column_first.len();
+                #(
+                    if #column_rest.len() != len {
+                        return ::core::result::Result::Err(
+                            <#e as #serde::de::Error>::invalid_length(
+                                #column_rest.len(),
+                                &"columns of equal length",
+                            ),
+                        );
+                    }
+                )*
+                #(
+                    let mut #column_all = ::core::iter::IntoIterator::into_iter(#column_all);
+                )*
+                let mut out = ::soa_rs::Soa::<Self>::with_capacity(len);
+                for _ in 0..len {
+                    out.push(#ident {
+                        #(
+                            #ident_all: ::core::iter::Iterator::next(&mut #column_all).unwrap(),
+                        )*
+                    });
+                }
+                ::core::result::Result::Ok(out)
+            }
+        }
+
+        #array_rows
+
+        #array_columns
+    }
+}
+
+/// Implements row serialization for the reference type and the array type of
+/// an enum. Variants are externally tagged, as with serde's derive.
+pub fn enum_impls(
+    ident: &Ident,
+    generics: &Generics,
+    variants: &[EnumVariant],
+    phantom_arm: &TokenStream,
+) -> TokenStream {
+    let item_ref = format_ident!("{ident}Ref");
+    let array = format_ident!("{ident}Array");
+    let lt = fresh_lifetime(generics);
+    let generics_lt = with_lifetime(generics, &lt);
+    let lt_bound = fresh_lifetime(&generics_lt);
+    let s = fresh_ident(generics, "S");
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics_lt, ty_generics_lt, _) = generics_lt.split_for_impl();
+    let item = quote! { #ident #ty_generics };
+    let name = ident.unraw().to_string();
+    let serde = quote! { ::soa_rs::serde::__private::serde };
+
+    let mut ref_where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| parse_quote! { where });
+    for field in variants.iter().flat_map(|variant| variant.fields.iter()) {
+        let ty = &field.ty;
+        ref_where_clause
+            .predicates
+            .push(parse_quote! { for<#lt_bound> #ty: #serde::Serialize });
+    }
+
+    let arms = variants.iter().enumerate().map(|(i, variant)| {
+        let index = i as u32;
+        let variant_name = variant.ident.unraw().to_string();
+        let pattern = variant.pattern(quote! { Self });
+        let bindings = variant.bindings();
+        let fields_len = bindings.len();
+        let body = match (variant.kind, fields_len) {
+            (VariantKind::Unit, _) => quote! {
+                #serde::Serializer::serialize_unit_variant(serializer, #name, #index, #variant_name)
+            },
+            (VariantKind::Unnamed, 1) => {
+                let binding = &bindings[0];
+                quote! {
+                    #serde::Serializer::serialize_newtype_variant(
+                        serializer,
+                        #name,
+                        #index,
+                        #variant_name,
+                        #binding,
+                    )
+                }
+            }
+            (VariantKind::Unnamed, _) => quote! {
+                let mut state = #serde::Serializer::serialize_tuple_variant(
+                    serializer,
+                    #name,
+                    #index,
+                    #variant_name,
+                    #fields_len,
+                )?;
+                #(
+                    #serde::ser::SerializeTupleVariant::serialize_field(&mut state, #bindings)?;
+                )*
+                #serde::ser::SerializeTupleVariant::end(state)
+            },
+            (VariantKind::Named, _) => {
+                let names = variant.fields.iter().map(|field| field.ident.name());
+                quote! {
+                    let mut state = #serde::Serializer::serialize_struct_variant(
+                        serializer,
+                        #name,
+                        #index,
+                        #variant_name,
+                        #fields_len,
+                    )?;
+                    #(
+                        #serde::ser::SerializeStructVariant::serialize_field(
+                            &mut state,
+                            #names,
+                            #bindings,
+                        )?;
+                    )*
+                    #serde::ser::SerializeStructVariant::end(state)
+                }
+            }
+        };
+        quote! { #pattern => { #body } }
+    });
+
+    let array_rows = array_rows(&array, generics, &item, Some(&item_ref));
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics_lt #serde::Serialize for #item_ref #ty_generics_lt
+        #ref_where_clause
+        {
+            fn serialize<#s>(
+                &self,
+                serializer: #s,
+            ) -> ::core::result::Result<#s::Ok, #s::Error>
+            where
+                #s: #serde::Serializer,
+            {
+                match self {
+                    #(#arms)*
+                    #phantom_arm
+                }
+            }
+        }
+
+        #array_rows
+    }
+}
+
+/// Implements row serialization for the array type of `item`. The array
+/// deserializes from a sequence of exactly its length.
+///
+/// The bounds name the generated reference type, or the item itself for ZSTs
+/// where `item_ref` is `None`, rather than the `Soars::Ref` projection. A
+/// higher-ranked bound on the projection would require the item to be
+/// `'static` in order to normalize it.
+pub fn array_rows(
+    array: &Ident,
+    generics: &Generics,
+    item: &TokenStream,
+    item_ref: Option<&Ident>,
+) -> TokenStream {
+    let n = fresh_ident(generics, "N");
+    let generics_n = with_const(generics, &n);
+    let lt_bound = fresh_lifetime(generics);
+    let generics_bound = with_lifetime(generics, &lt_bound);
+    let (_, ty_generics_bound, _) = generics_bound.split_for_impl();
+    let de = fresh_lifetime_named(generics, "de");
+    let generics_de_n = with_lifetime(&generics_n, &de);
+    let s = fresh_ident(generics, "S");
+    let d = fresh_ident(generics, "D");
+    let (_, _, where_clause) = generics.split_for_impl();
+    let (impl_generics_n, ty_generics_n, _) = generics_n.split_for_impl();
+    let (impl_generics_de_n, _, _) = generics_de_n.split_for_impl();
+    let serde = quote! { ::soa_rs::serde::__private::serde };
+    let private = quote! { ::soa_rs::serde::__private };
+
+    let mut ser_where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| parse_quote! { where });
+    ser_where_clause.predicates.push(match item_ref {
+        Some(item_ref) => parse_quote! {
+            for<#lt_bound> #item_ref #ty_generics_bound: #serde::Serialize
+        },
+        None => parse_quote! { for<#lt_bound> #item: #serde::Serialize },
+    });
+    let mut de_where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| parse_quote! { where });
+    de_where_clause
+        .predicates
+        .push(parse_quote! { #item: #serde::Deserialize<#de> });
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics_n #serde::Serialize for #array #ty_generics_n
+        #ser_where_clause
+        {
+            fn serialize<#s>(
+                &self,
+                serializer: #s,
+            ) -> ::core::result::Result<#s::Ok, #s::Error>
+            where
+                #s: #serde::Serializer,
+            {
+                #serde::Serializer::collect_seq(
+                    serializer,
+                    ::soa_rs::SoaArray::as_slice(self).iter(),
+                )
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics_de_n #serde::Deserialize<#de> for #array #ty_generics_n
+        #de_where_clause
+        {
+            fn deserialize<#d>(deserializer: #d) -> ::core::result::Result<Self, #d::Error>
+            where
+                #d: #serde::Deserializer<#de>,
+            {
+                let soa = <::soa_rs::Soa<#item> as #serde::Deserialize<#de>>::deserialize(
+                    deserializer,
+                )?;
+                #private::array_from_soa::<#item, #n, _>(soa)
+            }
+        }
+    }
+}
+
+/// Implements columnar serialization for the array type of `item`, given the
+/// where clause of its `SoarsColumns` implementation. As with [`array_rows`],
+/// the bounds avoid the `Soars` projections. Bounding on the field types
+/// rather than `#item: SoarsColumns` keeps the `Soars` supertrait out of the
+/// where clause, which would otherwise prevent the compiler from normalizing
+/// `<#item as Soars>::Array<N>` to the array type.
+fn array_columns(
+    array: &Ident,
+    generics: &Generics,
+    item: &TokenStream,
+    slices: &Ident,
+    columns_where_clause: &WhereClause,
+) -> TokenStream {
+    let n = fresh_ident(generics, "N");
+    let generics_n = with_const(generics, &n);
+    let lt_bound = fresh_lifetime(generics);
+    let generics_bound = with_lifetime(generics, &lt_bound);
+    let (_, ty_generics_bound, _) = generics_bound.split_for_impl();
+    let de = fresh_lifetime_named(generics, "de");
+    let s = fresh_ident(generics, "S");
+    let d = fresh_ident(generics, "D");
+    let (_, _, where_clause) = generics.split_for_impl();
+    let (impl_generics_n, ty_generics_n, _) = generics_n.split_for_impl();
+    let serde = quote! { ::soa_rs::serde::__private::serde };
+    let private = quote! { ::soa_rs::serde::__private };
+    let columnar = quote! { ::soa_rs::serde::columnar };
+
+    let mut ser_where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| parse_quote! { where });
+    ser_where_clause.predicates.push(parse_quote! {
+        for<#lt_bound> #slices #ty_generics_bound: #serde::Serialize
+    });
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics_n #columnar::SerializeColumns for #array #ty_generics_n
+        #ser_where_clause
+        {
+            fn serialize_columns<#s>(
+                &self,
+                serializer: #s,
+            ) -> ::core::result::Result<#s::Ok, #s::Error>
+            where
+                #s: #serde::Serializer,
+            {
+                #serde::Serialize::serialize(&::soa_rs::SoaArray::as_slice(self).slices(), serializer)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics_n #columnar::DeserializeColumns for #array #ty_generics_n
+        #columns_where_clause
+        {
+            fn deserialize_columns<#de, #d>(
+                deserializer: #d,
+            ) -> ::core::result::Result<Self, #d::Error>
+            where
+                #d: #serde::Deserializer<#de>,
+            {
+                let soa = <::soa_rs::Soa<#item> as #columnar::DeserializeColumns>::deserialize_columns(
+                    deserializer,
+                )?;
+                #private::array_from_soa::<#item, #n, _>(soa)
+            }
+        }
+    }
+}
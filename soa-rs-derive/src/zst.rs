@@ -1,69 +1,121 @@
+use crate::{
+    generics::{fresh_ident, fresh_lifetime, with_const},
+    serde, SoaDerive,
+};
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
-use syn::Visibility;
-
-pub fn zst_struct(ident: Ident, vis: Visibility, kind: ZstKind) -> TokenStream {
+use syn::{Generics, Visibility};
+
+pub fn zst_struct(
+    ident: Ident,
+    vis: Visibility,
+    generics: Generics,
+    kind: ZstKind,
+    soa_derive: SoaDerive,
+) -> TokenStream {
     let raw = format_ident!("{ident}SoaRaw");
     let deref = format_ident!("{ident}Deref");
     let array = format_ident!("{ident}Array");
+    let lt = fresh_lifetime(&generics);
+    let n = fresh_ident(&generics, "N");
+    let generics_n = with_const(&generics, &n);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics_n, ty_generics_n, _) = generics_n.split_for_impl();
+    let item = quote! { #ident #ty_generics };
+    let with_serde = soa_derive.serde;
+    let extra = soa_derive.into_derive();
     let unit_construct = match kind {
         ZstKind::Unit => quote! {},
         ZstKind::Empty => quote! { {} },
         ZstKind::EmptyTuple => quote! { () },
     };
 
+    // The reference type of a ZST is the item itself, so serializing rows
+    // relies on the item's own serde implementations.
+    let serde_impls = if with_serde {
+        serde::array_rows(&array, &generics, &item, None)
+    } else {
+        quote! {}
+    };
+
     quote! {
+        #serde_impls
+
         #[automatically_derived]
-        unsafe impl ::soa_rs::Soars for #ident {
-            type Raw = #raw;
-            type Deref = #deref;
-            type Ref<'a> = #ident;
-            type RefMut<'a> = #ident;
-            type Slices<'a> = #ident;
-            type SlicesMut<'a> = #ident;
-            type Array<const N: usize> = #array<N>;
+        unsafe impl #impl_generics ::soa_rs::Soars for #item #where_clause {
+            type Raw = #raw #ty_generics;
+            type Deref = #deref #ty_generics;
+            type Ref<#lt> = #item;
+            type RefMut<#lt> = #item;
+            type Slices<#lt> = #item;
+            type SlicesMut<#lt> = #item;
+            type Array<const #n: usize> = #array #ty_generics_n;
+            type Chunk<#lt, const #n: usize> = #item;
+            type ChunkMut<#lt, const #n: usize> = #item;
         }
 
+        #extra
         #[automatically_derived]
-        #vis struct #array<const N: usize>;
+        #vis struct #array #impl_generics_n #where_clause;
+
+        impl #impl_generics_n #array #ty_generics_n #where_clause {
+            #vis const fn from_array(array: [#item; #n]) -> Self {
+                ::core::mem::forget(array);
+                Self
+            }
+
+            /// Returns the array as a chunk, which has the same type as the
+            /// chunks yielded by `Slice::chunks_lanes`.
+            #vis fn as_chunk(&self) -> #item {
+                #ident #unit_construct
+            }
+
+            /// Returns the array as a mutable chunk, which has the same type as
+            /// the chunks yielded by `Slice::chunks_lanes_mut`.
+            #vis fn as_chunk_mut(&mut self) -> #item {
+                #ident #unit_construct
+            }
+        }
 
         #[automatically_derived]
-        impl<const N: usize> ::soa_rs::SoaArray for #array<N> {
-            type Item = #ident;
+        impl #impl_generics_n ::soa_rs::SoaArray for #array #ty_generics_n #where_clause {
+            type Item = #item;
 
             fn as_slice(&self) -> ::soa_rs::SliceRef<'_, Self::Item> {
                 let raw = #raw;
                 let slice = ::soa_rs::Slice::with_raw(raw);
-                unsafe { ::soa_rs::SliceRef::from_slice(slice, N) }
+                unsafe { ::soa_rs::SliceRef::from_slice(slice, #n) }
             }
 
             fn as_mut_slice(&mut self) -> ::soa_rs::SliceMut<'_, Self::Item> {
                 let raw = #raw;
                 let slice = ::soa_rs::Slice::with_raw(raw);
-                unsafe { ::soa_rs::SliceMut::from_slice(slice, N) }
+                unsafe { ::soa_rs::SliceMut::from_slice(slice, #n) }
             }
         }
 
         // TODO: Consolidate duplication from fields
         #[automatically_derived]
         #[repr(transparent)]
-        #vis struct #deref(::soa_rs::Slice<#ident>);
+        #vis struct #deref #impl_generics (::soa_rs::Slice<#item>) #where_clause;
 
-        impl ::soa_rs::SoaDeref for #deref {
-            type Item = #ident;
+        impl #impl_generics ::soa_rs::SoaDeref for #deref #ty_generics #where_clause {
+            type Item = #item;
 
             fn from_slice(slice: &::soa_rs::Slice<Self::Item>) -> &Self {
-                unsafe { ::std::mem::transmute(slice) }
+                let slice = ::core::ptr::from_ref(slice) as *const Self;
+                unsafe { &*slice }
             }
 
             fn from_slice_mut(slice: &mut ::soa_rs::Slice<Self::Item>) -> &mut Self {
-                unsafe { ::std::mem::transmute(slice) }
+                let slice = ::core::ptr::from_mut(slice) as *mut Self;
+                unsafe { &mut *slice }
             }
         }
 
         #[automatically_derived]
-        impl ::soa_rs::AsSoaRef for #ident {
-            type Item = #ident;
+        impl #impl_generics ::soa_rs::AsSoaRef for #item #where_clause {
+            type Item = #item;
 
             fn as_soa_ref(&self) -> Self::Item {
                 #ident #unit_construct
@@ -72,68 +124,112 @@ pub fn zst_struct(ident: Ident, vis: Visibility, kind: ZstKind) -> TokenStream {
 
         #[automatically_derived]
         #[derive(Copy, Clone)]
-        #vis struct #raw;
+        #vis struct #raw #impl_generics #where_clause;
+
+        #[automatically_derived]
+        unsafe impl #impl_generics ::soa_rs::SoaRawClone for #raw #ty_generics #where_clause {
+            #[inline]
+            unsafe fn clone_to(self, dst: Self) { }
+        }
 
         #[automatically_derived]
-        unsafe impl ::soa_rs::SoaRaw for #raw {
-            type Item = #ident;
+        unsafe impl #impl_generics ::soa_rs::SoaRaw for #raw #ty_generics #where_clause {
+            type Item = #item;
 
             #[inline]
             fn dangling() -> Self { Self }
 
+            #[inline]
+            fn layout(capacity: usize) -> Result<::core::alloc::Layout, ::core::alloc::LayoutError> {
+                Ok(::core::alloc::Layout::new::<()>())
+            }
+
             #[inline]
             unsafe fn from_parts(ptr: *mut u8, capacity: usize) -> Self { Self }
 
             #[inline]
-            fn into_parts(self) -> *mut u8 { ::std::ptr::NonNull::dangling().as_ptr() }
+            fn into_parts(self) -> *mut u8 { ::core::ptr::NonNull::dangling().as_ptr() }
 
             #[inline]
-            unsafe fn alloc(capacity: usize) -> Self { Self }
+            unsafe fn from_array<const #n: usize>(
+                array: *mut <Self::Item as ::soa_rs::Soars>::Array<#n>,
+            ) -> Self {
+                Self
+            }
 
             #[inline]
-            unsafe fn realloc_grow(
+            unsafe fn try_alloc<A: ::soa_rs::Allocator>(
+                capacity: usize,
+                allocator: &A,
+            ) -> Result<Self, ::soa_rs::TryReserveError> {
+                Ok(Self)
+            }
+
+            #[inline]
+            unsafe fn try_realloc_grow<A: ::soa_rs::Allocator>(
                 &mut self,
                 old_capacity: usize,
                 new_capacity: usize,
                 length: usize,
-            ) -> Self { Self }
+                allocator: &A,
+            ) -> Result<Self, ::soa_rs::TryReserveError> {
+                Ok(Self)
+            }
 
             #[inline]
-            unsafe fn realloc_shrink(
+            unsafe fn realloc_shrink<A: ::soa_rs::Allocator>(
                 &mut self,
                 old_capacity: usize,
                 new_capacity: usize,
                 length: usize,
+                allocator: &A,
             ) -> Self { Self }
 
             #[inline]
-            unsafe fn dealloc(self, old_capacity: usize) { }
+            unsafe fn dealloc<A: ::soa_rs::Allocator>(self, old_capacity: usize, allocator: &A) { }
 
             #[inline]
             unsafe fn copy_to(self, dst: Self, count: usize) { }
 
             #[inline]
-            unsafe fn set(self, element: #ident) { }
+            unsafe fn copy_to_rev(self, dst: Self, count: usize) { }
+
+            #[inline]
+            unsafe fn set(self, element: #item) { }
 
             #[inline]
-            unsafe fn get(self) -> #ident { #ident #unit_construct }
+            unsafe fn get(self) -> #item { #ident #unit_construct }
 
             #[inline]
-            unsafe fn get_ref<'a>(self) -> <#ident as Soars>::Ref<'a> { #ident #unit_construct }
+            unsafe fn get_ref<#lt>(self) -> <#item as ::soa_rs::Soars>::Ref<#lt> {
+                #ident #unit_construct
+            }
 
             #[inline]
-            unsafe fn get_mut<'a>(self) -> <#ident as Soars>::RefMut<'a> { #ident #unit_construct }
+            unsafe fn get_mut<#lt>(self) -> <#item as ::soa_rs::Soars>::RefMut<#lt> {
+                #ident #unit_construct
+            }
 
             #[inline]
             unsafe fn offset(self, count: usize) -> Self { Self }
 
             #[inline]
-            unsafe fn slices<'a>(self, len: usize) -> <#ident as Soars>::Slices<'a> {
+            unsafe fn slices<#lt>(self, len: usize) -> <#item as ::soa_rs::Soars>::Slices<#lt> {
+                #ident #unit_construct
+            }
+
+            #[inline]
+            unsafe fn slices_mut<#lt>(self, len: usize) -> <#item as ::soa_rs::Soars>::SlicesMut<#lt> {
+                #ident #unit_construct
+            }
+
+            #[inline]
+            unsafe fn chunk<#lt, const #n: usize>(self) -> <#item as ::soa_rs::Soars>::Chunk<#lt, #n> {
                 #ident #unit_construct
             }
 
             #[inline]
-            unsafe fn slices_mut<'a>(self, len: usize) -> <#ident as Soars>::SlicesMut<'a> {
+            unsafe fn chunk_mut<#lt, const #n: usize>(self) -> <#item as ::soa_rs::Soars>::ChunkMut<#lt, #n> {
                 #ident #unit_construct
             }
         }
@@ -0,0 +1,106 @@
+//! Checks that soa-rs and the code generated by its derive macro build without
+//! the standard library.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use soa_rs::{soa, Soa, Soars, TryReserveError};
+
+/// A reading from a sensor.
+#[derive(Soars, Clone, Copy, Debug, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+pub struct Sample {
+    /// When the sample was taken.
+    pub timestamp: u32,
+    /// The measured value.
+    pub value: f32,
+}
+
+/// A sample tagged with the sensor that took it.
+#[derive(Soars, Clone, Copy, Debug, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+pub struct Tagged<const N: usize> {
+    /// The sensor that took the sample.
+    pub sensor: [u8; N],
+    /// The sample.
+    #[soa(nested)]
+    pub sample: Sample,
+}
+
+/// An event on the sensor bus.
+#[derive(Soars, Clone, Debug, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+pub enum Event {
+    /// A new sample arrived.
+    Sample(Sample),
+    /// The sensor reported an error code.
+    Error {
+        /// The reported error code.
+        code: u16,
+    },
+    /// The bus was reset.
+    Reset,
+}
+
+/// A marker type without fields.
+#[derive(Soars, Clone, Copy, Debug, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+pub struct Marker;
+
+/// Returns the mean of the sample values.
+pub fn mean(samples: &Soa<Sample>) -> f32 {
+    samples.value().iter().sum::<f32>() / samples.len() as f32
+}
+
+/// Appends a batch of samples, rejecting it if it doesn't fit in memory.
+pub fn record(samples: &mut Soa<Sample>, batch: &[Sample]) -> Result<(), TryReserveError> {
+    samples.try_reserve(batch.len())?;
+    samples.extend(batch.iter().copied());
+    Ok(())
+}
+
+/// Exercises the container with each kind of derived type.
+pub fn exercise() -> Vec<u32> {
+    let first = Sample {
+        timestamp: 0,
+        value: 1.0,
+    };
+    let second = Sample {
+        timestamp: 1,
+        value: 3.0,
+    };
+    let mut samples = soa![first];
+    let _ = record(&mut samples, &[second]);
+    samples.retain(|sample| *sample.value > 0.0);
+    let _ = mean(&samples);
+
+    let mut tagged: Soa<Tagged<2>> = Soa::new();
+    tagged.push(Tagged {
+        sensor: [1, 2],
+        sample: first,
+    });
+    let _ = tagged.clone();
+
+    let mut events: Soa<Event> = Soa::new();
+    events.push(Event::Sample(second));
+    events.push(Event::Error { code: 3 });
+    events.push(Event::Reset);
+    events.drain(1..);
+
+    let mut markers = Soa::<Marker>::new();
+    markers.push(Marker);
+
+    samples.into_iter().map(|sample| sample.timestamp).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exercise_without_std() {
+        assert_eq!(exercise(), [0, 1]);
+    }
+}
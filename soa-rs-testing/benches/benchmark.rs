@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::{rngs::StdRng, RngCore, SeedableRng};
-use soa_rs::{Soa, Soars};
+use soa_rs::{AoSoa, Soa, Soars};
 
 struct Rng(StdRng);
 
@@ -90,6 +90,28 @@ fn criterion_benchmark(c: &mut Criterion) {
         })
     });
 
+    let aosoa1: AoSoa<_, 8> = soa1.iter().map(|v| Vec4(*v.0, *v.1, *v.2, *v.3)).collect();
+    let aosoa2: AoSoa<_, 8> = soa2.iter().map(|v| Vec4(*v.0, *v.1, *v.2, *v.3)).collect();
+    c.bench_function("aosoa", |b| {
+        b.iter(|| {
+            aosoa1
+                .blocks()
+                .iter()
+                .zip(aosoa2.blocks())
+                .fold([0.; 8], |acc, (a, b)| {
+                    std::array::from_fn(|i| {
+                        acc[i]
+                            + a.0[i] * b.0[i]
+                            + a.1[i] * b.1[i]
+                            + a.2[i] * b.2[i]
+                            + a.3[i] * b.3[i]
+                    })
+                })
+                .into_iter()
+                .sum::<f32>()
+        })
+    });
+
     #[rustfmt::skip]
     c.bench_function("chunked-vec", |b| {
         b.iter(|| {
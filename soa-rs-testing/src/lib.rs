@@ -1,8 +1,14 @@
 #![cfg(test)]
 
-use soa_rs::{soa, AsSoaRef, Soa, SoaArray, Soars};
+use soa_rs::{
+    soa, AoSoa, ArraySoa, AsSoaRef, SmallSoa, Soa, SoaArray, SoaDeque, SoaSlotMap, Soars,
+};
 use std::fmt::Debug;
 
+#[cfg(feature = "serde")]
+mod serde_tests;
+
+#[allow(dead_code)]
 #[derive(Soars, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[soa_derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct ExtraImplTester {
@@ -212,7 +218,7 @@ pub fn from_iter() {
 #[test]
 pub fn iter() {
     let soa: Soa<_> = ABCDE.into();
-    for (borrowed, owned) in soa.iter().zip(ABCDE.into_iter()) {
+    for (borrowed, owned) in soa.iter().zip(ABCDE) {
         assert_eq!(borrowed.foo, &owned.foo);
         assert_eq!(borrowed.bar, &owned.bar);
         assert_eq!(borrowed.baz, &owned.baz);
@@ -226,7 +232,7 @@ pub fn iter_mut() {
         *el.foo += 1;
         *el.bar += 2;
     }
-    for (borrowed, owned) in soa.iter().zip(ABCDE.into_iter()) {
+    for (borrowed, owned) in soa.iter().zip(ABCDE) {
         assert_eq!(borrowed.foo, &(owned.foo + 1));
         assert_eq!(borrowed.bar, &(owned.bar + 2));
     }
@@ -260,7 +266,7 @@ pub fn clone() {
 
 #[test]
 pub fn clone_from() {
-    let mut dst: Soa<_> = std::iter::repeat(Tuple(100, 100, 100)).take(7).collect();
+    let mut dst: Soa<_> = std::iter::repeat_n(Tuple(100, 100, 100), 7).collect();
     let src: Soa<_> = [Tuple(1, 2, 3), Tuple(4, 5, 6), Tuple(7, 8, 9)].into();
     dst.clone_from(&src);
     assert_eq!(dst, src);
@@ -609,6 +615,1532 @@ fn array_with_box() {
     let foo = Box::new(42_u8);
     let x = ExampleArray::from_array([Example { foo }]);
     let s = x.as_slice();
+    #[allow(clippy::borrowed_box)]
     let v: &Box<u8> = s.get(0).unwrap().foo;
     dbg!(v);
 }
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+struct Generic<F>
+where
+    F: Copy + std::ops::Add<Output = F>,
+{
+    pos: F,
+    vel: F,
+}
+
+#[test]
+fn generic() {
+    let mut soa: Soa<Generic<f32>> = Soa::new();
+    soa.push(Generic { pos: 1.0, vel: 2.0 });
+    soa.push(Generic { pos: 3.0, vel: 4.0 });
+    for el in soa.iter_mut() {
+        *el.pos += *el.vel;
+    }
+    assert_eq!(soa.pos(), [3.0, 7.0]);
+    assert_eq!(soa.vel(), [2.0, 4.0]);
+    assert_eq!(soa.idx(1), Generic { pos: 7.0, vel: 4.0 });
+    assert_eq!(soa.pop(), Some(Generic { pos: 7.0, vel: 4.0 }));
+}
+
+#[derive(Soars, Debug, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+struct GenericParams<'a, T: Debug + PartialEq, const N: usize>(&'a str, [T; N]);
+
+#[test]
+fn generic_params() {
+    let s = String::from("hello");
+    let mut soa = Soa::with(GenericParams(&s[..1], [1u8, 2]));
+    soa.push(GenericParams(&s[1..], [3, 4]));
+    assert_eq!(soa.f0(), ["h", "ello"]);
+    assert_eq!(soa.f1(), [[1, 2], [3, 4]]);
+    assert_eq!(soa.idx(0), GenericParams("h", [1, 2]));
+    assert_eq!(soa.pop(), Some(GenericParams("ello", [3, 4])));
+}
+
+#[test]
+fn generic_array() {
+    const ARRAY: GenericArray<u16, 2> =
+        GenericArray::from_array([Generic { pos: 1, vel: 2 }, Generic { pos: 3, vel: 4 }]);
+    assert_eq!(ARRAY.pos, [1, 3]);
+    assert_eq!(
+        ARRAY.as_slice(),
+        [Generic { pos: 1, vel: 2 }, Generic { pos: 3, vel: 4 }]
+    );
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+struct GenericUnit<const N: usize>;
+
+#[test]
+fn generic_unit() {
+    let mut soa = Soa::<GenericUnit<3>>::new();
+    soa.push(GenericUnit);
+    soa.push(GenericUnit);
+    assert_eq!(soa.len(), 2);
+    assert_eq!(soa.pop(), Some(GenericUnit));
+}
+
+#[derive(Soars, Debug, Clone, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+enum Event {
+    Click { x: i32, y: i32 },
+    Key(char),
+    Drop(SingleDrop),
+    Quit,
+}
+
+#[test]
+fn enum_push_get() {
+    let mut soa = Soa::<Event>::new();
+    soa.push(Event::Click { x: 1, y: 2 });
+    soa.push(Event::Key('a'));
+    soa.push(Event::Quit);
+    soa.push(Event::Drop(SingleDrop::DEFAULT));
+    assert_eq!(
+        soa.tags(),
+        [
+            EventTag::Click,
+            EventTag::Key,
+            EventTag::Quit,
+            EventTag::Drop
+        ]
+    );
+    assert_eq!(soa.idx(0), Event::Click { x: 1, y: 2 });
+    assert_eq!(soa.idx(1), Event::Key('a'));
+    assert_eq!(soa.idx(2), Event::Quit);
+    assert_eq!(soa.pop(), Some(Event::Drop(SingleDrop::DEFAULT)));
+    assert_eq!(soa.remove(0), Event::Click { x: 1, y: 2 });
+    assert_eq!(soa, [Event::Key('a'), Event::Quit]);
+}
+
+#[test]
+fn enum_ref_accessors() {
+    let mut soa = soa![Event::Click { x: 1, y: 2 }, Event::Key('a'), Event::Quit];
+    let el = soa.idx(0);
+    assert_eq!(el.tag(), EventTag::Click);
+    assert!(el.is_click());
+    assert!(!el.is_quit());
+    assert_eq!(el.as_click(), Some((&1, &2)));
+    assert_eq!(el.as_key(), None);
+    assert_eq!(soa.idx(1).as_key(), Some(&'a'));
+    assert!(soa.idx(2).is_quit());
+
+    if let Some((x, _)) = soa.idx_mut(0).as_click() {
+        *x += 10;
+    }
+    if let Some(key) = soa.idx_mut(1).into_key() {
+        *key = 'b';
+    }
+    assert_eq!(soa.idx_mut(2).as_click(), None);
+    assert_eq!(
+        soa,
+        [Event::Click { x: 11, y: 2 }, Event::Key('b'), Event::Quit]
+    );
+
+    match soa.idx(0) {
+        EventRef::Click { x, y } => assert_eq!((*x, *y), (11, 2)),
+        _ => panic!("expected a click"),
+    }
+}
+
+#[test]
+fn enum_variant_slices() {
+    let mut soa = soa![
+        Event::Click { x: 1, y: 2 },
+        Event::Click { x: 3, y: 4 },
+        Event::Key('a'),
+    ];
+    assert_eq!(soa.as_click(), None);
+    assert_eq!(soa.as_key(), None);
+
+    let mut clicks = soa.get_mut(..2).unwrap();
+    let (x, y) = clicks.as_click_mut().unwrap();
+    x[0] += 10;
+    y[1] += 10;
+    assert_eq!(
+        soa.get(..2).unwrap().as_click(),
+        Some(([11, 3].as_slice(), [2, 14].as_slice()))
+    );
+    assert_eq!(soa.get(2..).unwrap().as_key(), Some(['a'].as_slice()));
+    assert_eq!(soa.get(2..2).unwrap().as_key(), Some([].as_slice()));
+}
+
+#[test]
+fn enum_slices() {
+    let soa = soa![Event::Click { x: 1, y: 2 }, Event::Key('a')];
+    let slices = soa.slices();
+    assert_eq!(slices.tag, soa.tags());
+    assert_eq!(unsafe { slices.click_x[0].assume_init() }, 1);
+    assert_eq!(unsafe { slices.key_0[1].assume_init() }, 'a');
+}
+
+#[test]
+fn enum_array() {
+    const ARRAY: EventArray<2> = EventArray::from_array([Event::Key('a'), Event::Quit]);
+    assert_eq!(ARRAY.as_slice(), [Event::Key('a'), Event::Quit]);
+
+    let array = EventArray::from_array([
+        Event::Drop(SingleDrop::DEFAULT),
+        Event::Quit,
+        Event::Drop(SingleDrop::DEFAULT),
+    ]);
+    assert_eq!(
+        array.as_slice().tags(),
+        [EventTag::Drop, EventTag::Quit, EventTag::Drop]
+    );
+    // Dropping the array drops each active field exactly once
+    drop(array);
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+enum Direction {
+    Up,
+    Down,
+}
+
+#[test]
+fn enum_unit() {
+    let mut soa = soa![Direction::Up, Direction::Down];
+    assert_eq!(soa.tags(), [DirectionTag::Up, DirectionTag::Down]);
+    assert!(soa.idx(0).is_up());
+    assert_eq!(soa.pop(), Some(Direction::Down));
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+enum GenericEnum<T: Copy> {
+    Some(T),
+    None,
+}
+
+#[test]
+fn enum_generic() {
+    let soa = soa![GenericEnum::Some(1u8), GenericEnum::None];
+    assert_eq!(soa.idx(0).as_some(), Some(&1));
+    assert_eq!(soa.idx(1), GenericEnum::None);
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+struct Vec3 {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+struct Particle {
+    #[soa(nested)]
+    pos: Vec3,
+    #[soa(nested)]
+    vel: Vec3,
+    mass: u8,
+}
+
+const P0: Particle = Particle {
+    pos: Vec3 {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    },
+    vel: Vec3 {
+        x: 4.0,
+        y: 5.0,
+        z: 6.0,
+    },
+    mass: 7,
+};
+
+const P1: Particle = Particle {
+    pos: Vec3 {
+        x: 8.0,
+        y: 9.0,
+        z: 10.0,
+    },
+    vel: Vec3 {
+        x: 11.0,
+        y: 12.0,
+        z: 13.0,
+    },
+    mass: 14,
+};
+
+#[test]
+fn nested_push_get() {
+    let mut soa = Soa::<Particle>::new();
+    for _ in 0..10 {
+        soa.push(P0);
+        soa.push(P1);
+    }
+    assert_eq!(soa.len(), 20);
+    assert_eq!(soa.idx(0), P0);
+    assert_eq!(soa.idx(19), P1);
+    assert_eq!(soa.pos().x(), [1.0, 8.0].repeat(10));
+    assert_eq!(soa.vel().z(), [6.0, 13.0].repeat(10));
+    assert_eq!(soa.mass(), [7, 14].repeat(10));
+
+    soa.truncate(2);
+    soa.shrink_to_fit();
+    assert_eq!(soa, [P0, P1]);
+    assert_eq!(soa.remove(0), P0);
+    assert_eq!(soa.pop(), Some(P1));
+}
+
+#[test]
+fn nested_refs() {
+    let mut soa = soa![P0, P1];
+    let el = soa.idx(1);
+    assert_eq!(
+        el.pos,
+        Vec3 {
+            x: 8.0,
+            y: 9.0,
+            z: 10.0
+        }
+    );
+    assert_eq!(*el.vel.y, 12.0);
+
+    let el = soa.idx_mut(0);
+    *el.pos.x += 10.0;
+    *el.mass += 1;
+    for x in soa.vel_mut().x_mut() {
+        *x = 0.0;
+    }
+    assert_eq!(soa.pos().x(), [11.0, 8.0]);
+    assert_eq!(soa.vel().x(), [0.0, 0.0]);
+    assert_eq!(soa.mass(), [8, 14]);
+}
+
+#[test]
+fn nested_slices() {
+    let mut soa = soa![P0, P1];
+    let slices = soa.slices();
+    assert_eq!(slices.pos.y, [2.0, 9.0]);
+    assert_eq!(slices.mass, [7, 14]);
+
+    let slices = soa.slices_mut();
+    slices.vel.y[1] = 0.0;
+    assert_eq!(soa.vel().y(), [5.0, 0.0]);
+}
+
+#[test]
+fn nested_array() {
+    const ARRAY: ParticleArray<2> = ParticleArray::from_array([P0, P1]);
+    assert_eq!(ARRAY.pos.as_slice().x(), [1.0, 8.0]);
+    assert_eq!(ARRAY.as_slice(), [P0, P1]);
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+struct NestedGeneric<F: Copy + std::ops::Add<Output = F>>(
+    #[soa(nested)] GenericUnit<2>,
+    #[soa(nested)] Generic<F>,
+    u8,
+);
+
+#[test]
+fn nested_generic() {
+    let mut soa = Soa::<NestedGeneric<u16>>::new();
+    for i in 0..10 {
+        soa.push(NestedGeneric(
+            GenericUnit,
+            Generic { pos: i, vel: i + 1 },
+            3,
+        ));
+    }
+    soa.shrink_to(5);
+    assert_eq!(soa.f1().pos(), (0..10).collect::<Vec<_>>());
+    assert_eq!(
+        soa.idx(2),
+        NestedGeneric(GenericUnit, Generic { pos: 2, vel: 3 }, 3)
+    );
+}
+
+#[derive(Soars, Debug, Clone, PartialEq)]
+#[soa_derive(Debug, PartialEq)]
+struct Owned {
+    name: String,
+    shared: std::sync::Arc<u8>,
+}
+
+#[test]
+fn clone_owned() {
+    let shared = std::sync::Arc::new(5);
+    let mut soa = Soa::<Owned>::new();
+    for name in ["a", "b", "c"] {
+        soa.push(Owned {
+            name: name.to_string(),
+            shared: shared.clone(),
+        });
+    }
+    let cloned = soa.clone();
+    assert_eq!(cloned, soa);
+    assert_eq!(std::sync::Arc::strong_count(&shared), 7);
+
+    let mut other = soa![Owned {
+        name: "d".to_string(),
+        shared: shared.clone(),
+    }];
+    other.clone_from(&soa);
+    assert_eq!(other, soa);
+    drop((soa, cloned, other));
+    assert_eq!(std::sync::Arc::strong_count(&shared), 1);
+}
+
+#[test]
+fn clone_enum_and_nested() {
+    let soa = soa![
+        Event::Key('a'),
+        Event::Drop(SingleDrop::DEFAULT),
+        Event::Quit
+    ];
+    assert_eq!(soa.clone(), soa);
+
+    let soa = soa![P0, P1];
+    assert_eq!(soa.clone(), soa);
+}
+
+// Deriving Soars doesn't require the fields to implement Clone
+#[allow(dead_code)]
+struct NotClone;
+
+#[allow(dead_code)]
+#[derive(Soars)]
+struct WithNotClone(u8, NotClone);
+
+#[test]
+fn retain() {
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.retain(|el| *el.bar != 5 && *el.bar != 13);
+    assert_eq!(soa, [A, C, E]);
+
+    soa.retain(|_| true);
+    assert_eq!(soa, [A, C, E]);
+
+    soa.retain(|_| false);
+    assert_eq!(soa, []);
+}
+
+#[test]
+fn retain_mut() {
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.retain_mut(|el| {
+        *el.foo += 1;
+        *el.bar % 2 == 1
+    });
+    assert_eq!(soa.foo(), [1, 5, 9, 13, 17]);
+
+    soa.retain_mut(|el| *el.foo > 5);
+    assert_eq!(soa.foo(), [9, 13, 17]);
+    assert_eq!(soa.bar(), [9, 13, 17]);
+}
+
+#[test]
+fn retain_panic() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        soa.retain(|el| match *el.foo {
+            4 => false,
+            12 => panic!(),
+            _ => true,
+        });
+    }));
+    assert!(result.is_err());
+    // B was removed, D and E weren't visited and remain
+    assert_eq!(soa, [A, C, D, E]);
+}
+
+#[test]
+fn extract_if() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let extracted: Soa<El> = soa.extract_if(.., |el| *el.foo % 8 == 0).collect();
+    assert_eq!(extracted, [A, C, E]);
+    assert_eq!(soa, [B, D]);
+
+    let mut soa: Soa<El> = ABCDE.into();
+    let extracted: Soa<El> = soa.extract_if(1..4, |_| true).collect();
+    assert_eq!(extracted, [B, C, D]);
+    assert_eq!(soa, [A, E]);
+}
+
+#[test]
+fn extract_if_partial() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let mut iter = soa.extract_if(1.., |el| *el.bar != 9);
+    assert_eq!(iter.size_hint(), (0, Some(4)));
+    assert_eq!(iter.next(), Some(B));
+    assert_eq!(iter.next(), Some(D));
+    assert_eq!(iter.size_hint(), (0, Some(1)));
+    drop(iter);
+    assert_eq!(soa, [A, C, E]);
+
+    let mut soa: Soa<El> = ABCDE.into();
+    std::mem::forget(soa.extract_if(.., |_| true));
+    assert_eq!(soa, []);
+}
+
+#[test]
+#[should_panic]
+fn extract_if_out_of_bounds() {
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.extract_if(..6, |_| true);
+}
+
+#[test]
+fn drain() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let mut drain = soa.drain(1..4);
+    assert_eq!(drain.len(), 3);
+    assert_eq!(drain.as_slice(), [B, C, D]);
+    assert_eq!(drain.next_back(), Some(D));
+    assert_eq!(drain.next(), Some(B));
+    drop(drain);
+    assert_eq!(soa, [A, E]);
+
+    let mut other = Soa::<El>::with_capacity(2);
+    other.extend(soa.drain(..));
+    assert_eq!(other, [A, E]);
+    assert_eq!(soa, []);
+    assert!(soa.capacity() >= 5);
+}
+
+#[test]
+fn drain_leak() {
+    let mut soa: Soa<El> = ABCDE.into();
+    std::mem::forget(soa.drain(2..3));
+    assert_eq!(soa, [A, B]);
+}
+
+#[test]
+fn drain_zst() {
+    let mut soa: Soa<Unit> = [Unit; 5].into();
+    assert_eq!(soa.drain(1..3).count(), 2);
+    assert_eq!(soa.len(), 3);
+}
+
+#[test]
+fn splice() {
+    // Fewer replacements than removals
+    let mut soa: Soa<El> = ABCDE.into();
+    let removed: Soa<El> = soa.splice(1..4, [E]).collect();
+    assert_eq!(removed, [B, C, D]);
+    assert_eq!(soa, [A, E, E]);
+
+    // No tail
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.splice(3.., [A, B, C]);
+    assert_eq!(soa, [A, B, C, A, B, C]);
+
+    // Exact size hint larger than the range
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.splice(1..2, [D, D, D]);
+    assert_eq!(soa, [A, D, D, D, C, D, E]);
+
+    // Size hint that underestimates the number of elements
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.splice(..1, ABCDE.into_iter().filter(|el| el.foo != 8));
+    assert_eq!(soa, [A, B, D, E, B, C, D, E]);
+}
+
+#[test]
+fn split_off() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let other = soa.split_off(2);
+    assert_eq!(soa, [A, B]);
+    assert_eq!(other, [C, D, E]);
+    assert_eq!(other.capacity(), 3);
+
+    let mut soa: Soa<El> = ABCDE.into();
+    assert_eq!(soa.split_off(5), []);
+    assert_eq!(soa.split_off(0), ABCDE);
+    assert_eq!(soa, []);
+}
+
+#[test]
+#[should_panic]
+fn split_off_out_of_bounds() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let _ = soa.split_off(6);
+}
+
+#[test]
+fn resize() {
+    let mut soa = Soa::<El>::new();
+    soa.resize(3, A);
+    assert_eq!(soa, [A, A, A]);
+    soa.resize(1, B);
+    assert_eq!(soa, [A]);
+    soa.resize(0, B);
+    assert_eq!(soa, []);
+
+    let mut soa = Soa::<Unit>::new();
+    soa.resize(3, Unit);
+    assert_eq!(soa.len(), 3);
+}
+
+#[test]
+fn resize_with() {
+    let mut soa: Soa<El> = [A].into();
+    let mut iter = ABCDE.into_iter().skip(1);
+    soa.resize_with(5, || iter.next().unwrap());
+    assert_eq!(soa, ABCDE);
+    soa.resize_with(2, || unreachable!());
+    assert_eq!(soa, [A, B]);
+}
+
+#[test]
+fn extend_from_slice() {
+    let mut soa: Soa<El> = [A].into();
+    let other: Soa<El> = ABCDE.into();
+    soa.extend_from_slice(&other.idx(1..3));
+    soa.extend_from_slice(&other);
+    assert_eq!(soa, [A, B, C, A, B, C, D, E]);
+}
+
+#[test]
+fn insert_many() {
+    let mut soa: Soa<El> = [A, E].into();
+    soa.insert_many(1, [B, C, D]);
+    assert_eq!(soa, ABCDE);
+    soa.insert_many(5, [A]);
+    soa.insert_many(0, []);
+    soa.insert_many(0, [E].into_iter().filter(|_| true));
+    assert_eq!(soa, [E, A, B, C, D, E, A]);
+    // Size hint that underestimates by more than one doubling of the gap
+    soa.insert_many(6, ABCDE.into_iter().filter(|_| true));
+    assert_eq!(soa, [E, A, B, C, D, E, A, B, C, D, E, A]);
+}
+
+#[test]
+fn insert_many_panic() {
+    let panic_at_d = |el: El| if el == D { panic!() } else { el };
+
+    let mut soa: Soa<El> = [A, E].into();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        soa.insert_many(1, [B, C, D].into_iter().map(panic_at_d));
+    }));
+    assert!(result.is_err());
+    // The elements yielded before the panic are inserted, then the tail
+    assert_eq!(soa, [A, B, C, E]);
+
+    let mut soa: Soa<El> = [A, E].into();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let iter = [B, C, D].into_iter().filter(|_| true).map(panic_at_d);
+        soa.insert_many(1, iter);
+    }));
+    assert!(result.is_err());
+    assert_eq!(soa, [A, B, C, E]);
+}
+
+#[test]
+fn try_reserve() {
+    let mut soa: Soa<El> = ABCDE.into();
+    assert!(soa.try_reserve(10).is_ok());
+    assert!(soa.capacity() >= 15);
+    assert!(soa.try_reserve_exact(20).is_ok());
+    assert_eq!(soa.capacity(), 25);
+
+    let capacity = soa.capacity();
+    for additional in [usize::MAX, usize::MAX - 5, isize::MAX as usize] {
+        let err = soa.try_reserve(additional).unwrap_err();
+        assert_eq!(err.kind(), soa_rs::TryReserveErrorKind::CapacityOverflow);
+        assert!(soa.try_reserve_exact(additional).is_err());
+    }
+    assert_eq!(soa.capacity(), capacity);
+    assert_eq!(soa, ABCDE);
+
+    let mut soa = Soa::<Unit>::new();
+    assert!(soa.try_reserve(usize::MAX).is_ok());
+    assert_eq!(soa.capacity(), usize::MAX);
+}
+
+#[test]
+fn try_with_capacity() {
+    let soa = Soa::<El>::try_with_capacity(5).unwrap();
+    assert_eq!(soa.capacity(), 5);
+    assert!(Soa::<El>::try_with_capacity(usize::MAX / 2).is_err());
+    assert_eq!(
+        Soa::<Unit>::try_with_capacity(5).unwrap().capacity(),
+        usize::MAX
+    );
+}
+
+#[test]
+fn try_push() {
+    let mut soa = Soa::<El>::new();
+    for el in ABCDE {
+        soa.try_push(el).unwrap();
+    }
+    assert_eq!(soa, ABCDE);
+}
+
+/// Tracks the number of live allocations made through it.
+#[derive(Default)]
+struct CountingAlloc {
+    live: std::cell::Cell<isize>,
+    total: std::cell::Cell<usize>,
+}
+
+unsafe impl soa_rs::Allocator for CountingAlloc {
+    fn allocate(
+        &self,
+        layout: std::alloc::Layout,
+    ) -> Result<std::ptr::NonNull<[u8]>, soa_rs::AllocError> {
+        self.live.set(self.live.get() + 1);
+        self.total.set(self.total.get() + 1);
+        soa_rs::Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: std::ptr::NonNull<u8>, layout: std::alloc::Layout) {
+        self.live.set(self.live.get() - 1);
+        unsafe { soa_rs::Global.deallocate(ptr, layout) }
+    }
+}
+
+#[test]
+fn custom_allocator() {
+    let alloc = CountingAlloc::default();
+    let mut soa = Soa::<El, _>::new_in(&alloc);
+    assert_eq!(alloc.total.get(), 0);
+    soa.extend(ABCDE);
+    assert_eq!(soa, ABCDE);
+    // Growing uses the default allocate, copy, deallocate path
+    assert!(alloc.total.get() > 1);
+    assert_eq!(alloc.live.get(), 1);
+
+    soa.shrink_to_fit();
+    assert_eq!(soa.capacity(), 5);
+    assert_eq!(alloc.live.get(), 1);
+
+    let mut cloned = soa.clone();
+    let tail = cloned.split_off(2);
+    assert_eq!(alloc.live.get(), 3);
+    assert_eq!(cloned, [A, B]);
+    assert_eq!(tail, [C, D, E]);
+    drop((cloned, tail));
+    assert_eq!(alloc.live.get(), 1);
+
+    assert_eq!(soa.drain(1..4).count(), 3);
+    assert_eq!(soa, [A, E]);
+
+    // Inserting and splicing grow in place without a temporary buffer
+    soa.reserve(8);
+    let total = alloc.total.get();
+    soa.insert_many(1, [B, C, D]);
+    assert_eq!(soa, ABCDE);
+    soa.splice(1..2, ABCDE.into_iter().filter(|el| *el != A));
+    assert_eq!(soa, [A, B, C, D, E, C, D, E]);
+    assert_eq!(alloc.total.get(), total);
+    soa.splice(1..7, []);
+    assert_eq!(soa, [A, E]);
+    assert_eq!(alloc.live.get(), 1);
+
+    let mut iter = soa.into_iter();
+    assert_eq!(iter.next(), Some(A));
+    drop(iter);
+    assert_eq!(alloc.live.get(), 0);
+
+    let soa = Soa::<El, _>::with_capacity_in(3, &alloc);
+    assert_eq!(soa.capacity(), 3);
+    assert_eq!(alloc.live.get(), 1);
+    drop(soa);
+    assert_eq!(alloc.live.get(), 0);
+
+    // Zero-sized types never allocate
+    let total = alloc.total.get();
+    let soa = Soa::<Unit, _>::with_capacity_in(3, &alloc);
+    assert_eq!(soa.capacity(), usize::MAX);
+    assert_eq!(alloc.total.get(), total);
+}
+
+#[test]
+fn allocator_failure() {
+    struct Exhausted;
+
+    unsafe impl soa_rs::Allocator for Exhausted {
+        fn allocate(
+            &self,
+            _: std::alloc::Layout,
+        ) -> Result<std::ptr::NonNull<[u8]>, soa_rs::AllocError> {
+            Err(soa_rs::AllocError)
+        }
+
+        unsafe fn deallocate(&self, _: std::ptr::NonNull<u8>, _: std::alloc::Layout) {
+            unreachable!()
+        }
+    }
+
+    let mut soa = Soa::<El, _>::new_in(Exhausted);
+    let err = soa.try_push(A).unwrap_err();
+    assert!(matches!(
+        err.error().kind(),
+        soa_rs::TryReserveErrorKind::AllocError { .. }
+    ));
+    assert_eq!(err.into_element(), A);
+    assert!(Soa::<El, _>::try_with_capacity_in(4, Exhausted).is_err());
+    assert_eq!(soa, []);
+}
+
+#[test]
+fn array_soa() {
+    let mut soa = ArraySoa::<El, 4>::new();
+    soa.push(A);
+    soa.push(B);
+    soa.insert(0, C);
+    assert_eq!(soa, [C, A, B]);
+    assert_eq!(soa.foo(), [8, 0, 4]);
+    soa.push(D);
+    assert!(soa.is_full());
+    assert_eq!(soa.try_push(E), Err(E));
+    assert_eq!(soa.remove(1), A);
+    assert_eq!(soa.swap_remove(0), C);
+    assert_eq!(soa, [D, B]);
+    assert_eq!(soa.pop(), Some(B));
+    assert_eq!(soa.pop(), Some(D));
+    assert_eq!(soa.pop(), None);
+}
+
+#[test]
+fn array_soa_move() {
+    fn make() -> ArraySoa<El, 5> {
+        let soa: ArraySoa<El, 5> = ABCDE.into_iter().collect();
+        // Cache pointers that go stale when returning
+        assert_eq!(soa.bar(), [1, 5, 9, 13, 17]);
+        soa
+    }
+
+    let mut soa = make();
+    assert_eq!(soa, ABCDE);
+    let mut boxed = Box::new(soa.clone());
+    soa.foo_mut()[0] = 20;
+    assert_eq!(soa.foo(), [20, 4, 8, 12, 16]);
+    boxed.truncate(2);
+    assert_eq!(*boxed, [A, B]);
+    let moved = *boxed;
+    assert_eq!(moved, [A, B]);
+    assert_eq!(moved.iter().count(), 2);
+}
+
+#[test]
+fn array_soa_mutate_then_read() {
+    // Mutable access must refresh the cached pointers even though their
+    // addresses stay the same. Run under Miri to check.
+    let mut soa = ArraySoa::<El, 4>::new();
+    soa.push(A);
+    assert_eq!(soa.foo(), [0]);
+    soa.foo_mut()[0] = 5;
+    soa.push(B);
+    assert_eq!(soa.foo(), [5, 4]);
+    soa.bar_mut()[1] = 6;
+    assert_eq!(soa.bar(), [1, 6]);
+    soa.insert(0, C);
+    assert_eq!(soa.foo(), [8, 5, 4]);
+    assert_eq!(soa.as_slice().bar(), [9, 1, 6]);
+    soa.pop();
+    assert_eq!(soa.bar(), [9, 1]);
+}
+
+#[test]
+fn array_soa_retain() {
+    let mut soa: ArraySoa<El, 5> = ABCDE.into_iter().collect();
+    soa.retain(|el| el.foo % 8 == 0);
+    assert_eq!(soa, [A, C, E]);
+    soa.retain_mut(|el| {
+        *el.bar += 1;
+        *el.foo > 0
+    });
+    assert_eq!(soa.bar(), [10, 18]);
+}
+
+#[test]
+fn array_soa_nested_and_enum() {
+    let mut particles = ArraySoa::<Particle, 2>::new();
+    particles.push(P0);
+    particles.push(P1);
+    assert_eq!(particles.pos().x(), [1.0, 8.0]);
+    assert_eq!(particles.vel().z(), [6.0, 13.0]);
+    assert_eq!(particles.pop(), Some(P1));
+
+    let mut events = ArraySoa::<Event, 3>::new();
+    events.push(Event::Key('a'));
+    events.push(Event::Drop(SingleDrop::DEFAULT));
+    events.insert(0, Event::Quit);
+    assert_eq!(
+        events.tags(),
+        [EventTag::Quit, EventTag::Key, EventTag::Drop]
+    );
+    assert_eq!(events.remove(1), Event::Key('a'));
+
+    let mut units = ArraySoa::<Unit, 3>::new();
+    units.extend([Unit, Unit, Unit]);
+    assert_eq!(units, [Unit; 3]);
+}
+
+#[test]
+fn small_soa_spill() {
+    let mut soa = SmallSoa::<El, 2>::new();
+    soa.push(A);
+    soa.insert(0, B);
+    assert!(!soa.spilled());
+    assert_eq!(soa, [B, A]);
+    soa.push(C);
+    assert!(soa.spilled());
+    assert!(soa.capacity() >= 4);
+    assert_eq!(soa, [B, A, C]);
+    assert_eq!(soa.bar(), [5, 1, 9]);
+    soa.extend([D, E]);
+    assert_eq!(soa.remove(0), B);
+    soa.retain(|el| *el.foo != 8);
+    assert_eq!(soa, [A, D, E]);
+    soa.truncate(2);
+    soa.shrink_to_fit();
+    assert!(!soa.spilled());
+    assert_eq!(soa, [A, D]);
+    assert_eq!(soa.clone().into_soa(), [A, D]);
+}
+
+#[test]
+fn small_soa_mutate_then_read_inline() {
+    // Inline reads go through the ArraySoa slice cache. Run under Miri to
+    // check that mutations before spilling keep it valid.
+    let mut soa = SmallSoa::<El, 3>::new();
+    soa.push(A);
+    assert_eq!(soa.foo(), [0]);
+    soa.foo_mut()[0] = 5;
+    soa.push(B);
+    assert_eq!(soa.foo(), [5, 4]);
+    soa.bar_mut()[1] = 6;
+    soa.insert(0, C);
+    assert!(!soa.spilled());
+    assert_eq!(soa.bar(), [9, 1, 6]);
+    soa.push(D);
+    assert!(soa.spilled());
+    assert_eq!(soa.foo(), [8, 5, 4, 12]);
+}
+
+#[test]
+fn small_soa_move() {
+    let soa: SmallSoa<El, 8> = ABCDE.into_iter().collect();
+    assert!(!soa.spilled());
+    let soa = Box::new(soa);
+    assert_eq!(*soa, ABCDE);
+    let mut soa = *soa;
+    soa.foo_mut()[4] = 0;
+    assert_eq!(soa.foo(), [0, 4, 8, 12, 0]);
+    assert_eq!(soa.swap_remove(0), A);
+}
+
+#[test]
+fn small_soa_with_capacity() {
+    let soa = SmallSoa::<Particle, 2>::with_capacity(2);
+    assert!(!soa.spilled());
+    let mut soa = SmallSoa::<Particle, 2>::with_capacity(3);
+    assert!(soa.spilled());
+    soa.push(P0);
+    soa.push(P1);
+    assert_eq!(soa.pos().y(), [2.0, 9.0]);
+    let soa: SmallSoa<Particle, 2> = Soa::from([P1]).into();
+    assert!(soa.spilled());
+    assert_eq!(soa, [P1]);
+}
+
+#[test]
+fn soa_deque_matches_vec_deque() {
+    use std::collections::VecDeque;
+
+    let mut deque = SoaDeque::<Tuple>::new();
+    let mut expected = VecDeque::new();
+    // A simple LCG keeps the sequence of operations deterministic
+    let mut state = 7u32;
+    for i in 0..500u32 {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        let element = Tuple(i as u8, i as u16, i);
+        match (state >> 16) % 6 {
+            0 | 1 => {
+                deque.push_back(element);
+                expected.push_back(element);
+            }
+            2 => {
+                deque.push_front(element);
+                expected.push_front(element);
+            }
+            3 => assert_eq!(deque.pop_front(), expected.pop_front()),
+            4 => assert_eq!(deque.pop_back(), expected.pop_back()),
+            _ => {
+                let (a, b) = deque.as_slices();
+                assert_eq!(a.len() + b.len(), expected.len());
+                if (state >> 8).is_multiple_of(4) {
+                    let contiguous = deque.make_contiguous();
+                    assert_eq!(
+                        contiguous.f2(),
+                        expected
+                            .make_contiguous()
+                            .iter()
+                            .map(|t| t.2)
+                            .collect::<Vec<_>>()
+                    );
+                }
+            }
+        }
+        assert_eq!(deque.len(), expected.len());
+        assert!(deque
+            .iter()
+            .map(|t| Tuple(*t.0, *t.1, *t.2))
+            .eq(expected.iter().cloned()));
+    }
+}
+
+#[test]
+fn soa_deque_wrapped() {
+    let mut deque = SoaDeque::<El>::with_capacity(4);
+    deque.extend([A, B, C]);
+    assert_eq!(deque.pop_front(), Some(A));
+    deque.push_back(D);
+    deque.push_back(E);
+    let (front, back) = deque.slices();
+    assert_eq!(front.foo, [4, 8, 12]);
+    assert_eq!(back.foo, [16]);
+    // Full, so the front has to be rotated into place
+    let mut full = deque.clone();
+    assert_eq!(full.make_contiguous(), [B, C, D, E]);
+    deque.push_front(A);
+    assert_eq!(deque.front().map(|el| *el.foo), Some(0));
+    assert_eq!(deque.back().map(|el| *el.foo), Some(16));
+    *deque.get_mut(1).unwrap().bar = 0;
+    let soa: Soa<El> = deque.clone().into();
+    assert_eq!(soa.bar(), [1, 0, 9, 13, 17]);
+    let deque = SoaDeque::from(soa);
+    assert_eq!(deque.len(), 5);
+}
+
+#[test]
+fn soa_deque_zst() {
+    let mut deque = SoaDeque::<Unit>::new();
+    deque.push_back(Unit);
+    deque.push_front(Unit);
+    assert_eq!(deque.capacity(), usize::MAX);
+    assert_eq!(deque.make_contiguous().len(), 2);
+    assert_eq!(deque.pop_back(), Some(Unit));
+    assert_eq!(deque.pop_front(), Some(Unit));
+    assert_eq!(deque.pop_front(), None);
+}
+
+#[test]
+fn soa_slot_map() {
+    let mut map = SoaSlotMap::<El>::new();
+    let keys: Vec<_> = ABCDE.into_iter().map(|el| map.insert(el)).collect();
+    assert_eq!(map.keys(), keys);
+    assert_eq!(map.remove(keys[1]), Some(B));
+    assert_eq!(map.remove(keys[1]), None);
+    assert_eq!(map.foo(), [0, 16, 8, 12]);
+    assert_eq!(map.get(keys[4]).map(|el| *el.bar), Some(17));
+    assert_eq!(map.index_of(keys[4]), Some(1));
+
+    // The slot is reused, but the old key stays invalid
+    let f = map.insert(B);
+    assert!(!map.contains_key(keys[1]));
+    assert_ne!(f, keys[1]);
+    assert_eq!(map.get(f).map(|el| *el.foo), Some(4));
+
+    map.retain(|_, el| *el.foo >= 8);
+    assert_eq!(map.len(), 3);
+    for key in [keys[2], keys[3], keys[4]] {
+        let index = map.index_of(key).unwrap();
+        assert_eq!(map.keys()[index], key);
+    }
+    let mut clone = map.clone();
+    clone.clear();
+    assert!(clone.is_empty());
+    assert!(!clone.contains_key(keys[2]));
+    assert_eq!(map.into_soa().len(), 3);
+}
+
+#[test]
+fn aosoa() {
+    let mut aosoa: AoSoa<El, 2> = ABCDE.into_iter().collect();
+    assert_eq!(aosoa.len(), 5);
+    assert_eq!(aosoa.blocks().len(), 2);
+    assert_eq!(aosoa.blocks()[1].foo, [8, 12]);
+    assert_eq!(aosoa.remainder(), [E]);
+    assert!(aosoa.iter().eq(ABCDE.iter().map(|el| el.as_soa_ref())));
+    assert_eq!(aosoa.get(5), None);
+
+    for mut block in aosoa
+        .blocks_mut()
+        .iter_mut()
+        .map(|block| block.as_mut_slice())
+    {
+        block.foo_mut().reverse();
+    }
+    assert_eq!(
+        aosoa.iter().map(|el| *el.foo).collect::<Vec<_>>(),
+        [4, 0, 12, 8, 16]
+    );
+
+    assert_eq!(aosoa.pop(), Some(E));
+    assert!(aosoa.remainder().is_empty());
+    let popped = aosoa.pop().unwrap();
+    assert_eq!((popped.foo, popped.bar), (8, 13));
+    assert_eq!(aosoa.remainder().foo(), [12]);
+
+    for el in aosoa.iter_mut() {
+        *el.bar += 1;
+    }
+    assert_eq!(
+        aosoa.iter().map(|el| *el.bar).collect::<Vec<_>>(),
+        [2, 6, 10]
+    );
+
+    let clone = aosoa.clone();
+    assert_eq!(clone, aosoa);
+    aosoa.clear();
+    assert!(aosoa.is_empty());
+    assert_eq!(clone.len(), 3);
+}
+
+#[test]
+fn aosoa_zst() {
+    let mut aosoa: AoSoa<Unit, 4> = std::iter::repeat_n(Unit, 6).collect();
+    assert_eq!(aosoa.blocks().len(), 1);
+    assert_eq!(aosoa.remainder().len(), 2);
+    assert_eq!(aosoa.pop(), Some(Unit));
+    assert_eq!(aosoa.iter().count(), 5);
+}
+
+#[test]
+fn chunks_lanes() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let mut chunks = soa.chunks_lanes::<2>();
+    assert_eq!(chunks.len(), 2);
+    let chunk = chunks.next().unwrap();
+    assert_eq!((chunk.foo, chunk.bar), (&[0, 4], &[1, 5]));
+    assert_eq!(chunks.next().unwrap().foo, &[8, 12]);
+    assert!(chunks.next().is_none());
+    assert_eq!(chunks.remainder(), [E]);
+    assert_eq!(soa.chunks_lanes::<8>().remainder().len(), 5);
+
+    let mut chunks = soa.chunks_lanes_mut::<2>();
+    for chunk in &mut chunks {
+        chunk.foo.swap(0, 1);
+    }
+    assert_eq!(chunks.into_remainder(), [E]);
+    assert_eq!(soa.foo(), [4, 0, 12, 8, 16]);
+}
+
+#[test]
+fn chunks_lanes_nested_and_enum() {
+    let soa: Soa<Particle> = [P0, P1, P0].into();
+    let mut chunks = soa.chunks_lanes::<2>();
+    let chunk = chunks.next().unwrap();
+    assert_eq!(chunk.pos.x, &[1.0, 8.0]);
+    assert_eq!(chunk.vel.z, &[6.0, 13.0]);
+    assert_eq!(chunk.mass, &[7, 14]);
+    let padded = chunks.remainder_padded(P1);
+    assert_eq!(padded.as_chunk().pos.y, &[2.0, 9.0]);
+    assert_eq!(padded.as_chunk().mass, &[7, 14]);
+
+    let soa: Soa<Event> = [Event::Key('a'), Event::Quit, Event::Key('b')].into();
+    let chunks = soa.chunks_lanes::<2>();
+    let padded = chunks.remainder_padded(Event::Quit);
+    assert_eq!(padded.as_chunk().tag, &[EventTag::Key, EventTag::Quit]);
+    assert_eq!(padded.as_slice(), [Event::Key('b'), Event::Quit]);
+}
+
+#[test]
+fn chunking_matches_slice() {
+    // Compares the chunks by their last field in both directions
+    macro_rules! check {
+        ($soa:ident.$method:ident($arg:expr), $vec:ident) => {
+            let to_vec = |s: &[Tuple]| s.iter().map(|t| t.2).collect::<Vec<_>>();
+            let actual: Vec<_> = $soa.$method($arg).map(|s| s.f2().to_vec()).collect();
+            let expected: Vec<_> = $vec.$method($arg).map(to_vec).collect();
+            assert_eq!(actual, expected);
+            let actual: Vec<_> = $soa.$method($arg).rev().map(|s| s.f2().to_vec()).collect();
+            let expected: Vec<_> = $vec.$method($arg).rev().map(to_vec).collect();
+            assert_eq!(actual, expected);
+        };
+    }
+
+    for len in 0..12u32 {
+        let vec: Vec<_> = (0..len).map(|i| Tuple(i as u8, i as u16, i)).collect();
+        let soa: Soa<_> = vec.iter().copied().collect();
+        for size in 1..6 {
+            check!(soa.chunks(size), vec);
+            check!(soa.chunks_exact(size), vec);
+            check!(soa.rchunks(size), vec);
+            check!(soa.rchunks_exact(size), vec);
+            check!(soa.windows(size), vec);
+            let runs = size as u32;
+            check!(soa.chunk_by(|a, b| a.2 / runs == b.2 / runs), vec);
+            assert_eq!(soa.chunks(size).len(), vec.chunks(size).len());
+            assert_eq!(soa.windows(size).len(), vec.windows(size).len());
+            assert_eq!(
+                soa.rchunks_exact(size).remainder().f2(),
+                vec.rchunks_exact(size)
+                    .remainder()
+                    .iter()
+                    .map(|t| t.2)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
+#[test]
+fn chunking_mut() {
+    let mut soa: Soa<El> = ABCDE.into();
+    for mut chunk in soa.chunks_mut(2).rev() {
+        chunk.foo_mut().reverse();
+    }
+    assert_eq!(soa.foo(), [4, 0, 12, 8, 16]);
+
+    let mut iter = soa.chunks_exact_mut(2);
+    assert_eq!(iter.len(), 2);
+    iter.next_back().unwrap().bar_mut().fill(0);
+    assert_eq!(iter.into_remainder(), [E]);
+    assert_eq!(soa.bar(), [1, 5, 0, 0, 17]);
+}
+
+#[test]
+fn split_and_disjoint() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let (left, right) = soa.split_at(2);
+    assert_eq!(left, [A, B]);
+    assert_eq!(right, [C, D, E]);
+    let (left, right) = soa.split_at(5);
+    assert_eq!(left.len(), 5);
+    assert!(right.is_empty());
+
+    let (mut left, mut right) = soa.split_at_mut(3);
+    left.foo_mut().fill(1);
+    right.foo_mut().fill(2);
+    assert_eq!(soa.foo(), [1, 1, 1, 2, 2]);
+
+    let (first, rest) = soa.split_first_mut().unwrap();
+    *first.bar = 0;
+    assert_eq!(rest.len(), 4);
+    let (last, rest) = soa.split_last().unwrap();
+    assert_eq!(*last.bar, 17);
+    assert_eq!(rest.bar(), [0, 5, 9, 13]);
+    assert!(Soa::<El>::new().split_first().is_none());
+    assert!(Soa::<El>::new().split_last_mut().is_none());
+
+    let [a, b] = soa.get_disjoint_mut([4, 1]).unwrap();
+    core::mem::swap(a.bar, b.bar);
+    assert_eq!(soa.bar(), [0, 17, 9, 13, 5]);
+    assert_eq!(
+        soa.get_disjoint_mut([0, 2, 0]).err(),
+        Some(soa_rs::GetDisjointMutError::OverlappingIndices)
+    );
+    assert_eq!(
+        soa.get_disjoint_mut([5]).err(),
+        Some(soa_rs::GetDisjointMutError::IndexOutOfBounds)
+    );
+    assert!(soa.get_disjoint_mut([]).unwrap().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "mid > len")]
+fn split_at_out_of_bounds() {
+    let soa: Soa<El> = ABCDE.into();
+    let _ = soa.split_at(6);
+}
+
+#[test]
+fn sort() {
+    let mut soa = soa![E, B, D, A, C];
+    soa.sort_by(|a, b| a.foo.cmp(b.foo));
+    assert_eq!(soa, ABCDE);
+    soa.sort_unstable_by(|a, b| b.bar.cmp(a.bar));
+    assert_eq!(soa, [E, D, C, B, A]);
+    soa.sort_by_key(|el| *el.bar);
+    assert_eq!(soa, ABCDE);
+    soa.sort_unstable_by_key(|el| std::cmp::Reverse(*el.foo));
+    assert_eq!(soa, [E, D, C, B, A]);
+
+    // Stable sorts keep equal elements in order
+    let mut soa: Soa<El> = [A, B, C, D, E, A, B, C, D, E].into();
+    soa.sort_by_key(|el| *el.foo % 8);
+    assert_eq!(soa, [A, C, E, A, C, E, B, D, B, D]);
+    let mut calls = 0;
+    soa.sort_by_cached_key(|el| {
+        calls += 1;
+        *el.foo / 8
+    });
+    assert_eq!(calls, 10);
+    assert_eq!(soa, [A, A, B, B, C, C, D, D, E, E]);
+
+    let mut soa = soa![P1, P0, P1];
+    soa.sort_by_key(|p| *p.mass);
+    assert_eq!(soa, [P0, P1, P1]);
+
+    let mut soa = soa![Event::Quit, Event::Key('b'), Event::Key('a')];
+    soa.sort_by_key(|event| match event {
+        EventRef::Key(c) => *c,
+        _ => 'z',
+    });
+    assert_eq!(soa, [Event::Key('a'), Event::Key('b'), Event::Quit]);
+
+    let mut soa = soa![Unit, Unit];
+    soa.sort_by(|_, _| std::cmp::Ordering::Less);
+    assert_eq!(soa.len(), 2);
+}
+
+#[test]
+fn sort_owned() {
+    let shared = std::sync::Arc::new(0);
+    let mut soa: Soa<Owned> = ["c", "a", "b"]
+        .into_iter()
+        .map(|name| Owned {
+            name: name.to_string(),
+            shared: shared.clone(),
+        })
+        .collect();
+    soa.sort_by(|a, b| a.name.cmp(b.name));
+    assert_eq!(soa.name(), ["a", "b", "c"]);
+    assert_eq!(std::sync::Arc::strong_count(&shared), 4);
+}
+
+#[test]
+fn sort_panic() {
+    let mut soa = soa![E, B, D, A, C];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        soa.sort_by(|a, b| {
+            if *a.foo == 0 || *b.foo == 0 {
+                panic!();
+            }
+            a.foo.cmp(b.foo)
+        });
+    }));
+    assert!(result.is_err());
+    assert_eq!(soa, [E, B, D, A, C]);
+}
+
+#[test]
+fn search() {
+    let soa: Soa<El> = ABCDE.into();
+    assert_eq!(soa.binary_search_by(|el| el.foo.cmp(&12)), Ok(3));
+    assert_eq!(soa.binary_search_by(|el| el.foo.cmp(&13)), Err(4));
+    assert_eq!(soa.binary_search_by_key(&0, |el| *el.bar), Err(0));
+    assert_eq!(soa.binary_search_by_key(&17, |el| *el.bar), Ok(4));
+    assert_eq!(soa.binary_search_by_key(&18, |el| *el.bar), Err(5));
+    assert_eq!(
+        Soa::<El>::new().binary_search_by(|_| unreachable!()),
+        Err(0)
+    );
+    for i in 0..=20 {
+        let expected = soa.iter().filter(|el| *el.foo < i).count();
+        assert_eq!(soa.partition_point(|el| *el.foo < i), expected);
+    }
+
+    assert!(soa.contains(&C));
+    assert!(soa.contains(&soa.idx(4)));
+    assert!(!soa.contains(&El {
+        foo: 0,
+        bar: 0,
+        baz: SingleDrop::DEFAULT,
+    }));
+    assert!(soa.starts_with(&[A, B]));
+    assert!(soa.starts_with(&ABCDE));
+    assert!(!soa.starts_with(&[B]));
+    assert!(!soa.idx(..2).starts_with(&[A, B, C]));
+    assert!(soa.ends_with(&[D, E]));
+    assert!(!soa.ends_with(&[C, D]));
+    assert!(soa.ends_with(&[]));
+
+    let soa = soa![Event::Key('a'), Event::Quit];
+    assert!(soa.contains(&Event::Quit));
+    assert!(!soa.contains(&Event::Key('b')));
+    assert!(soa.ends_with(&[Event::Quit]));
+}
+
+#[test]
+fn reorder() {
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.reverse();
+    assert_eq!(soa, [E, D, C, B, A]);
+    soa.idx_mut(1..4).reverse();
+    assert_eq!(soa, [E, B, C, D, A]);
+
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.rotate_left(2);
+    assert_eq!(soa, [C, D, E, A, B]);
+    soa.rotate_right(2);
+    assert_eq!(soa, ABCDE);
+    soa.rotate_left(0);
+    soa.rotate_right(5);
+    assert_eq!(soa, ABCDE);
+
+    let mut other = soa![P1, P0];
+    let mut soa = soa![P0, P1];
+    soa.swap_with_slice(&mut other);
+    assert_eq!(soa, [P1, P0]);
+    assert_eq!(other, [P0, P1]);
+
+    let mut soa = soa![Event::Quit, Event::Key('a'), Event::Click { x: 1, y: 2 }];
+    soa.rotate_right(1);
+    assert_eq!(
+        soa,
+        [Event::Click { x: 1, y: 2 }, Event::Quit, Event::Key('a')]
+    );
+}
+
+#[test]
+#[should_panic(expected = "different lengths")]
+fn swap_with_slice_len_mismatch() {
+    let mut soa: Soa<El> = ABCDE.into();
+    let mut other = soa![A];
+    soa.swap_with_slice(&mut other);
+}
+
+#[test]
+fn copy_within() {
+    let mut soa = soa![
+        Tuple(0, 1, 2),
+        Tuple(3, 4, 5),
+        Tuple(6, 7, 8),
+        Tuple(9, 10, 11)
+    ];
+    soa.copy_within(..2, 2);
+    assert_eq!(soa.f0(), [0, 3, 0, 3]);
+    soa.copy_within(1..=3, 0);
+    assert_eq!(soa.f2(), [5, 2, 5, 5]);
+    soa.copy_within(4.., 4);
+    assert_eq!(soa.f1(), [4, 1, 4, 4]);
+}
+
+#[test]
+#[should_panic(expected = "dest is out of bounds")]
+fn copy_within_out_of_bounds() {
+    let mut soa = soa![Tuple(0, 1, 2), Tuple(3, 4, 5)];
+    soa.copy_within(..2, 1);
+}
+
+#[test]
+fn fill() {
+    let shared = std::sync::Arc::new(0);
+    let owned = |name: &str| Owned {
+        name: name.to_string(),
+        shared: shared.clone(),
+    };
+    let mut soa: Soa<Owned> = ["a", "b", "c"].into_iter().map(owned).collect();
+    soa.fill(owned("x"));
+    assert_eq!(soa.name(), ["x", "x", "x"]);
+    assert_eq!(std::sync::Arc::strong_count(&shared), 4);
+
+    let mut names = ["d", "e", "f"].into_iter();
+    soa.idx_mut(1..).fill_with(|| owned(names.next().unwrap()));
+    assert_eq!(soa.name(), ["x", "d", "e"]);
+    assert_eq!(std::sync::Arc::strong_count(&shared), 4);
+
+    soa.idx_mut(..0).fill(owned("y"));
+    assert_eq!(std::sync::Arc::strong_count(&shared), 4);
+}
+
+#[test]
+fn permute() {
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.permute(&[3, 0, 4, 1, 2]);
+    assert_eq!(soa, [D, A, E, B, C]);
+    let mut indices: Vec<_> = (0..soa.len()).collect();
+    indices.sort_by_key(|&i| soa.foo()[i]);
+    soa.permute(&indices);
+    assert_eq!(soa, ABCDE);
+    soa.permute(&[0, 1, 2, 3, 4]);
+    assert_eq!(soa, ABCDE);
+
+    let mut soa = soa![Event::Quit, Event::Key('a'), Event::Click { x: 1, y: 2 }];
+    soa.permute(&[2, 0, 1]);
+    assert_eq!(
+        soa,
+        [Event::Click { x: 1, y: 2 }, Event::Quit, Event::Key('a')]
+    );
+}
+
+#[test]
+#[should_panic(expected = "duplicate indices")]
+fn permute_duplicate() {
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.permute(&[0, 1, 2, 3, 3]);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn permute_out_of_bounds() {
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.permute(&[0, 1, 2, 3, 5]);
+}
+
+#[test]
+fn gather_scatter() {
+    let soa = soa![P0, P1];
+    let gathered = Soa::gather(&soa, &[1, 1, 0]);
+    assert_eq!(gathered, [P1, P1, P0]);
+    assert_eq!(Soa::gather(&soa, &[]), []);
+
+    let shared = std::sync::Arc::new(0);
+    let owned = |name: &str| Owned {
+        name: name.to_string(),
+        shared: shared.clone(),
+    };
+    let mut soa: Soa<Owned> = ["a", "b", "c"].into_iter().map(owned).collect();
+    let src: Soa<Owned> = ["x", "y"].into_iter().map(owned).collect();
+    soa.scatter(&src, &[2, 0]);
+    assert_eq!(soa.name(), ["y", "b", "x"]);
+    assert_eq!(std::sync::Arc::strong_count(&shared), 6);
+    let swapped = Soa::gather(&soa, &[1, 0]);
+    soa.scatter(&swapped, &[0, 1]);
+    assert_eq!(soa.name(), ["b", "y", "x"]);
+    drop((soa, src, swapped));
+    assert_eq!(std::sync::Arc::strong_count(&shared), 1);
+}
+
+#[test]
+fn masks() {
+    let soa: Soa<El> = ABCDE.into();
+    let mask: Vec<_> = soa.bar().iter().map(|bar| bar % 4 == 1).collect();
+    assert_eq!(soa.filter_by_mask(&mask), ABCDE);
+    let mask = [true, false, false, true, true];
+    assert_eq!(soa.filter_by_mask(&mask), [A, D, E]);
+    assert_eq!(soa.filter_by_mask(&[false; 5]), []);
+
+    let mut soa = soa;
+    soa.retain_mask(&mask);
+    assert_eq!(soa, [A, D, E]);
+    soa.retain_mask(&[false, true, false]);
+    assert_eq!(soa, [D]);
+
+    let tuples = soa![Tuple(1, 2, 3), Tuple(4, 5, 6)];
+    assert_eq!(tuples.filter_by_mask(&[false, true]), [Tuple(4, 5, 6)]);
+}
+
+#[test]
+#[should_panic(expected = "different lengths")]
+fn retain_mask_len_mismatch() {
+    let mut soa: Soa<El> = ABCDE.into();
+    soa.retain_mask(&[true]);
+}
+
+#[test]
+fn partition() {
+    let mut soa: Soa<El> = ABCDE.into();
+    assert_eq!(soa.partition(|el| *el.foo % 8 != 0), 2);
+    assert_eq!(soa.idx(..2), [B, D]);
+    let mut rest = soa.idx(2..).iter().map(|el| *el.foo).collect::<Vec<_>>();
+    rest.sort();
+    assert_eq!(rest, [0, 8, 16]);
+
+    assert_eq!(soa.partition(|_| true), 5);
+    assert_eq!(soa.partition(|_| false), 0);
+
+    let mut soa = soa![Event::Quit, Event::Key('a'), Event::Quit, Event::Key('b')];
+    let keys = soa.partition(|event| matches!(event, EventRef::Key(_)));
+    assert_eq!(keys, 2);
+    assert_eq!(soa.idx(..keys), [Event::Key('a'), Event::Key('b')]);
+    assert_eq!(soa.idx(keys..), [Event::Quit, Event::Quit]);
+}
@@ -1,5 +1,6 @@
 use soa_rs::Soars;
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Soars)]
 #[soa_derive(Debug, PartialEq)]
 struct Alignment {
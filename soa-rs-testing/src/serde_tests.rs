@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use soa_rs::{serde::columnar::Columnar, soa, Soa, SoaArray, Soars};
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[soa_derive(Debug, PartialEq)]
+#[soa(serde)]
+struct Tuple(u8, u16, u32);
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[soa_derive(Debug, PartialEq)]
+#[soa(serde)]
+struct Vec3 {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[soa_derive(Debug, PartialEq)]
+#[soa(serde)]
+struct Particle {
+    #[soa(nested)]
+    pos: Vec3,
+    #[soa(nested)]
+    vel: Vec3,
+    mass: u8,
+}
+
+const P0: Particle = Particle {
+    pos: Vec3 {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    },
+    vel: Vec3 {
+        x: 4.0,
+        y: 5.0,
+        z: 6.0,
+    },
+    mass: 7,
+};
+
+const P1: Particle = Particle {
+    pos: Vec3 {
+        x: 8.0,
+        y: 9.0,
+        z: 10.0,
+    },
+    vel: Vec3 {
+        x: 11.0,
+        y: 12.0,
+        z: 13.0,
+    },
+    mass: 14,
+};
+
+#[derive(Soars, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[soa_derive(Debug, PartialEq)]
+#[soa(serde)]
+enum Event {
+    Click { x: i32, y: i32 },
+    Key(char),
+    Pair(u8, u8),
+    Quit,
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[soa_derive(Debug, PartialEq)]
+#[soa(serde)]
+struct Unit;
+
+#[test]
+fn rows() {
+    let soa = soa![Tuple(1, 2, 3), Tuple(4, 5, 6)];
+    let json = serde_json::to_string(&soa).unwrap();
+    assert_eq!(json, "[[1,2,3],[4,5,6]]");
+    assert_eq!(serde_json::from_str::<Soa<Tuple>>(&json).unwrap(), soa);
+    assert_eq!(serde_json::to_string(&soa.idx(1..)).unwrap(), "[[4,5,6]]");
+
+    let particles = soa![P0, P1];
+    let json = serde_json::to_string(&particles).unwrap();
+    assert_eq!(json, serde_json::to_string(&[P0, P1]).unwrap());
+    assert_eq!(
+        serde_json::from_str::<Soa<Particle>>(&json).unwrap(),
+        particles
+    );
+
+    let events = vec![
+        Event::Click { x: 1, y: 2 },
+        Event::Key('a'),
+        Event::Pair(3, 4),
+        Event::Quit,
+    ];
+    let soa: Soa<Event> = events.iter().cloned().collect();
+    let json = serde_json::to_string(&soa).unwrap();
+    assert_eq!(json, serde_json::to_string(&events).unwrap());
+    assert_eq!(serde_json::from_str::<Soa<Event>>(&json).unwrap(), events);
+
+    let units = soa![Unit, Unit];
+    let json = serde_json::to_string(&units).unwrap();
+    assert_eq!(json, "[null,null]");
+    assert_eq!(serde_json::from_str::<Soa<Unit>>(&json).unwrap(), units);
+}
+
+#[test]
+fn columnar() {
+    let soa = soa![Tuple(1, 2, 3), Tuple(4, 5, 6)];
+    let json = serde_json::to_string(&Columnar(&soa)).unwrap();
+    assert_eq!(json, r#"{"0":[1,4],"1":[2,5],"2":[3,6]}"#);
+    let Columnar(de): Columnar<Soa<Tuple>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(de, soa);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Scene {
+        #[serde(with = "soa_rs::serde::columnar")]
+        particles: Soa<Particle>,
+    }
+
+    let scene = Scene {
+        particles: soa![P0, P1],
+    };
+    let json = serde_json::to_string(&scene).unwrap();
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"particles":{"#,
+            r#""pos":{"x":[1.0,8.0],"y":[2.0,9.0],"z":[3.0,10.0]},"#,
+            r#""vel":{"x":[4.0,11.0],"y":[5.0,12.0],"z":[6.0,13.0]},"#,
+            r#""mass":[7,14]}}"#,
+        )
+    );
+    assert_eq!(serde_json::from_str::<Scene>(&json).unwrap(), scene);
+}
+
+#[test]
+fn columnar_errors() {
+    let de = |json| serde_json::from_str::<Columnar<Soa<Tuple>>>(json).map(|columnar| columnar.0);
+    let err = |json| de(json).unwrap_err().to_string();
+    assert!(err(r#"{"0":[1],"1":[2]}"#).contains("missing field `2`"));
+    assert!(err(r#"{"0":[1],"0":[1],"1":[2],"2":[3]}"#).contains("duplicate field `0`"));
+    assert!(err(r#"{"0":[1,4],"1":[2],"2":[3]}"#).contains("invalid length 1"));
+    assert!(err(r#"[[1],[2]]"#).contains("invalid length 2"));
+    assert_eq!(
+        de(r#"{"0":[1],"1":[2],"2":[3],"extra":true}"#).unwrap(),
+        [Tuple(1, 2, 3)]
+    );
+    assert_eq!(de(r#"[[1],[2],[3]]"#).unwrap(), [Tuple(1, 2, 3)]);
+}
+
+#[test]
+fn array() {
+    let array = TupleArray::from_array([Tuple(1, 2, 3), Tuple(4, 5, 6)]);
+    let json = serde_json::to_string(&array).unwrap();
+    assert_eq!(json, "[[1,2,3],[4,5,6]]");
+    assert_eq!(serde_json::from_str::<TupleArray<2>>(&json).unwrap(), array);
+    let err = serde_json::from_str::<TupleArray<3>>(&json).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("invalid length 2, expected an array of length 3"));
+
+    let json = serde_json::to_string(&Columnar(&array)).unwrap();
+    assert_eq!(json, r#"{"0":[1,4],"1":[2,5],"2":[3,6]}"#);
+    let Columnar(de): Columnar<TupleArray<2>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(de, array);
+
+    let particles = ParticleArray::from_array([P0, P1]);
+    let json = serde_json::to_string(&particles).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ParticleArray<2>>(&json).unwrap(),
+        particles
+    );
+
+    let events = EventArray::from_array([Event::Key('a'), Event::Pair(1, 2)]);
+    let json = serde_json::to_string(&events).unwrap();
+    assert_eq!(json, r#"[{"Key":"a"},{"Pair":[1,2]}]"#);
+    let de = serde_json::from_str::<EventArray<2>>(&json).unwrap();
+    assert_eq!(de.as_slice(), events.as_slice());
+
+    let units = UnitArray::from_array([Unit; 3]);
+    let json = serde_json::to_string(&units).unwrap();
+    assert_eq!(json, "[null,null,null]");
+    serde_json::from_str::<UnitArray<3>>(&json).unwrap();
+}
+
+#[derive(Soars, Debug, Clone, PartialEq, Serialize)]
+#[soa_derive(Debug, PartialEq, Serialize)]
+enum Shape {
+    Circle(f32),
+    Rect { w: f32, h: f32 },
+}
+
+#[derive(Soars, Debug, Clone, Copy, PartialEq, Serialize)]
+#[soa_derive(Debug, PartialEq, Serialize)]
+struct Marker;
+
+#[test]
+fn soa_derive_serialize() {
+    // Without soa(serde), the serde derive on the generated types doesn't
+    // conflict with the derive macro, and rows serialize through it.
+    let shapes = vec![Shape::Circle(1.0), Shape::Rect { w: 2.0, h: 3.0 }];
+    let soa: Soa<Shape> = shapes.iter().cloned().collect();
+    let json = serde_json::to_string(&soa).unwrap();
+    assert_eq!(json, serde_json::to_string(&shapes).unwrap());
+    assert_eq!(
+        serde_json::to_string(&soa.idx(1)).unwrap(),
+        r#"{"Rect":{"w":2.0,"h":3.0}}"#
+    );
+
+    let markers = soa![Marker, Marker];
+    assert_eq!(serde_json::to_string(&markers).unwrap(), "[null,null]");
+    let array = MarkerArray::from_array([Marker; 2]);
+    assert_eq!(serde_json::to_string(&array).unwrap(), "null");
+}
@@ -0,0 +1,239 @@
+use alloc::alloc::{alloc, dealloc, realloc};
+use core::{
+    alloc::Layout,
+    fmt::{self, Display, Formatter},
+    ptr::{self, NonNull},
+};
+
+/// An implementation of `Allocator` can allocate, grow, shrink, and deallocate
+/// arbitrary blocks of data.
+///
+/// This mirrors the unstable [`std::alloc::Allocator`] trait so that a [`Soa`]
+/// can be backed by arena, bump, or other custom allocators on stable Rust.
+/// [`Global`] forwards to the global allocator and is the default.
+///
+/// # Safety
+///
+/// Memory blocks returned from an allocator must point to valid memory and
+/// retain their validity until the block is deallocated or the allocator is
+/// dropped. Moving the allocator must not invalidate memory blocks returned
+/// from it. Any pointer to a memory block which is currently allocated may be
+/// passed to any other method of the allocator.
+///
+/// [`std::alloc::Allocator`]: https://doc.rust-lang.org/std/alloc/trait.Allocator.html
+/// [`Soa`]: crate::Soa
+pub unsafe trait Allocator {
+    /// Attempts to allocate a block of memory that fits `layout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory is exhausted or `layout` does not meet
+    /// the allocator's size or alignment constraints.
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+    /// Deallocates the memory referenced by `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must denote a block of memory currently allocated via this
+    ///   allocator
+    /// - `layout` must fit that block of memory
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// Attempts to extend the memory block. The contents of the old block are
+    /// copied to the start of the new block.
+    ///
+    /// If this returns `Ok`, ownership of the memory block referenced by `ptr`
+    /// has been transferred to the returned block. If this returns `Err`, the
+    /// memory block is left unaltered.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must denote a block of memory currently allocated via this
+    ///   allocator
+    /// - `old_layout` must fit that block of memory
+    /// - `new_layout.size()` must be greater than or equal to
+    ///   `old_layout.size()`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new layout cannot be allocated.
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        debug_assert!(new_layout.size() >= old_layout.size());
+        let new_ptr = self.allocate(new_layout)?;
+        unsafe {
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.cast().as_ptr(), old_layout.size());
+            self.deallocate(ptr, old_layout);
+        }
+        Ok(new_ptr)
+    }
+
+    /// Attempts to shrink the memory block. The start of the old block, up to
+    /// the size of the new block, is copied to the new block.
+    ///
+    /// If this returns `Ok`, ownership of the memory block referenced by `ptr`
+    /// has been transferred to the returned block. If this returns `Err`, the
+    /// memory block is left unaltered.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must denote a block of memory currently allocated via this
+    ///   allocator
+    /// - `old_layout` must fit that block of memory
+    /// - `new_layout.size()` must be smaller than or equal to
+    ///   `old_layout.size()`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new layout cannot be allocated.
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        debug_assert!(new_layout.size() <= old_layout.size());
+        let new_ptr = self.allocate(new_layout)?;
+        unsafe {
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.cast().as_ptr(), new_layout.size());
+            self.deallocate(ptr, old_layout);
+        }
+        Ok(new_ptr)
+    }
+}
+
+unsafe impl<A> Allocator for &A
+where
+    A: Allocator + ?Sized,
+{
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { (**self).deallocate(ptr, layout) }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { (**self).grow(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { (**self).shrink(ptr, old_layout, new_layout) }
+    }
+}
+
+/// The global memory allocator.
+///
+/// This forwards calls to the allocator registered with the
+/// `#[global_allocator]` attribute if there is one, or the default allocator
+/// otherwise.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Global;
+
+impl Global {
+    /// Reallocates with the global allocator, which preserves the contents up
+    /// to the smaller of the two sizes.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as [`Allocator::grow`] and [`Allocator::shrink`]
+    /// apply, and the alignments of the layouts must match.
+    unsafe fn realloc(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        debug_assert_eq!(old_layout.align(), new_layout.align());
+        let ptr = unsafe { realloc(ptr.as_ptr(), old_layout, new_layout.size()) };
+        NonNull::new(ptr)
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+            .ok_or(AllocError)
+    }
+}
+
+unsafe impl Allocator for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = if layout.size() == 0 {
+            // A dangling pointer without provenance, like
+            // `ptr::without_provenance_mut`, which requires Rust 1.84.
+            // SAFETY: Alignment is never zero
+            unsafe { NonNull::new_unchecked(ptr::null_mut::<u8>().wrapping_add(layout.align())) }
+        } else {
+            NonNull::new(unsafe { alloc(layout) }).ok_or(AllocError)?
+        };
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() > 0 {
+            unsafe { dealloc(ptr.as_ptr(), layout) }
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if old_layout.size() == 0 || old_layout.align() != new_layout.align() {
+            let new_ptr = self.allocate(new_layout)?;
+            unsafe {
+                ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.cast().as_ptr(), old_layout.size());
+                self.deallocate(ptr, old_layout);
+            }
+            Ok(new_ptr)
+        } else {
+            unsafe { self.realloc(ptr, old_layout, new_layout) }
+        }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if new_layout.size() == 0 || old_layout.align() != new_layout.align() {
+            let new_ptr = self.allocate(new_layout)?;
+            unsafe {
+                ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.cast().as_ptr(), new_layout.size());
+                self.deallocate(ptr, old_layout);
+            }
+            Ok(new_ptr)
+        } else {
+            unsafe { self.realloc(ptr, old_layout, new_layout) }
+        }
+    }
+}
+
+/// The error type returned by an [`Allocator`] when it fails to allocate
+/// memory.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct AllocError;
+
+impl Display for AllocError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocError {}
@@ -0,0 +1,507 @@
+use crate::{Iter, IterMut, Slice, SliceMut, SliceRef, SoaArray, SoaRaw, SoaRawClone, Soars};
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::{Chain, FlatMap},
+    mem::{needs_drop, MaybeUninit},
+    slice,
+};
+
+/// Iterator over the elements of an [`AoSoa`], returned by [`AoSoa::iter`].
+pub type AoSoaIter<'a, T, const LANES: usize> = Chain<
+    FlatMap<
+        slice::Iter<'a, <T as Soars>::Array<LANES>>,
+        SliceRef<'a, T>,
+        fn(&'a <T as Soars>::Array<LANES>) -> SliceRef<'a, T>,
+    >,
+    Iter<'a, T>,
+>;
+
+/// Iterator over mutable references to the elements of an [`AoSoa`], returned
+/// by [`AoSoa::iter_mut`].
+pub type AoSoaIterMut<'a, T, const LANES: usize> = Chain<
+    FlatMap<
+        slice::IterMut<'a, <T as Soars>::Array<LANES>>,
+        SliceMut<'a, T>,
+        fn(&'a mut <T as Soars>::Array<LANES>) -> SliceMut<'a, T>,
+    >,
+    IterMut<'a, T>,
+>;
+
+/// A growable array-of-structures-of-arrays container.
+///
+/// The elements are stored in blocks of `LANES` elements, where each block is
+/// a SoA array such as `FooArray<LANES>`. Within a block, the values of each
+/// field are contiguous, and the fields of a block are next to each other in
+/// memory. This keeps all the fields of nearby elements close together while
+/// still letting each field be loaded `LANES` values at a time, which suits
+/// SIMD code where `LANES` is the vector width.
+///
+/// All blocks but the last are full. The full blocks are available through
+/// [`AoSoa::blocks`] and the elements of the last, partial block through
+/// [`AoSoa::remainder`]. Elements can also be accessed individually by index.
+///
+/// # Examples
+///
+/// ```
+/// # use soa_rs::{AoSoa, Soars};
+/// # #[derive(Soars, Debug, PartialEq)]
+/// # #[soa_derive(Debug, PartialEq)]
+/// # struct Foo {
+/// #     bar: f32,
+/// #     baz: f32,
+/// # }
+/// let aosoa: AoSoa<Foo, 4> = (0..6)
+///     .map(|i| Foo {
+///         bar: i as f32,
+///         baz: 1.0,
+///     })
+///     .collect();
+/// assert_eq!(aosoa.blocks().len(), 1);
+/// assert_eq!(aosoa.blocks()[0].bar, [0.0, 1.0, 2.0, 3.0]);
+/// assert_eq!(aosoa.remainder().bar(), [4.0, 5.0]);
+/// assert_eq!(aosoa.idx(5), FooRef { bar: &5.0, baz: &1.0 });
+/// ```
+pub struct AoSoa<T, const LANES: usize>
+where
+    T: Soars,
+{
+    // Holds len.div_ceil(LANES) blocks, of which only the last may be
+    // partially initialized.
+    blocks: Vec<MaybeUninit<T::Array<LANES>>>,
+    len: usize,
+}
+
+impl<T, const LANES: usize> AoSoa<T, LANES>
+where
+    T: Soars,
+{
+    const NONZERO_LANES: () = assert!(LANES > 0, "LANES must be nonzero");
+
+    /// Constructs a new, empty `AoSoa<T, LANES>`.
+    ///
+    /// The container will not allocate until elements are pushed onto it.
+    pub fn new() -> Self {
+        let () = Self::NONZERO_LANES;
+        Self {
+            blocks: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Constructs a new, empty `AoSoa<T, LANES>` with room for at least
+    /// `capacity` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{AoSoa, Soars};
+    /// # #[derive(Soars)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let aosoa = AoSoa::<Foo, 4>::with_capacity(10);
+    /// assert!(aosoa.capacity() >= 12);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut out = Self::new();
+        out.reserve(capacity);
+        out
+    }
+
+    /// Returns the total number of elements the container can hold without
+    /// reallocating, which is always a multiple of `LANES`.
+    pub fn capacity(&self) -> usize {
+        self.blocks.capacity().saturating_mul(LANES)
+    }
+
+    /// Returns the number of elements in the container.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the container contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        let blocks = required.div_ceil(LANES);
+        self.blocks.reserve(blocks - self.blocks.len());
+    }
+
+    /// Appends an element to the back of the container, starting a new block
+    /// if the last one is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{AoSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut aosoa = AoSoa::<Foo, 2>::new();
+    /// aosoa.push(Foo(1));
+    /// aosoa.push(Foo(2));
+    /// aosoa.push(Foo(3));
+    /// assert_eq!(aosoa.blocks().len(), 1);
+    /// assert_eq!(aosoa.remainder(), [Foo(3)]);
+    /// ```
+    // `is_multiple_of` requires Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn push(&mut self, element: T) {
+        if self.len % LANES == 0 {
+            self.blocks.push(MaybeUninit::uninit());
+        }
+        unsafe {
+            self.raw_mut(self.len).set(element);
+        }
+        self.len += 1;
+    }
+
+    /// Removes the last element and returns it, or [`None`] if the container
+    /// is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{AoSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut aosoa: AoSoa<Foo, 2> = [Foo(1), Foo(2)].into_iter().collect();
+    /// assert_eq!(aosoa.pop(), Some(Foo(2)));
+    /// assert_eq!(aosoa.pop(), Some(Foo(1)));
+    /// assert_eq!(aosoa.pop(), None);
+    /// ```
+    // `is_multiple_of` requires Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            let out = unsafe { self.raw_mut(self.len).get() };
+            if self.len % LANES == 0 {
+                self.blocks.pop();
+            }
+            Some(out)
+        }
+    }
+
+    /// Shortens the container, keeping the first `len` elements and dropping
+    /// the rest.
+    pub fn truncate(&mut self, len: usize) {
+        while len < self.len {
+            self.pop();
+        }
+    }
+
+    /// Clears the container, removing all values.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Returns a reference to the element at `index`, or [`None`] if out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<T::Ref<'_>> {
+        if index < self.len {
+            Some(unsafe { self.raw_ref(index).get_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or [`None`] if
+    /// out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<T::RefMut<'_>> {
+        if index < self.len {
+            Some(unsafe { self.raw_mut(index).get_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn idx(&self, index: usize) -> T::Ref<'_> {
+        self.get(index).expect("index out of bounds")
+    }
+
+    /// Returns a mutable reference to the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{AoSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut aosoa: AoSoa<Foo, 2> = (0..3).map(Foo).collect();
+    /// *aosoa.idx_mut(2).0 = 10;
+    /// assert_eq!(aosoa.remainder().f0(), [10]);
+    /// ```
+    pub fn idx_mut(&mut self, index: usize) -> T::RefMut<'_> {
+        self.get_mut(index).expect("index out of bounds")
+    }
+
+    /// Returns the full blocks of `LANES` elements.
+    ///
+    /// Elements that don't fill a block are returned by
+    /// [`AoSoa::remainder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{AoSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(f32, f32);
+    /// let aosoa: AoSoa<Foo, 2> = (0..5).map(|i| Foo(i as f32, 2.0)).collect();
+    /// let products: Vec<_> = aosoa
+    ///     .blocks()
+    ///     .iter()
+    ///     .map(|block| [block.0[0] * block.1[0], block.0[1] * block.1[1]])
+    ///     .collect();
+    /// assert_eq!(products, [[0.0, 2.0], [4.0, 6.0]]);
+    /// ```
+    pub fn blocks(&self) -> &[T::Array<LANES>] {
+        let full = self.len / LANES;
+        // SAFETY: The first `full` blocks are initialized, and MaybeUninit<U>
+        // has the same layout as U.
+        unsafe { slice::from_raw_parts(self.blocks.as_ptr().cast(), full) }
+    }
+
+    /// Returns the full blocks of `LANES` elements mutably.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{AoSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(f32);
+    /// let mut aosoa: AoSoa<Foo, 2> = (0..3).map(|i| Foo(i as f32)).collect();
+    /// for block in aosoa.blocks_mut() {
+    ///     block.0 = block.0.map(|x| x * 2.0);
+    /// }
+    /// assert_eq!(aosoa.iter().map(|foo| *foo.0).collect::<Vec<_>>(), [0.0, 2.0, 2.0]);
+    /// ```
+    pub fn blocks_mut(&mut self) -> &mut [T::Array<LANES>] {
+        self.split_mut().0
+    }
+
+    /// Returns the elements of the last block if it is only partially full,
+    /// or an empty slice otherwise.
+    pub fn remainder(&self) -> SliceRef<'_, T> {
+        let rem = self.len % LANES;
+        let slice = if rem == 0 {
+            Slice::empty()
+        } else {
+            Slice::with_raw(unsafe { self.raw_ref(self.len - rem) })
+        };
+        unsafe { SliceRef::from_slice(slice, rem) }
+    }
+
+    /// Returns the elements of the last block mutably if it is only partially
+    /// full, or an empty slice otherwise.
+    pub fn remainder_mut(&mut self) -> SliceMut<'_, T> {
+        self.split_mut().1
+    }
+
+    /// Returns an iterator over the elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{AoSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let aosoa: AoSoa<Foo, 2> = (1..=5).map(Foo).collect();
+    /// let sum: usize = aosoa.iter().map(|foo| foo.0).sum();
+    /// assert_eq!(sum, 15);
+    /// ```
+    pub fn iter(&self) -> AoSoaIter<'_, T, LANES> {
+        let as_slice: fn(&T::Array<LANES>) -> SliceRef<'_, T> = SoaArray::as_slice;
+        self.blocks()
+            .iter()
+            .flat_map(as_slice)
+            .chain(self.remainder())
+    }
+
+    /// Returns an iterator over the elements that returns mutable references.
+    pub fn iter_mut(&mut self) -> AoSoaIterMut<'_, T, LANES> {
+        let as_mut_slice: fn(&mut T::Array<LANES>) -> SliceMut<'_, T> = SoaArray::as_mut_slice;
+        let (blocks, remainder) = self.split_mut();
+        blocks.iter_mut().flat_map(as_mut_slice).chain(remainder)
+    }
+
+    /// Returns the full blocks and the partial block mutably at once.
+    fn split_mut(&mut self) -> (&mut [T::Array<LANES>], SliceMut<'_, T>) {
+        let rem = self.len % LANES;
+        let slice = if rem == 0 {
+            Slice::empty()
+        } else {
+            Slice::with_raw(unsafe { self.raw_mut(self.len - rem) })
+        };
+        let remainder = unsafe { SliceMut::from_slice(slice, rem) };
+        let full = self.len / LANES;
+        // SAFETY: As in AoSoa::blocks. The remainder is in the block after
+        // the last full one, so the two don't overlap.
+        let blocks = unsafe { slice::from_raw_parts_mut(self.blocks.as_mut_ptr().cast(), full) };
+        (blocks, remainder)
+    }
+
+    /// Returns a [`SoaRaw`] for the element at `index`, only to be used for
+    /// reading.
+    ///
+    /// # Safety
+    ///
+    /// The block containing `index` must be allocated.
+    unsafe fn raw_ref(&self, index: usize) -> T::Raw {
+        let block = self.blocks.as_ptr().add(index / LANES);
+        T::Raw::from_array::<LANES>(block.cast_mut().cast()).offset(index % LANES)
+    }
+
+    /// Returns a [`SoaRaw`] for the element at `index`.
+    ///
+    /// # Safety
+    ///
+    /// The same as for `AoSoa::raw_ref`.
+    unsafe fn raw_mut(&mut self, index: usize) -> T::Raw {
+        let block = self.blocks.as_mut_ptr().add(index / LANES);
+        T::Raw::from_array::<LANES>(block.cast()).offset(index % LANES)
+    }
+}
+
+impl<T, const LANES: usize> Drop for AoSoa<T, LANES>
+where
+    T: Soars,
+{
+    fn drop(&mut self) {
+        if needs_drop::<T>() {
+            while self.pop().is_some() {}
+        }
+    }
+}
+
+impl<'a, T, const LANES: usize> IntoIterator for &'a AoSoa<T, LANES>
+where
+    T: Soars,
+{
+    type Item = T::Ref<'a>;
+
+    type IntoIter = AoSoaIter<'a, T, LANES>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const LANES: usize> IntoIterator for &'a mut AoSoa<T, LANES>
+where
+    T: Soars,
+{
+    type Item = T::RefMut<'a>;
+
+    type IntoIter = AoSoaIterMut<'a, T, LANES>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// NOTE: Elements are cloned field by field for the same reasons as in the
+// Clone implementation for Soa.
+impl<T, const LANES: usize> Clone for AoSoa<T, LANES>
+where
+    T: Soars + Clone,
+    T::Raw: SoaRawClone,
+{
+    fn clone(&self) -> Self {
+        let mut out = Self::with_capacity(self.len);
+        for i in 0..self.len {
+            if i % LANES == 0 {
+                out.blocks.push(MaybeUninit::uninit());
+            }
+            // SAFETY: The source element is initialized and the destination
+            // block was just allocated.
+            unsafe { self.raw_ref(i).clone_to(out.raw_mut(i)) };
+            out.len += 1;
+        }
+        out
+    }
+}
+
+impl<T, const LANES: usize> Extend<T> for AoSoa<T, LANES>
+where
+    T: Soars,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T, const LANES: usize> FromIterator<T> for AoSoa<T, LANES>
+where
+    T: Soars,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<T, const LANES: usize> Debug for AoSoa<T, LANES>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const LANES: usize> PartialEq for AoSoa<T, LANES>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, const LANES: usize> Eq for AoSoa<T, LANES>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Eq,
+{
+}
+
+impl<T, const LANES: usize> Default for AoSoa<T, LANES>
+where
+    T: Soars,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,706 @@
+use crate::{eq_impl, Iter, IterMut, Slice, SliceMut, SliceRef, Soa, SoaRaw, SoaRawClone, Soars};
+use core::{
+    borrow::{Borrow, BorrowMut},
+    cell::UnsafeCell,
+    cmp::Ordering,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    mem::{needs_drop, MaybeUninit},
+    ops::{Deref, DerefMut},
+};
+
+/// A fixed-capacity SoA container that stores its elements inline.
+///
+/// What [`Soa`] is to `Vec<T>`, `ArraySoa` is to `ArrayVec<T, N>`. The columns
+/// are stored in the container itself as `[MaybeUninit<F>; N]` arrays, so it
+/// never allocates. Like [`Soa`], it dereferences to [`Slice`].
+///
+/// Because dereferencing to [`Slice`] needs pointers into the container's own
+/// storage, they are cached and refreshed after the container moves. For this
+/// reason, `ArraySoa` is [`Send`] but not [`Sync`].
+///
+/// # Examples
+///
+/// ```
+/// # use soa_rs::{ArraySoa, Soars};
+/// # #[derive(Soars, Debug, PartialEq)]
+/// # #[soa_derive(Debug, PartialEq)]
+/// # struct Foo(usize);
+/// let mut soa = ArraySoa::<Foo, 4>::new();
+/// soa.push(Foo(1));
+/// soa.push(Foo(2));
+/// assert_eq!(soa, [Foo(1), Foo(2)]);
+/// assert_eq!(soa.f0(), [1, 2]);
+/// ```
+pub struct ArraySoa<T, const N: usize>
+where
+    T: Soars,
+{
+    // Only valid while the container hasn't moved. See ArraySoa::slice.
+    slice: UnsafeCell<Slice<T, ()>>,
+    pub(crate) len: usize,
+    array: MaybeUninit<T::Array<N>>,
+}
+
+impl<T, const N: usize> ArraySoa<T, N>
+where
+    T: Soars,
+{
+    /// Constructs a new, empty `ArraySoa<T, N>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Copy, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo;
+    /// let soa = ArraySoa::<Foo, 8>::new();
+    /// assert!(soa.is_empty());
+    /// assert_eq!(soa.capacity(), 8);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            slice: UnsafeCell::new(Slice::empty()),
+            len: 0,
+            array: MaybeUninit::uninit(),
+        }
+    }
+
+    /// Returns the number of elements the container can hold, which is always
+    /// `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of elements that can still be added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = ArraySoa::<Foo, 4>::new();
+    /// soa.push(Foo(1));
+    /// assert_eq!(soa.remaining_capacity(), 3);
+    /// ```
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// Returns `true` if the container holds `N` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = ArraySoa::<Foo, 1>::new();
+    /// assert!(!soa.is_full());
+    /// soa.push(Foo(1));
+    /// assert!(soa.is_full());
+    /// ```
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Appends an element to the back of the container.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = ArraySoa::<Foo, 4>::new();
+    /// soa.push(Foo(1));
+    /// soa.push(Foo(2));
+    /// assert_eq!(soa, [Foo(1), Foo(2)]);
+    /// ```
+    pub fn push(&mut self, element: T) {
+        if self.try_push(element).is_err() {
+            panic!("capacity exceeded");
+        }
+    }
+
+    /// Appends an element to the back of the container, or returns it if the
+    /// container is full.
+    ///
+    /// # Errors
+    ///
+    /// If the container is full, `element` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = ArraySoa::<Foo, 1>::new();
+    /// assert_eq!(soa.try_push(Foo(1)), Ok(()));
+    /// assert_eq!(soa.try_push(Foo(2)), Err(Foo(2)));
+    /// assert_eq!(soa, [Foo(1)]);
+    /// ```
+    pub fn try_push(&mut self, element: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(element);
+        }
+        unsafe {
+            self.raw_mut().offset(self.len).set(element);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, or [`None`] if the container
+    /// is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = [Foo(1), Foo(2)].into_iter().collect();
+    /// assert_eq!(soa.pop(), Some(Foo(2)));
+    /// assert_eq!(soa, [Foo(1)]);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            Some(unsafe { self.raw_mut().offset(self.len).get() })
+        }
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after it
+    /// to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len` or if the container is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = [Foo(1), Foo(2)].into_iter().collect();
+    /// soa.insert(1, Foo(3));
+    /// assert_eq!(soa, [Foo(1), Foo(3), Foo(2)]);
+    /// ```
+    pub fn insert(&mut self, index: usize, element: T) {
+        assert!(index <= self.len, "index out of bounds");
+        assert!(!self.is_full(), "capacity exceeded");
+        unsafe {
+            let ith = self.raw_mut().offset(index);
+            ith.copy_to(ith.offset(1), self.len - index);
+            ith.set(element);
+        }
+        self.len += 1;
+    }
+
+    /// Removes and returns the element at position `index`, shifting all
+    /// elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = [Foo(1), Foo(2), Foo(3)].into_iter().collect();
+    /// assert_eq!(soa.remove(1), Foo(2));
+    /// assert_eq!(soa, [Foo(1), Foo(3)]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+        self.len -= 1;
+        let ith = unsafe { self.raw_mut().offset(index) };
+        let out = unsafe { ith.get() };
+        unsafe {
+            ith.offset(1).copy_to(ith, self.len - index);
+        }
+        out
+    }
+
+    /// Removes an element and returns it, replacing it with the last element.
+    ///
+    /// This does not preserve ordering, but is O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = [Foo(0), Foo(1), Foo(2)].into_iter().collect();
+    /// assert_eq!(soa.swap_remove(0), Foo(0));
+    /// assert_eq!(soa, [Foo(2), Foo(1)]);
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+        self.len -= 1;
+        let raw = self.raw_mut();
+        let to_remove = unsafe { raw.offset(index) };
+        let last = unsafe { raw.offset(self.len) };
+        let out = unsafe { to_remove.get() };
+        unsafe {
+            last.copy_to(to_remove, 1);
+        }
+        out
+    }
+
+    /// Shortens the container, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater or equal to the current length, this has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = [Foo(1), Foo(2), Foo(3)].into_iter().collect();
+    /// soa.truncate(1);
+    /// assert_eq!(soa, [Foo(1)]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        while len < self.len {
+            self.pop();
+        }
+    }
+
+    /// Clears the container, removing all values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = [Foo(1), Foo(2)].into_iter().collect();
+    /// soa.clear();
+    /// assert!(soa.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` for which `f(e)` returns
+    /// `false`. This method operates in place, visiting each element exactly
+    /// once in the original order, and preserves the order of the retained
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = (1..=4).map(Foo).collect();
+    /// soa.retain(|foo| foo.0 % 2 == 0);
+    /// assert_eq!(soa, [Foo(2), Foo(4)]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T::Ref<'_>) -> bool,
+    {
+        self.retain_raw(|raw| f(unsafe { raw.get_ref() }));
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable
+    /// reference to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = (1..=4).map(Foo).collect();
+    /// soa.retain_mut(|foo| {
+    ///     *foo.0 += 1;
+    ///     *foo.0 <= 3
+    /// });
+    /// assert_eq!(soa, [Foo(2), Foo(3)]);
+    /// ```
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T::RefMut<'_>) -> bool,
+    {
+        self.retain_raw(|raw| f(unsafe { raw.get_mut() }));
+    }
+
+    /// Implements [`ArraySoa::retain`] and [`ArraySoa::retain_mut`].
+    ///
+    /// See `Soa::retain_raw`, which this mirrors.
+    fn retain_raw<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T::Raw) -> bool,
+    {
+        struct Guard<'a, T, const N: usize>
+        where
+            T: Soars,
+        {
+            soa: &'a mut ArraySoa<T, N>,
+            processed: usize,
+            deleted: usize,
+            original_len: usize,
+        }
+
+        impl<T, const N: usize> Drop for Guard<'_, T, N>
+        where
+            T: Soars,
+        {
+            fn drop(&mut self) {
+                if self.deleted > 0 {
+                    unsafe {
+                        let raw = self.soa.raw_mut();
+                        let src = raw.offset(self.processed);
+                        let dst = raw.offset(self.processed - self.deleted);
+                        src.copy_to(dst, self.original_len - self.processed);
+                    }
+                }
+                self.soa.len = self.original_len - self.deleted;
+            }
+        }
+
+        let original_len = self.len;
+        // Avoid double drops if the guard is leaked
+        self.len = 0;
+        let mut guard = Guard {
+            soa: self,
+            processed: 0,
+            deleted: 0,
+            original_len,
+        };
+
+        while guard.processed < guard.original_len {
+            let raw = guard.soa.raw_mut();
+            let cur = unsafe { raw.offset(guard.processed) };
+            if f(cur) {
+                if guard.deleted > 0 {
+                    unsafe {
+                        cur.copy_to(raw.offset(guard.processed - guard.deleted), 1);
+                    }
+                }
+                guard.processed += 1;
+            } else {
+                // Advance first in case the destructor panics
+                guard.processed += 1;
+                guard.deleted += 1;
+                drop(unsafe { cur.get() });
+            }
+        }
+    }
+
+    /// Returns a [`SliceRef`] over the elements.
+    ///
+    /// Unlike dereferencing, this doesn't need to refresh the cached pointers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa: ArraySoa<Foo, 4> = [Foo(1), Foo(2)].into_iter().collect();
+    /// assert_eq!(soa.as_slice(), [Foo(1), Foo(2)]);
+    /// ```
+    pub fn as_slice(&self) -> SliceRef<'_, T> {
+        let raw = self.raw_ref();
+        unsafe { SliceRef::from_slice(Slice::with_raw(raw), self.len) }
+    }
+
+    /// Returns a [`SliceMut`] over the elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{ArraySoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: ArraySoa<Foo, 4> = [Foo(1), Foo(2)].into_iter().collect();
+    /// soa.as_mut_slice().f0_mut()[0] = 3;
+    /// assert_eq!(soa, [Foo(3), Foo(2)]);
+    /// ```
+    pub fn as_mut_slice(&mut self) -> SliceMut<'_, T> {
+        let raw = self.raw_mut();
+        unsafe { SliceMut::from_slice(Slice::with_raw(raw), self.len) }
+    }
+
+    /// Returns a [`SoaRaw`] pointing into the columns, only to be used for
+    /// reading.
+    fn raw_ref(&self) -> T::Raw {
+        unsafe { T::Raw::from_array::<N>(self.array.as_ptr().cast_mut()) }
+    }
+
+    /// Returns a [`SoaRaw`] pointing into the columns.
+    ///
+    /// The cached slice is replaced with the same pointers. Borrowing the
+    /// columns mutably invalidates any pointers derived from earlier borrows,
+    /// even though their addresses haven't changed, so [`Self::slice`] could
+    /// not otherwise tell that the cache is stale.
+    pub(crate) fn raw_mut(&mut self) -> T::Raw {
+        let raw = unsafe { T::Raw::from_array::<N>(self.array.as_mut_ptr()) };
+        *self.slice.get_mut() = Slice::with_raw(raw);
+        raw
+    }
+
+    /// Returns the cached slice, refreshing it first if the container has
+    /// moved since it was last cached. Mutable access goes through
+    /// [`Self::raw_mut`], which keeps the cache current otherwise.
+    fn slice(&self) -> *mut Slice<T, ()> {
+        let slice = self.slice.get();
+        let raw = self.raw_ref();
+        // SAFETY: If the cached pointers are stale, then the container moved
+        // after any reference derived from them was created, so none of those
+        // references are alive. ArraySoa is not Sync, so no other thread can
+        // be doing the same.
+        unsafe {
+            if (*slice).raw.into_parts() != raw.into_parts() {
+                *slice = Slice::with_raw(raw);
+            }
+        }
+        slice
+    }
+}
+
+impl<T, const N: usize> Drop for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    fn drop(&mut self) {
+        if needs_drop::<T>() {
+            while self.pop().is_some() {}
+        }
+    }
+}
+
+// SAFETY: ArraySoa owns its elements, and the cached pointers only point into
+// the container itself.
+unsafe impl<T, const N: usize> Send for ArraySoa<T, N> where T: Send + Soars {}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArraySoa<T, N>
+where
+    T: Soars,
+{
+    type Item = T::Ref<'a>;
+
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref().into_iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArraySoa<T, N>
+where
+    T: Soars,
+{
+    type Item = T::RefMut<'a>;
+
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref_mut().into_iter()
+    }
+}
+
+// NOTE: Elements are cloned field by field for the same reasons as in the
+// Clone implementation for Soa.
+impl<T, const N: usize> Clone for ArraySoa<T, N>
+where
+    T: Soars + Clone,
+    T::Raw: SoaRawClone,
+{
+    fn clone(&self) -> Self {
+        let mut out = Self::new();
+        let src = self.raw_ref();
+        let dst = out.raw_mut();
+        for i in 0..self.len {
+            // SAFETY: The source element is initialized and the destination
+            // is within the capacity and past the length.
+            unsafe { src.offset(i).clone_to(dst.offset(i)) };
+            out.len += 1;
+        }
+        out
+    }
+}
+
+impl<T, const N: usize> Extend<T> for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    /// Extends the container with the contents of an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator yields more elements than the container has
+    /// room for.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    /// Creates an `ArraySoa` from an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator yields more than `N` elements.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<T, const N: usize> Debug for ArraySoa<T, N>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+impl<T, const N: usize> PartialOrd for ArraySoa<T, N>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.deref().partial_cmp(other.deref())
+    }
+}
+
+impl<T, const N: usize> Ord for ArraySoa<T, N>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deref().cmp(other.deref())
+    }
+}
+
+impl<T, const N: usize> Hash for ArraySoa<T, N>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref().hash(state)
+    }
+}
+
+impl<T, const N: usize> Default for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> AsRef<Slice<T>> for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    fn as_ref(&self) -> &Slice<T> {
+        unsafe { (*self.slice()).as_unsized(self.len) }
+    }
+}
+
+impl<T, const N: usize> AsMut<Slice<T>> for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    fn as_mut(&mut self) -> &mut Slice<T> {
+        // Refreshes the cache so that the pointers are derived from &mut self
+        self.raw_mut();
+        unsafe { self.slice.get_mut().as_unsized_mut(self.len) }
+    }
+}
+
+impl<T, const N: usize> Deref for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    type Target = Slice<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut()
+    }
+}
+
+impl<T, const N: usize> Borrow<Slice<T>> for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    fn borrow(&self) -> &Slice<T> {
+        self.as_ref()
+    }
+}
+
+impl<T, const N: usize> BorrowMut<Slice<T>> for ArraySoa<T, N>
+where
+    T: Soars,
+{
+    fn borrow_mut(&mut self) -> &mut Slice<T> {
+        self.as_mut()
+    }
+}
+
+eq_impl::impl_for!(ArraySoa<T, M>, const M: usize);
+eq_impl::uni!(ArraySoa<T, M>, ArraySoa<T, K>, const M: usize, const K: usize);
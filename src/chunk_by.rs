@@ -0,0 +1,126 @@
+use crate::{Slice, SliceRef, SoaRaw, Soars};
+use core::{iter::FusedIterator, marker::PhantomData};
+
+/// An iterator over a [`Slice`] producing non-overlapping runs of elements
+/// using a predicate to separate them.
+///
+/// This struct is created by the [`chunk_by`] method.
+///
+/// [`chunk_by`]: Slice::chunk_by
+pub struct ChunkBy<'a, T, P>
+where
+    T: 'a + Soars,
+{
+    slice: Slice<T, ()>,
+    len: usize,
+    predicate: P,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T, P> ChunkBy<'a, T, P>
+where
+    T: Soars,
+{
+    pub(crate) fn new(slice: &'a Slice<T>, predicate: P) -> Self {
+        Self {
+            len: slice.len(),
+            slice: unsafe { slice.as_sized() },
+            predicate,
+            marker: PhantomData,
+        }
+    }
+}
+
+// SAFETY: ChunkBy is Send and Sync under the same conditions as &[T].
+unsafe impl<'a, T, P> Send for ChunkBy<'a, T, P>
+where
+    T: 'a + Sync + Soars,
+    P: Send,
+{
+}
+unsafe impl<'a, T, P> Sync for ChunkBy<'a, T, P>
+where
+    T: 'a + Sync + Soars,
+    P: Sync,
+{
+}
+
+impl<'a, T, P> Iterator for ChunkBy<'a, T, P>
+where
+    T: Soars,
+    P: FnMut(T::Ref<'a>, T::Ref<'a>) -> bool,
+{
+    type Item = SliceRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            let mut len = 1;
+            while len < self.len {
+                let (a, b) = unsafe {
+                    let a = self.slice.raw().offset(len - 1);
+                    (a.get_ref(), a.offset(1).get_ref())
+                };
+                if !(self.predicate)(a, b) {
+                    break;
+                }
+                len += 1;
+            }
+            let out = SliceRef {
+                slice: self.slice,
+                len,
+                marker: PhantomData,
+            };
+            self.len -= len;
+            self.slice.raw = unsafe { self.slice.raw().offset(len) };
+            Some(out)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.len == 0 {
+            (0, Some(0))
+        } else {
+            (1, Some(self.len))
+        }
+    }
+}
+
+impl<'a, T, P> DoubleEndedIterator for ChunkBy<'a, T, P>
+where
+    T: Soars,
+    P: FnMut(T::Ref<'a>, T::Ref<'a>) -> bool,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            let mut start = self.len - 1;
+            while start > 0 {
+                let (a, b) = unsafe {
+                    let a = self.slice.raw().offset(start - 1);
+                    (a.get_ref(), a.offset(1).get_ref())
+                };
+                if !(self.predicate)(a, b) {
+                    break;
+                }
+                start -= 1;
+            }
+            let len = self.len - start;
+            self.len = start;
+            Some(SliceRef {
+                slice: Slice::with_raw(unsafe { self.slice.raw().offset(start) }),
+                len,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+impl<'a, T, P> FusedIterator for ChunkBy<'a, T, P>
+where
+    T: Soars,
+    P: FnMut(T::Ref<'a>, T::Ref<'a>) -> bool,
+{
+}
@@ -1,5 +1,5 @@
 use crate::{Slice, SliceRef, SoaRaw, Soars};
-use std::marker::PhantomData;
+use core::{iter::FusedIterator, marker::PhantomData};
 
 /// An iterator over a [`Slice`] in (non-overlapping) chunks of `chunk_size`
 /// elements.
@@ -47,6 +47,10 @@ where
     }
 }
 
+// SAFETY: ChunksExact is Send and Sync under the same conditions as &[T].
+unsafe impl<'a, T> Send for ChunksExact<'a, T> where T: 'a + Sync + Soars {}
+unsafe impl<'a, T> Sync for ChunksExact<'a, T> where T: 'a + Sync + Soars {}
+
 impl<'a, T> Iterator for ChunksExact<'a, T>
 where
     T: Soars,
@@ -67,4 +71,30 @@ where
             Some(out)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.parts_remaining, Some(self.parts_remaining))
+    }
 }
+
+impl<'a, T> DoubleEndedIterator for ChunksExact<'a, T>
+where
+    T: Soars,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.parts_remaining == 0 {
+            None
+        } else {
+            self.parts_remaining -= 1;
+            let offset = self.parts_remaining * self.chunk_size;
+            Some(SliceRef {
+                slice: Slice::with_raw(unsafe { self.slice.raw().offset(offset) }),
+                len: self.chunk_size,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for ChunksExact<'a, T> where T: Soars {}
+impl<'a, T> ExactSizeIterator for ChunksExact<'a, T> where T: Soars {}
@@ -0,0 +1,105 @@
+use crate::{Slice, SliceMut, SoaRaw, Soars};
+use core::{iter::FusedIterator, marker::PhantomData};
+
+/// An iterator over a [`Slice`] in (non-overlapping) mutable chunks of
+/// `chunk_size` elements.
+///
+/// When the slice len is not evenly divided by the chunk size, the last up to
+/// `chunk_size-1` elements will be omitted but can be retrieved from the
+/// [`into_remainder`] function from the iterator.
+///
+/// This struct is created by the [`chunks_exact_mut`] method.
+///
+/// [`into_remainder`]: ChunksExactMut::into_remainder
+/// [`chunks_exact_mut`]: Slice::chunks_exact_mut
+pub struct ChunksExactMut<'a, T>
+where
+    T: 'a + Soars,
+{
+    slice: Slice<T, ()>,
+    remainder: SliceMut<'a, T>,
+    parts_remaining: usize,
+    chunk_size: usize,
+}
+
+impl<'a, T> ChunksExactMut<'a, T>
+where
+    T: Soars,
+{
+    pub(crate) fn new(slice: &'a mut Slice<T>, chunk_size: usize) -> Self {
+        let len = slice.len();
+        let rem_len = len % chunk_size;
+        let fst_len = len - rem_len;
+        let slice = unsafe { slice.as_sized() };
+        let remainder = SliceMut {
+            slice: Slice::with_raw(unsafe { slice.raw().offset(fst_len) }),
+            len: rem_len,
+            marker: PhantomData,
+        };
+        Self {
+            slice,
+            remainder,
+            parts_remaining: fst_len / chunk_size,
+            chunk_size,
+        }
+    }
+
+    /// Returns the remainder of the original slice that is not going to be
+    /// returned by the iterator.
+    pub fn into_remainder(self) -> SliceMut<'a, T> {
+        self.remainder
+    }
+}
+
+// SAFETY: ChunksExactMut is Send and Sync under the same conditions as
+// &mut [T].
+unsafe impl<'a, T> Send for ChunksExactMut<'a, T> where T: 'a + Send + Soars {}
+unsafe impl<'a, T> Sync for ChunksExactMut<'a, T> where T: 'a + Sync + Soars {}
+
+impl<'a, T> Iterator for ChunksExactMut<'a, T>
+where
+    T: Soars,
+{
+    type Item = SliceMut<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.parts_remaining == 0 {
+            None
+        } else {
+            let out = SliceMut {
+                slice: self.slice,
+                len: self.chunk_size,
+                marker: PhantomData,
+            };
+            self.parts_remaining -= 1;
+            self.slice.raw = unsafe { self.slice.raw().offset(self.chunk_size) };
+            Some(out)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.parts_remaining, Some(self.parts_remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ChunksExactMut<'a, T>
+where
+    T: Soars,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.parts_remaining == 0 {
+            None
+        } else {
+            self.parts_remaining -= 1;
+            let offset = self.parts_remaining * self.chunk_size;
+            Some(SliceMut {
+                slice: Slice::with_raw(unsafe { self.slice.raw().offset(offset) }),
+                len: self.chunk_size,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for ChunksExactMut<'a, T> where T: Soars {}
+impl<'a, T> ExactSizeIterator for ChunksExactMut<'a, T> where T: Soars {}
@@ -0,0 +1,211 @@
+use crate::{Slice, SliceMut, SliceRef, SoaRaw, SoaRawClone, Soars};
+use core::mem::MaybeUninit;
+
+/// An iterator over a [`Slice`] in (non-overlapping) chunks of `N` elements,
+/// where each chunk has a fixed-size array for each field.
+///
+/// When the slice len is not evenly divided by `N`, the last up to `N-1`
+/// elements will be omitted but can be retrieved from the [`remainder`]
+/// function from the iterator, or as a full chunk using [`remainder_padded`].
+///
+/// This struct is created by the [`chunks_lanes`] method.
+///
+/// [`remainder`]: ChunksLanes::remainder
+/// [`remainder_padded`]: ChunksLanes::remainder_padded
+/// [`chunks_lanes`]: Slice::chunks_lanes
+pub struct ChunksLanes<'a, T, const N: usize>
+where
+    T: 'a + Soars,
+{
+    slice: Slice<T, ()>,
+    remainder: SliceRef<'a, T>,
+    parts_remaining: usize,
+}
+
+impl<'a, T, const N: usize> ChunksLanes<'a, T, N>
+where
+    T: Soars,
+{
+    pub(crate) fn new(slice: &'a Slice<T>) -> Self {
+        let len = slice.len();
+        let rem_len = len % N;
+        let fst_len = len - rem_len;
+        let remainder = slice.idx(fst_len..);
+        let slice = unsafe { slice.as_sized() };
+        Self {
+            slice,
+            remainder,
+            parts_remaining: fst_len / N,
+        }
+    }
+
+    /// Returns the remainder of the original slice that has not been yielded by
+    /// the iterator.
+    pub fn remainder(&self) -> &Slice<T> {
+        self.remainder.as_ref()
+    }
+
+    /// Returns the remainder of the original slice, padded to `N` elements
+    /// with clones of `fill`.
+    ///
+    /// Use the `as_chunk` method of the returned SoA array to process it with
+    /// the same code as the other chunks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars};
+    /// # #[derive(Soars, Clone, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(u32);
+    /// let soa: Soa<Foo> = (0..6).map(Foo).collect();
+    /// let chunks = soa.chunks_lanes::<4>();
+    /// let padded = chunks.remainder_padded(Foo(0));
+    /// assert_eq!(padded.as_chunk(), FooChunk(&[4, 5, 0, 0]));
+    /// ```
+    pub fn remainder_padded(&self, fill: T) -> T::Array<N>
+    where
+        T: Clone,
+        T::Raw: SoaRawClone,
+    {
+        pad(self.remainder.raw(), self.remainder.len(), fill)
+    }
+}
+
+// SAFETY: ChunksLanes is Send and Sync under the same conditions as &[T].
+unsafe impl<'a, T, const N: usize> Send for ChunksLanes<'a, T, N> where T: 'a + Sync + Soars {}
+unsafe impl<'a, T, const N: usize> Sync for ChunksLanes<'a, T, N> where T: 'a + Sync + Soars {}
+
+impl<'a, T, const N: usize> Iterator for ChunksLanes<'a, T, N>
+where
+    T: Soars,
+{
+    type Item = T::Chunk<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.parts_remaining == 0 {
+            None
+        } else {
+            let out = unsafe { self.slice.raw().chunk::<N>() };
+            self.parts_remaining -= 1;
+            self.slice.raw = unsafe { self.slice.raw().offset(N) };
+            Some(out)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.parts_remaining, Some(self.parts_remaining))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for ChunksLanes<'_, T, N> where T: Soars {}
+
+/// An iterator over a [`Slice`] in (non-overlapping) mutable chunks of `N`
+/// elements, where each chunk has a fixed-size array for each field.
+///
+/// When the slice len is not evenly divided by `N`, the last up to `N-1`
+/// elements will be omitted but can be retrieved from the [`into_remainder`]
+/// function from the iterator.
+///
+/// This struct is created by the [`chunks_lanes_mut`] method.
+///
+/// [`into_remainder`]: ChunksLanesMut::into_remainder
+/// [`chunks_lanes_mut`]: Slice::chunks_lanes_mut
+pub struct ChunksLanesMut<'a, T, const N: usize>
+where
+    T: 'a + Soars,
+{
+    slice: Slice<T, ()>,
+    remainder: SliceMut<'a, T>,
+    parts_remaining: usize,
+}
+
+impl<'a, T, const N: usize> ChunksLanesMut<'a, T, N>
+where
+    T: Soars,
+{
+    pub(crate) fn new(slice: &'a mut Slice<T>) -> Self {
+        let len = slice.len();
+        let rem_len = len % N;
+        let fst_len = len - rem_len;
+        let slice = unsafe { slice.as_sized() };
+        let remainder =
+            unsafe { SliceMut::from_slice(Slice::with_raw(slice.raw().offset(fst_len)), rem_len) };
+        Self {
+            slice,
+            remainder,
+            parts_remaining: fst_len / N,
+        }
+    }
+
+    /// Returns the remainder of the original slice that is not going to be
+    /// returned by the iterator.
+    pub fn into_remainder(self) -> SliceMut<'a, T> {
+        self.remainder
+    }
+
+    /// Returns a copy of the remainder of the original slice, padded to `N`
+    /// elements with clones of `fill`.
+    ///
+    /// See [`ChunksLanes::remainder_padded`].
+    pub fn remainder_padded(&self, fill: T) -> T::Array<N>
+    where
+        T: Clone,
+        T::Raw: SoaRawClone,
+    {
+        pad(self.remainder.raw(), self.remainder.len(), fill)
+    }
+}
+
+// SAFETY: ChunksLanesMut is Send and Sync under the same conditions as
+// &mut [T].
+unsafe impl<'a, T, const N: usize> Send for ChunksLanesMut<'a, T, N> where T: 'a + Send + Soars {}
+unsafe impl<'a, T, const N: usize> Sync for ChunksLanesMut<'a, T, N> where T: 'a + Sync + Soars {}
+
+impl<'a, T, const N: usize> Iterator for ChunksLanesMut<'a, T, N>
+where
+    T: Soars,
+{
+    type Item = T::ChunkMut<'a, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.parts_remaining == 0 {
+            None
+        } else {
+            let out = unsafe { self.slice.raw().chunk_mut::<N>() };
+            self.parts_remaining -= 1;
+            self.slice.raw = unsafe { self.slice.raw().offset(N) };
+            Some(out)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.parts_remaining, Some(self.parts_remaining))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for ChunksLanesMut<'_, T, N> where T: Soars {}
+
+/// Clones the `len` elements at `raw` into a new SoA array, filling the rest
+/// with clones of `fill`.
+fn pad<T, const N: usize>(raw: T::Raw, len: usize, fill: T) -> T::Array<N>
+where
+    T: Soars + Clone,
+    T::Raw: SoaRawClone,
+{
+    debug_assert!(len < N);
+    let mut out = MaybeUninit::<T::Array<N>>::uninit();
+    let dst = unsafe { T::Raw::from_array::<N>(out.as_mut_ptr()) };
+    for i in 0..len {
+        // SAFETY: The source elements are initialized and the destination
+        // array has room for N elements.
+        unsafe { raw.offset(i).clone_to(dst.offset(i)) };
+    }
+    for i in len..N - 1 {
+        unsafe { dst.offset(i).set(fill.clone()) };
+    }
+    unsafe { dst.offset(N - 1).set(fill) };
+    // SAFETY: All N elements were initialized above. If a clone panicked,
+    // the elements written so far are leaked.
+    unsafe { out.assume_init() }
+}
@@ -0,0 +1,92 @@
+use crate::{Slice, SliceMut, SoaRaw, Soars};
+use core::{iter::FusedIterator, marker::PhantomData};
+
+/// An iterator over a [`Slice`] in (non-overlapping) mutable chunks of
+/// `chunk_size` elements, starting at the beginning of the slice.
+///
+/// When the slice len is not evenly divided by the chunk size, the last slice
+/// of the iteration will be the remainder.
+///
+/// This struct is created by the [`chunks_mut`] method.
+///
+/// [`chunks_mut`]: Slice::chunks_mut
+pub struct ChunksMut<'a, T>
+where
+    T: 'a + Soars,
+{
+    slice: Slice<T, ()>,
+    len: usize,
+    chunk_size: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> ChunksMut<'a, T>
+where
+    T: Soars,
+{
+    pub(crate) fn new(slice: &'a mut Slice<T>, chunk_size: usize) -> Self {
+        Self {
+            len: slice.len(),
+            slice: unsafe { slice.as_sized() },
+            chunk_size,
+            marker: PhantomData,
+        }
+    }
+}
+
+// SAFETY: ChunksMut is Send and Sync under the same conditions as &mut [T].
+unsafe impl<'a, T> Send for ChunksMut<'a, T> where T: 'a + Send + Soars {}
+unsafe impl<'a, T> Sync for ChunksMut<'a, T> where T: 'a + Sync + Soars {}
+
+impl<'a, T> Iterator for ChunksMut<'a, T>
+where
+    T: Soars,
+{
+    type Item = SliceMut<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            let len = self.chunk_size.min(self.len);
+            let out = SliceMut {
+                slice: self.slice,
+                len,
+                marker: PhantomData,
+            };
+            self.len -= len;
+            self.slice.raw = unsafe { self.slice.raw().offset(len) };
+            Some(out)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.len.div_ceil(self.chunk_size);
+        (n, Some(n))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ChunksMut<'a, T>
+where
+    T: Soars,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            let len = match self.len % self.chunk_size {
+                0 => self.chunk_size,
+                rem => rem,
+            };
+            self.len -= len;
+            Some(SliceMut {
+                slice: Slice::with_raw(unsafe { self.slice.raw().offset(self.len) }),
+                len,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for ChunksMut<'a, T> where T: Soars {}
+impl<'a, T> ExactSizeIterator for ChunksMut<'a, T> where T: Soars {}
@@ -0,0 +1,19 @@
+/// ```
+/// use soa_rs::Soars;
+/// #[derive(Soars)]
+/// enum Error {
+///     HttpError,
+///     IoError(u8),
+/// }
+/// ```
+mod snake_case_variant_collision {
+    /// ```compile_fail
+    /// use soa_rs::Soars;
+    /// #[derive(Soars)]
+    /// enum Error {
+    ///     HttpError,
+    ///     HTTPError,
+    /// }
+    /// ```
+    mod fail {}
+}
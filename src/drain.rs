@@ -0,0 +1,183 @@
+use crate::{
+    iter_raw::{iter_with_raw, IterRaw, IterRawAdapter},
+    Allocator, Global, Slice, Soa, SoaRaw, Soars,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+    mem::needs_drop,
+};
+
+/// A draining iterator for [`Soa`].
+///
+/// This struct is created by [`Soa::drain`]. See its documentation for more.
+///
+/// [`Soa`]: crate::Soa
+/// [`Soa::drain`]: crate::Soa::drain
+pub struct Drain<'a, T, A = Global>
+where
+    T: 'a + Soars,
+    A: Allocator,
+{
+    pub(crate) iter_raw: IterRaw<T, Self>,
+    pub(crate) soa: &'a mut Soa<T, A>,
+    /// The index of the first element after the drained range.
+    pub(crate) tail_start: usize,
+    /// The number of elements after the drained range.
+    pub(crate) tail_len: usize,
+}
+
+impl<T, A> IterRawAdapter<T> for Drain<'_, T, A>
+where
+    T: Soars,
+    A: Allocator,
+{
+    type Item = T;
+
+    fn item_from_raw(raw: T::Raw) -> Self::Item {
+        unsafe { raw.get() }
+    }
+}
+
+impl<T, A> Debug for Drain<'_, T, A>
+where
+    T: Soars,
+    A: Allocator,
+    for<'b> T::Ref<'b>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, A> Drain<'_, T, A>
+where
+    T: Soars,
+    A: Allocator,
+{
+    /// Returns an immutable slice of all elements that have not been yielded
+    /// yet.
+    pub fn as_slice(&self) -> &Slice<T> {
+        unsafe { self.iter_raw.as_slice() }
+    }
+
+    /// Returns a mutable slice of all elements that have not been yielded yet.
+    pub fn as_mut_slice(&mut self) -> &mut Slice<T> {
+        unsafe { self.iter_raw.as_mut_slice() }
+    }
+
+    /// Returns a reference to the underlying allocator.
+    pub fn allocator(&self) -> &A {
+        self.soa.allocator()
+    }
+
+    /// Fills the gap between the end of the [`Soa`] and the start of the tail
+    /// with elements from `replace_with`. Returns whether the gap was filled.
+    ///
+    /// # Safety
+    ///
+    /// All the drained elements must have been yielded.
+    pub(crate) unsafe fn fill<I>(&mut self, replace_with: &mut I) -> bool
+    where
+        I: Iterator<Item = T>,
+    {
+        while self.soa.len < self.tail_start {
+            let Some(element) = replace_with.next() else {
+                return false;
+            };
+            unsafe {
+                self.soa.raw().offset(self.soa.len).set(element);
+            }
+            self.soa.len += 1;
+        }
+        true
+    }
+
+    /// Makes room for `additional` more elements before the tail.
+    ///
+    /// # Safety
+    ///
+    /// All the drained elements must have been yielded.
+    pub(crate) unsafe fn move_tail(&mut self, additional: usize) {
+        let len = self.soa.len;
+        let used = self.tail_start + self.tail_len;
+        let new_used = used.checked_add(additional).expect("capacity overflow");
+
+        // Growing only preserves the first `len` elements, so the tail has to
+        // be counted while reallocating. Reserving can only panic when the
+        // layout overflows, which is checked first so that the uninitialized
+        // gap before the tail is never observable.
+        <T::Raw as SoaRaw>::layout(new_used).expect("capacity overflow");
+        self.soa.len = used;
+        self.soa.reserve_exact(additional);
+        self.soa.len = len;
+
+        let new_tail_start = self.tail_start + additional;
+        unsafe {
+            let src = self.soa.raw().offset(self.tail_start);
+            let dst = self.soa.raw().offset(new_tail_start);
+            src.copy_to(dst, self.tail_len);
+        }
+        self.tail_start = new_tail_start;
+    }
+}
+
+impl<T, A> Drop for Drain<'_, T, A>
+where
+    T: Soars,
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        /// Moves the tail back into place, even if dropping a drained element
+        /// panics.
+        struct DropGuard<'r, 'a, T, A>(&'r mut Drain<'a, T, A>)
+        where
+            T: Soars,
+            A: Allocator;
+
+        impl<T, A> Drop for DropGuard<'_, '_, T, A>
+        where
+            T: Soars,
+            A: Allocator,
+        {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                if drain.tail_len > 0 {
+                    let start = drain.soa.len;
+                    if drain.tail_start != start {
+                        unsafe {
+                            let src = drain.soa.raw().offset(drain.tail_start);
+                            let dst = drain.soa.raw().offset(start);
+                            src.copy_to(dst, drain.tail_len);
+                        }
+                    }
+                }
+                drain.soa.len += drain.tail_len;
+            }
+        }
+
+        let guard = DropGuard(self);
+        if needs_drop::<T>() {
+            for _ in guard.0.iter_raw.by_ref() {}
+        } else {
+            guard.0.iter_raw.len = 0;
+        }
+    }
+}
+
+// SAFETY: Drain owns the elements it yields and otherwise behaves like
+// &mut Soa<T>, so it is Send and Sync under the same conditions.
+unsafe impl<'a, T, A> Send for Drain<'a, T, A>
+where
+    T: 'a + Send + Soars,
+    A: Send + Allocator,
+{
+}
+unsafe impl<'a, T, A> Sync for Drain<'a, T, A>
+where
+    T: 'a + Sync + Soars,
+    A: Sync + Allocator,
+{
+}
+
+iter_with_raw!(Drain<'a, T, A>, 'a; A: Allocator);
@@ -31,19 +31,19 @@ macro_rules! bi {
 }
 
 macro_rules! impl_for {
-    ($t:ty) => {
-        $crate::eq_impl::bi!($t, Vec<T>);
-        $crate::eq_impl::bi!($t, [T]);
-        $crate::eq_impl::bi!($t, &[T]);
-        $crate::eq_impl::bi!($t, &mut [T]);
-        $crate::eq_impl::bi!($t, [T; N], const N: usize);
-        $crate::eq_impl::bi!($t, &[T; N], const N: usize);
-        $crate::eq_impl::bi!($t, &mut [T; N], const N: usize);
-        $crate::eq_impl::bi!($t, Slice<T>);
-        $crate::eq_impl::uni!($t, SliceRef<'_, T>);
-        $crate::eq_impl::uni!($t, SliceMut<'_, T>);
-        $crate::eq_impl::uni!($t, Soa<T>);
-        impl<'a, T> Eq for $t where T: Soars, for<'b> T::Ref<'b>: Eq {}
+    ($t:ty $(, $($g:tt)+)?) => {
+        $crate::eq_impl::bi!($t, ::alloc::vec::Vec<T> $(, $($g)+)?);
+        $crate::eq_impl::bi!($t, [T] $(, $($g)+)?);
+        $crate::eq_impl::bi!($t, &[T] $(, $($g)+)?);
+        $crate::eq_impl::bi!($t, &mut [T] $(, $($g)+)?);
+        $crate::eq_impl::bi!($t, [T; N], const N: usize $(, $($g)+)?);
+        $crate::eq_impl::bi!($t, &[T; N], const N: usize $(, $($g)+)?);
+        $crate::eq_impl::bi!($t, &mut [T; N], const N: usize $(, $($g)+)?);
+        $crate::eq_impl::bi!($t, Slice<T> $(, $($g)+)?);
+        $crate::eq_impl::uni!($t, SliceRef<'_, T> $(, $($g)+)?);
+        $crate::eq_impl::uni!($t, SliceMut<'_, T> $(, $($g)+)?);
+        $crate::eq_impl::uni!($t, Soa<T, B>, B: $crate::Allocator $(, $($g)+)?);
+        impl<'a, T $(, $($g)+)?> Eq for $t where T: Soars, for<'b> T::Ref<'b>: Eq {}
     };
 }
 
@@ -0,0 +1,122 @@
+use crate::{Allocator, Global, Slice, Soa, SoaRaw, Soars};
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+};
+
+/// An iterator which uses a closure to determine if an element should be
+/// removed.
+///
+/// This struct is created by [`Soa::extract_if`]. See its documentation for
+/// more.
+///
+/// [`Soa::extract_if`]: crate::Soa::extract_if
+pub struct ExtractIf<'a, T, F, A = Global>
+where
+    T: Soars,
+    A: Allocator,
+{
+    soa: &'a mut Soa<T, A>,
+    /// The index of the next element to inspect.
+    idx: usize,
+    /// The index past the last element to inspect.
+    end: usize,
+    /// The number of elements removed so far.
+    del: usize,
+    /// The length of the container before extraction began.
+    old_len: usize,
+    pred: F,
+}
+
+impl<'a, T, F, A> ExtractIf<'a, T, F, A>
+where
+    T: Soars,
+    A: Allocator,
+{
+    pub(crate) fn new(soa: &'a mut Soa<T, A>, start: usize, end: usize, pred: F) -> Self {
+        let old_len = soa.len;
+        // Guard against the iterator being leaked, in which case the
+        // elements in the range may have been moved out or left as
+        // duplicates.
+        soa.len = 0;
+        Self {
+            soa,
+            idx: start,
+            end,
+            del: 0,
+            old_len,
+            pred,
+        }
+    }
+}
+
+impl<T, F, A> Iterator for ExtractIf<'_, T, F, A>
+where
+    T: Soars,
+    F: FnMut(T::RefMut<'_>) -> bool,
+    A: Allocator,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.idx < self.end {
+            let i = self.idx;
+            let raw = unsafe { self.soa.raw().offset(i) };
+            let extract = (self.pred)(unsafe { raw.get_mut() });
+            // Advance before moving anything so that a panic in the predicate
+            // leaves the iterator in a consistent state.
+            self.idx += 1;
+            if extract {
+                self.del += 1;
+                return Some(unsafe { raw.get() });
+            } else if self.del > 0 {
+                unsafe {
+                    raw.copy_to(self.soa.raw().offset(i - self.del), 1);
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.idx))
+    }
+}
+
+impl<T, F, A> FusedIterator for ExtractIf<'_, T, F, A>
+where
+    T: Soars,
+    F: FnMut(T::RefMut<'_>) -> bool,
+    A: Allocator,
+{
+}
+
+impl<T, F, A> Drop for ExtractIf<'_, T, F, A>
+where
+    T: Soars,
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        if self.del > 0 && self.idx < self.old_len {
+            unsafe {
+                let src = self.soa.raw().offset(self.idx);
+                let dst = self.soa.raw().offset(self.idx - self.del);
+                src.copy_to(dst, self.old_len - self.idx);
+            }
+        }
+        self.soa.len = self.old_len - self.del;
+    }
+}
+
+impl<T, F, A> Debug for ExtractIf<'_, T, F, A>
+where
+    T: Soars,
+    for<'b> T::Ref<'b>: Debug,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let remaining = Slice::<T, ()>::with_raw(unsafe { self.soa.raw().offset(self.idx) });
+        let remaining = unsafe { remaining.as_unsized(self.end - self.idx) };
+        f.debug_tuple("ExtractIf").field(&remaining).finish()
+    }
+}
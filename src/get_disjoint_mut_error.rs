@@ -0,0 +1,28 @@
+use core::fmt::{self, Display, Formatter};
+
+/// The error type returned by [`Slice::get_disjoint_mut`].
+///
+/// This mirrors [`core::slice::GetDisjointMutError`], which requires Rust
+/// 1.86.
+///
+/// [`Slice::get_disjoint_mut`]: crate::Slice::get_disjoint_mut
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GetDisjointMutError {
+    /// An index provided was out-of-bounds for the slice.
+    IndexOutOfBounds,
+    /// Two indices provided were overlapping.
+    OverlappingIndices,
+}
+
+impl Display for GetDisjointMutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::IndexOutOfBounds => "an index is out of bounds",
+            Self::OverlappingIndices => "there were overlapping indices",
+        };
+        f.write_str(message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GetDisjointMutError {}
@@ -1,7 +1,9 @@
 use crate::{Slice, SliceMut, SliceRef, SoaRaw, Soars};
-use std::{
+use core::{
     marker::PhantomData,
-    ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive},
+    ops::{
+        Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+    },
 };
 
 /// A helper trait for indexing operations.
@@ -195,3 +197,39 @@ where
         (*self.start()..*self.end() + 1).get_mut(slice)
     }
 }
+
+/// Converts `range` to a [`Range`] of indices into a slice of length `len`.
+///
+/// # Panics
+///
+/// Panics if the start of the range is greater than the end or if the end of
+/// the range is greater than `len`, matching the behavior of slice indexing.
+pub(crate) fn slice_range<R>(range: R, len: usize) -> Range<usize>
+where
+    R: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start
+            .checked_add(1)
+            .expect("attempted to index slice from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end
+            .checked_add(1)
+            .expect("attempted to index slice up to maximum usize"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if start > end {
+        panic!("slice index starts at {start} but ends at {end}");
+    }
+    if end > len {
+        panic!("range end index {end} out of range for slice of length {len}");
+    }
+
+    start..end
+}
@@ -1,8 +1,8 @@
 use crate::{
     iter_raw::{iter_with_raw, IterRaw, IterRawAdapter},
-    Slice, Soa, SoaRaw, Soars,
+    Allocator, Global, Slice, Soa, SoaRaw, Soars,
 };
-use std::{
+use core::{
     fmt::Debug,
     iter::FusedIterator,
     mem::{needs_drop, size_of},
@@ -15,18 +15,21 @@ use std::{
 ///
 /// [`Soa`]: crate::Soa
 /// [`into_iter`]: crate::Soa::into_iter
-pub struct IntoIter<T>
+pub struct IntoIter<T, A = Global>
 where
     T: Soars,
+    A: Allocator,
 {
     pub(crate) iter_raw: IterRaw<T, Self>,
     pub(crate) ptr: *mut u8,
     pub(crate) cap: usize,
+    pub(crate) alloc: A,
 }
 
-impl<T> IterRawAdapter<T> for IntoIter<T>
+impl<T, A> IterRawAdapter<T> for IntoIter<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     type Item = T;
 
@@ -44,19 +47,36 @@ where
     }
 }
 
-impl<T> Debug for IntoIter<T>
+impl<T, A> Debug for IntoIter<T, A>
 where
     T: Soars,
+    A: Allocator,
     for<'a> T::Ref<'a>: Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{:?}", self.as_slice())
     }
 }
 
-impl<T> IntoIter<T>
+// SAFETY: IntoIter owns its remaining elements, so it is Send and Sync under the
+// same conditions as Soa.
+unsafe impl<T, A> Send for IntoIter<T, A>
+where
+    T: Send + Soars,
+    A: Send + Allocator,
+{
+}
+unsafe impl<T, A> Sync for IntoIter<T, A>
+where
+    T: Sync + Soars,
+    A: Sync + Allocator,
+{
+}
+
+impl<T, A> IntoIter<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     /// Returns an immutable slice of all elements that have not been yielded
     /// yet.
@@ -68,11 +88,17 @@ where
     pub fn as_mut_slice(&mut self) -> &mut Slice<T> {
         unsafe { self.iter_raw.as_mut_slice() }
     }
+
+    /// Returns a reference to the underlying allocator.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
 }
 
-impl<T> Drop for IntoIter<T>
+impl<T, A> Drop for IntoIter<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn drop(&mut self) {
         if needs_drop::<T>() {
@@ -80,9 +106,11 @@ where
         }
 
         if size_of::<T>() > 0 && self.cap > 0 {
-            unsafe { <T::Raw as SoaRaw>::from_parts(self.ptr, self.cap).dealloc(self.cap) }
+            unsafe {
+                <T::Raw as SoaRaw>::from_parts(self.ptr, self.cap).dealloc(self.cap, &self.alloc)
+            }
         }
     }
 }
 
-iter_with_raw!(IntoIter<T>);
+iter_with_raw!(IntoIter<T, A>; A: Allocator);
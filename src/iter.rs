@@ -2,7 +2,7 @@ use crate::{
     iter_raw::{iter_with_raw, IterRaw, IterRawAdapter},
     Slice, SoaRaw, Soars,
 };
-use std::{
+use core::{
     fmt::{self, Debug, Formatter},
     iter::FusedIterator,
     marker::PhantomData,
@@ -60,6 +60,10 @@ where
     }
 }
 
+// SAFETY: Iter is Send and Sync under the same conditions as &[T].
+unsafe impl<'a, T> Send for Iter<'a, T> where T: 'a + Sync + Soars {}
+unsafe impl<'a, T> Sync for Iter<'a, T> where T: 'a + Sync + Soars {}
+
 impl<'a, T> Iter<'a, T>
 where
     T: Soars,
@@ -2,7 +2,7 @@ use crate::{
     iter_raw::{iter_with_raw, IterRaw, IterRawAdapter},
     Slice, SliceMut, SoaRaw, Soars,
 };
-use std::{
+use core::{
     fmt::{self, Debug, Formatter},
     iter::FusedIterator,
     marker::PhantomData,
@@ -59,6 +59,10 @@ where
     }
 }
 
+// SAFETY: IterMut is Send and Sync under the same conditions as &mut [T].
+unsafe impl<'a, T> Send for IterMut<'a, T> where T: 'a + Send + Soars {}
+unsafe impl<'a, T> Sync for IterMut<'a, T> where T: 'a + Sync + Soars {}
+
 impl<'a, T> IterMut<'a, T>
 where
     T: Soars,
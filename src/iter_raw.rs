@@ -1,5 +1,5 @@
 use crate::{Slice, SoaRaw, Soars};
-use std::{fmt::Debug, iter::FusedIterator, marker::PhantomData};
+use core::{fmt::Debug, iter::FusedIterator, marker::PhantomData};
 
 pub trait IterRawAdapter<T>
 where
@@ -56,7 +56,7 @@ where
     A: IterRawAdapter<T>,
     for<'a> T::Ref<'a>: Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         unsafe { self.slice.as_unsized(self.len).fmt(f) }
     }
 }
@@ -185,8 +185,8 @@ where
 }
 
 macro_rules! iter_with_raw {
-    ($t:ty $(,$lifetime:tt)?) => {
-        impl<$($lifetime,)? T> Iterator for $t
+    ($t:ty $(,$lifetime:tt)? $(; $($g:tt)+)?) => {
+        impl<$($lifetime,)? T $(, $($g)+)?> Iterator for $t
         where
             T: $($lifetime +)? Soars,
         {
@@ -227,7 +227,7 @@ macro_rules! iter_with_raw {
             }
         }
 
-        impl<$($lifetime,)? T> DoubleEndedIterator for $t
+        impl<$($lifetime,)? T $(, $($g)+)?> DoubleEndedIterator for $t
         where
             T: $($lifetime +)? Soars,
         {
@@ -236,10 +236,10 @@ macro_rules! iter_with_raw {
             }
         }
 
-        impl<$($lifetime,)? T> FusedIterator for $t where T: $($lifetime +)? Soars {}
-        impl<$($lifetime,)? T> ExactSizeIterator for $t where T: $($lifetime +)? Soars {}
+        impl<$($lifetime,)? T $(, $($g)+)?> FusedIterator for $t where T: $($lifetime +)? Soars {}
+        impl<$($lifetime,)? T $(, $($g)+)?> ExactSizeIterator for $t where T: $($lifetime +)? Soars {}
 
-        impl<$($lifetime,)? T> AsRef<Slice<T>> for $t where T: $($lifetime +)? Soars {
+        impl<$($lifetime,)? T $(, $($g)+)?> AsRef<Slice<T>> for $t where T: $($lifetime +)? Soars {
             fn as_ref(&self) -> &Slice<T> {
                 unsafe { self.iter_raw.as_slice() }
            }
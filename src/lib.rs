@@ -128,8 +128,26 @@
 //! assert_eq!(soa.f0(), [5, 10]);
 //! ```
 //!
+//! # Serde
+//!
+//! The `serde` feature implements `Serialize` and `Deserialize` for [`Soa`]
+//! and [`Slice`], and for the types generated by [`Soars`] when the item is
+//! tagged with `#[soa(serde)]`. They serialize as a sequence of rows by
+//! default, or as a map from field name to column with the `serde::columnar`
+//! module. Rows require the item type to implement the serde traits, while
+//! columns only require its fields to.
+//!
+//! # `no_std`
+//!
+//! The `std` feature is enabled by default. Disable default features to use
+//! this crate with `#![no_std]`; only the `alloc` crate is required. The
+//! error types only implement `std::error::Error` with the `std` feature.
+//!
 //! [`Soars`]: soa_rs_derive::Soars
 #![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 mod soa;
 pub use soa::Soa;
@@ -165,9 +183,75 @@ mod soa_raw;
 #[doc(hidden)]
 pub use soa_raw::SoaRaw;
 
+// Used by the derive macro, which can't name the alloc crate from crates that
+// don't declare it.
+#[doc(hidden)]
+pub use alloc::alloc::handle_alloc_error;
+
+mod soa_raw_clone;
+#[doc(hidden)]
+pub use soa_raw_clone::SoaRawClone;
+
+mod allocator;
+pub use allocator::{AllocError, Allocator, Global};
+
+mod try_reserve_error;
+pub use try_reserve_error::{TryPushError, TryReserveError, TryReserveErrorKind};
+
+mod get_disjoint_mut_error;
+pub use get_disjoint_mut_error::GetDisjointMutError;
+
+mod array_soa;
+pub use array_soa::ArraySoa;
+
+mod small_soa;
+pub use small_soa::SmallSoa;
+
+mod soa_deque;
+pub use soa_deque::SoaDeque;
+
+mod soa_slot_map;
+pub use soa_slot_map::{SlotKey, SoaSlotMap};
+
+mod ao_soa;
+pub use ao_soa::{AoSoa, AoSoaIter, AoSoaIterMut};
+
+mod chunks;
+pub use chunks::Chunks;
+
+mod chunks_mut;
+pub use chunks_mut::ChunksMut;
+
 mod chunks_exact;
 pub use chunks_exact::ChunksExact;
 
+mod chunks_exact_mut;
+pub use chunks_exact_mut::ChunksExactMut;
+
+mod rchunks;
+pub use rchunks::RChunks;
+
+mod rchunks_exact;
+pub use rchunks_exact::RChunksExact;
+
+mod windows;
+pub use windows::Windows;
+
+mod chunk_by;
+pub use chunk_by::ChunkBy;
+
+mod chunks_lanes;
+pub use chunks_lanes::{ChunksLanes, ChunksLanesMut};
+
+mod extract_if;
+pub use extract_if::ExtractIf;
+
+mod drain;
+pub use drain::Drain;
+
+mod splice;
+pub use splice::Splice;
+
 mod eq_impl;
 mod iter_raw;
 
@@ -177,29 +261,37 @@ pub use soa_array::SoaArray;
 mod as_soa_ref;
 pub use as_soa_ref::AsSoaRef;
 
+#[cfg(feature = "serde")]
+pub mod serde;
+
 /// Derive macro for the [`Soars`] trait.
 ///
 /// Deriving Soars for some struct `Foo` will create the following additional
 /// structs:
 ///
-/// | Struct         | Field type | Use                                          |
-/// |----------------|------------|----------------------------------------------|
-/// | `FooSoaRaw`    | `*mut T`   | Low-level, unsafe memory handling for SoA    |
-/// | `FooRef`       | `&T`       | SoA element reference                        |
-/// | `FooRefMut`    | `&mut T`   | Mutable SoA element reference                |
-/// | `FooSlices`    | `&[T]`     | SoA fields                                   |
-/// | `FooSlicesMut` | `&mut [T]` | Mutable SoA fields                           |
-/// | `FooArray`     | `[T; N]`   | `const`-compatible SoA                       |
-/// | `FooDeref`     |            | SoA [`Deref`] target, provides slice getters |
+/// | Struct         | Field type    | Use                                          |
+/// |----------------|---------------|----------------------------------------------|
+/// | `FooSoaRaw`    | `*mut T`      | Low-level, unsafe memory handling for SoA    |
+/// | `FooRef`       | `&T`          | SoA element reference                        |
+/// | `FooRefMut`    | `&mut T`      | Mutable SoA element reference                |
+/// | `FooSlices`    | `&[T]`        | SoA fields                                   |
+/// | `FooSlicesMut` | `&mut [T]`    | Mutable SoA fields                           |
+/// | `FooArray`     | `[T; N]`      | `const`-compatible SoA                       |
+/// | `FooChunk`     | `&[T; N]`     | Fixed-size SoA chunk                         |
+/// | `FooChunkMut`  | `&mut [T; N]` | Mutable fixed-size SoA chunk                 |
+/// | `FooDeref`     |               | SoA [`Deref`] target, provides slice getters |
 ///
 /// The [`Soars`] trait implementation for `Foo` references these as associated
 /// types. [`AsSoaRef`] is also implemented for `Foo`, `FooRef`, and `FooRefMut`.
+/// Enums generate the same types with a different shape, as well as `FooTag`.
+/// See [Enums](#enums) for details.
 ///
 /// # Derive for generated types
 ///
 /// The `soa_derive` attribute can be used to derive traits for the generated
 /// types. In the example, `Debug` and `PartialEq` will be implemented for
-/// `FooRef`, `FooRefMut`, `FooSlices`, `FooSlicesMut`, and `FooArray`.
+/// `FooRef`, `FooRefMut`, `FooSlices`, `FooSlicesMut`, `FooArray`, `FooChunk`,
+/// and `FooChunkMut`.
 ///
 /// ```
 /// # use soa_rs::{Soars};
@@ -209,6 +301,119 @@ pub use as_soa_ref::AsSoaRef;
 /// assert_eq!(FooRef(&10), FooRef(&10));
 /// ```
 ///
+/// # Generics
+///
+/// Lifetimes, type parameters, const parameters, and where clauses on the
+/// derived type are carried over to each of the generated types.
+///
+/// ```
+/// # use soa_rs::{Soars, soa};
+/// #[derive(Soars, Debug, PartialEq)]
+/// #[soa_derive(Debug, PartialEq)]
+/// struct Particle<F: Copy> {
+///     pos: F,
+///     vel: F,
+/// }
+///
+/// let soa = soa![Particle { pos: 1.0f32, vel: 2.0 }];
+/// assert_eq!(soa.pos(), [1.0]);
+/// ```
+///
+/// # Nested fields
+///
+/// A field whose type also derives [`Soars`] can be tagged with
+/// `#[soa(nested)]` to store the columns of that type in place of a single
+/// column for the field. `FooRef`, `FooRefMut`, `FooSlices`, `FooSlicesMut`,
+/// `FooArray`, `FooChunk`, and `FooChunkMut` use the nested type's
+/// corresponding types for that field, and the slice getters return a
+/// [`SliceRef`] or [`SliceMut`] of the nested type. Any traits listed in
+/// `soa_derive` must also be derived for the nested type's generated types.
+///
+/// ```
+/// # use soa_rs::{Soars, soa};
+/// #[derive(Soars, Debug, PartialEq)]
+/// #[soa_derive(Debug, PartialEq)]
+/// struct Vec3 {
+///     x: f32,
+///     y: f32,
+///     z: f32,
+/// }
+///
+/// #[derive(Soars, Debug, PartialEq)]
+/// #[soa_derive(Debug, PartialEq)]
+/// struct Particle {
+///     #[soa(nested)]
+///     pos: Vec3,
+///     #[soa(nested)]
+///     vel: Vec3,
+/// }
+///
+/// let mut soa = soa![Particle {
+///     pos: Vec3 { x: 1.0, y: 2.0, z: 3.0 },
+///     vel: Vec3 { x: 4.0, y: 5.0, z: 6.0 },
+/// }];
+/// *soa.idx_mut(0).pos.x += 10.0;
+/// assert_eq!(soa.pos().x(), [11.0]);
+/// assert_eq!(soa.vel().z(), [6.0]);
+/// ```
+///
+/// # Enums
+///
+/// Enums store a column of `FooTag`, a fieldless copy of the enum that
+/// identifies each element's variant, plus one column for each variant field.
+/// Columns are named for the variant and the field, such as `click_x` for the
+/// field `x` of the variant `Click`, or `key_0` for the first field of the
+/// tuple variant `Key`. A payload column only holds a value where the tag
+/// selects its variant.
+///
+/// `FooRef` and `FooRefMut` are enums with the same variants as `Foo`, so they
+/// can be matched on directly. They also have a `tag` method, and `is_click`
+/// and `as_click` methods for each variant. `FooRefMut` additionally has
+/// `into_click`, which keeps the lifetime of the reference. Variants with
+/// multiple fields produce tuples of references. Since these methods are
+/// named for the variant in snake case, variants such as `HttpError` and
+/// `HTTPError` that share a snake case name are rejected.
+///
+/// The slice getters are `tags`, plus `as_click` and `as_click_mut` for each
+/// variant with fields. These return `None` unless every element of the slice
+/// holds that variant. `FooSlices` exposes the payload columns as
+/// [`MaybeUninit`] and is used for both shared and mutable slices, since
+/// mutable access to the columns could deinitialize an active field. For the
+/// same reason, `FooChunk` serves as both the shared and the mutable chunk.
+///
+/// ```
+/// # use soa_rs::{Soars, soa};
+/// #[derive(Soars, Debug, PartialEq)]
+/// #[soa_derive(Debug, PartialEq)]
+/// enum Event {
+///     Click { x: i32, y: i32 },
+///     Key(char),
+///     Quit,
+/// }
+///
+/// let mut soa = soa![
+///     Event::Click { x: 1, y: 2 },
+///     Event::Click { x: 3, y: 4 },
+///     Event::Key('a'),
+/// ];
+/// assert_eq!(soa.tags(), [EventTag::Click, EventTag::Click, EventTag::Key]);
+/// assert_eq!(soa.idx(2).as_key(), Some(&'a'));
+/// assert_eq!(soa.as_click(), None);
+///
+/// let mut clicks = soa.get_mut(..2).unwrap();
+/// let (x, _) = clicks.as_click_mut().unwrap();
+/// x[1] = 5;
+/// assert_eq!(soa.idx(1), Event::Click { x: 5, y: 4 });
+/// ```
+///
+/// # Serde
+///
+/// With the `serde` feature enabled, `#[soa(serde)]` implements the serde
+/// traits for the generated types as described in the `soa_rs::serde`
+/// module. The attribute is opt-in so that it doesn't conflict with
+/// `Serialize` or `Deserialize` listed in `soa_derive`. Nested types must be
+/// tagged as well.
+///
 /// # Alignment
 ///
 /// Individual fields can be tagged with the `align` attribute to raise their
@@ -224,6 +429,7 @@ pub use as_soa_ref::AsSoaRef;
 /// ```
 ///
 /// [`Deref`]: std::ops::Deref
+/// [`MaybeUninit`]: std::mem::MaybeUninit
 pub use soa_rs_derive::Soars;
 
 /// Creates a [`Soa`] containing the arguments.
@@ -295,3 +501,5 @@ macro_rules! soa {
 mod readme_tests {}
 
 mod borrow_tests;
+mod derive_tests;
+mod send_sync_tests;
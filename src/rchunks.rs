@@ -0,0 +1,92 @@
+use crate::{Slice, SliceRef, SoaRaw, Soars};
+use core::{iter::FusedIterator, marker::PhantomData};
+
+/// An iterator over a [`Slice`] in (non-overlapping) chunks of `chunk_size`
+/// elements, starting at the end of the slice.
+///
+/// When the slice len is not evenly divided by the chunk size, the last slice
+/// of the iteration will be the remainder.
+///
+/// This struct is created by the [`rchunks`] method.
+///
+/// [`rchunks`]: Slice::rchunks
+pub struct RChunks<'a, T>
+where
+    T: 'a + Soars,
+{
+    slice: Slice<T, ()>,
+    len: usize,
+    chunk_size: usize,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> RChunks<'a, T>
+where
+    T: Soars,
+{
+    pub(crate) fn new(slice: &'a Slice<T>, chunk_size: usize) -> Self {
+        Self {
+            len: slice.len(),
+            slice: unsafe { slice.as_sized() },
+            chunk_size,
+            marker: PhantomData,
+        }
+    }
+}
+
+// SAFETY: RChunks is Send and Sync under the same conditions as &[T].
+unsafe impl<'a, T> Send for RChunks<'a, T> where T: 'a + Sync + Soars {}
+unsafe impl<'a, T> Sync for RChunks<'a, T> where T: 'a + Sync + Soars {}
+
+impl<'a, T> Iterator for RChunks<'a, T>
+where
+    T: Soars,
+{
+    type Item = SliceRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            let len = self.chunk_size.min(self.len);
+            self.len -= len;
+            Some(SliceRef {
+                slice: Slice::with_raw(unsafe { self.slice.raw().offset(self.len) }),
+                len,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.len.div_ceil(self.chunk_size);
+        (n, Some(n))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for RChunks<'a, T>
+where
+    T: Soars,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            None
+        } else {
+            let len = match self.len % self.chunk_size {
+                0 => self.chunk_size,
+                rem => rem,
+            };
+            let out = SliceRef {
+                slice: self.slice,
+                len,
+                marker: PhantomData,
+            };
+            self.len -= len;
+            self.slice.raw = unsafe { self.slice.raw().offset(len) };
+            Some(out)
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for RChunks<'a, T> where T: Soars {}
+impl<'a, T> ExactSizeIterator for RChunks<'a, T> where T: Soars {}
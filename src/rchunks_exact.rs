@@ -0,0 +1,98 @@
+use crate::{Slice, SliceRef, SoaRaw, Soars};
+use core::{iter::FusedIterator, marker::PhantomData};
+
+/// An iterator over a [`Slice`] in (non-overlapping) chunks of `chunk_size`
+/// elements, starting at the end of the slice.
+///
+/// When the slice len is not evenly divided by the chunk size, the first up
+/// to `chunk_size-1` elements will be omitted but can be retrieved from the
+/// [`remainder`] function from the iterator.
+///
+/// This struct is created by the [`rchunks_exact`] method.
+///
+/// [`remainder`]: RChunksExact::remainder
+/// [`rchunks_exact`]: Slice::rchunks_exact
+pub struct RChunksExact<'a, T>
+where
+    T: 'a + Soars,
+{
+    slice: Slice<T, ()>,
+    remainder: SliceRef<'a, T>,
+    parts_remaining: usize,
+    chunk_size: usize,
+}
+
+impl<'a, T> RChunksExact<'a, T>
+where
+    T: Soars,
+{
+    pub(crate) fn new(slice: &'a Slice<T>, chunk_size: usize) -> Self {
+        let rem_len = slice.len() % chunk_size;
+        let remainder = slice.idx(..rem_len);
+        let slice = slice.idx(rem_len..);
+        Self {
+            parts_remaining: slice.len() / chunk_size,
+            slice: unsafe { slice.as_sized() },
+            remainder,
+            chunk_size,
+        }
+    }
+
+    /// Returns the remainder of the original slice that has not been yielded by
+    /// the iterator.
+    pub fn remainder(&self) -> &Slice<T> {
+        self.remainder.as_ref()
+    }
+}
+
+// SAFETY: RChunksExact is Send and Sync under the same conditions as &[T].
+unsafe impl<'a, T> Send for RChunksExact<'a, T> where T: 'a + Sync + Soars {}
+unsafe impl<'a, T> Sync for RChunksExact<'a, T> where T: 'a + Sync + Soars {}
+
+impl<'a, T> Iterator for RChunksExact<'a, T>
+where
+    T: Soars,
+{
+    type Item = SliceRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.parts_remaining == 0 {
+            None
+        } else {
+            self.parts_remaining -= 1;
+            let offset = self.parts_remaining * self.chunk_size;
+            Some(SliceRef {
+                slice: Slice::with_raw(unsafe { self.slice.raw().offset(offset) }),
+                len: self.chunk_size,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.parts_remaining, Some(self.parts_remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for RChunksExact<'a, T>
+where
+    T: Soars,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.parts_remaining == 0 {
+            None
+        } else {
+            let out = SliceRef {
+                slice: self.slice,
+                len: self.chunk_size,
+                marker: PhantomData,
+            };
+            self.parts_remaining -= 1;
+            self.slice.raw = unsafe { self.slice.raw().offset(self.chunk_size) };
+            Some(out)
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for RChunksExact<'a, T> where T: Soars {}
+impl<'a, T> ExactSizeIterator for RChunksExact<'a, T> where T: Soars {}
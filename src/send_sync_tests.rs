@@ -0,0 +1,164 @@
+/// ```
+/// use soa_rs::{Soa, Soars, soa};
+/// #[derive(Soars, PartialEq, Debug)]
+/// #[soa_derive(Debug, PartialEq)]
+/// struct Foo(usize);
+/// let soa = soa![Foo(10), Foo(20)];
+/// let soa = std::thread::spawn(move || soa).join().unwrap();
+/// assert_eq!(soa, [Foo(10), Foo(20)]);
+/// ```
+mod send_soa {
+    /// ```compile_fail
+    /// use soa_rs::{Soa, Soars, soa};
+    /// use std::rc::Rc;
+    /// #[derive(Soars)]
+    /// #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(Rc<usize>);
+    /// let soa = soa![Foo(Rc::new(10)), Foo(Rc::new(20))];
+    /// std::thread::spawn(move || soa);
+    /// ```
+    mod fail {}
+}
+
+/// ```
+/// use soa_rs::{ArraySoa, Soars};
+/// #[derive(Soars, PartialEq, Debug)]
+/// #[soa_derive(Debug, PartialEq)]
+/// struct Foo(usize);
+/// let soa: ArraySoa<Foo, 2> = [Foo(10), Foo(20)].into_iter().collect();
+/// let soa = std::thread::spawn(move || soa).join().unwrap();
+/// assert_eq!(soa, [Foo(10), Foo(20)]);
+/// ```
+mod send_array_soa {
+    /// ```compile_fail
+    /// use soa_rs::{ArraySoa, Soars};
+    /// #[derive(Soars)]
+    /// #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(usize);
+    /// let soa: ArraySoa<Foo, 2> = [Foo(10), Foo(20)].into_iter().collect();
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| soa.len());
+    /// });
+    /// ```
+    mod not_sync {}
+}
+
+/// ```
+/// use soa_rs::{Soa, Soars, soa};
+/// #[derive(Soars, PartialEq, Debug)]
+/// #[soa_derive(Debug, PartialEq)]
+/// struct Foo(usize);
+/// let soa = soa![Foo(10), Foo(20)];
+/// std::thread::scope(|s| {
+///     s.spawn(|| assert_eq!(soa.as_slice(), [Foo(10), Foo(20)]));
+///     s.spawn(|| assert_eq!(soa.idx(..1), [Foo(10)]));
+///     s.spawn(|| assert_eq!(soa.iter().count(), 2));
+///     s.spawn(|| assert_eq!(soa.chunks_exact(1).count(), 2));
+/// });
+/// ```
+mod sync_soa {
+    /// ```compile_fail
+    /// use soa_rs::{Soa, Soars, soa};
+    /// use std::cell::Cell;
+    /// #[derive(Soars)]
+    /// #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(Cell<usize>);
+    /// let soa = soa![Foo(Cell::new(10)), Foo(Cell::new(20))];
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| soa.len());
+    /// });
+    /// ```
+    mod soa {}
+
+    /// ```compile_fail
+    /// use soa_rs::{Soa, Soars, soa};
+    /// use std::cell::Cell;
+    /// #[derive(Soars)]
+    /// #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(Cell<usize>);
+    /// let soa = soa![Foo(Cell::new(10)), Foo(Cell::new(20))];
+    /// let slice = soa.idx(..);
+    /// std::thread::scope(|s| {
+    ///     s.spawn(move || slice.len());
+    /// });
+    /// ```
+    mod slice_ref {}
+
+    /// ```compile_fail
+    /// use soa_rs::{Soa, Soars, soa};
+    /// use std::cell::Cell;
+    /// #[derive(Soars)]
+    /// #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(Cell<usize>);
+    /// let soa = soa![Foo(Cell::new(10)), Foo(Cell::new(20))];
+    /// let iter = soa.iter();
+    /// std::thread::scope(|s| {
+    ///     s.spawn(move || iter.count());
+    /// });
+    /// ```
+    mod iter {}
+}
+
+/// ```
+/// use soa_rs::{Soa, Soars, soa};
+/// use std::cell::Cell;
+/// #[derive(Soars, PartialEq, Debug)]
+/// #[soa_derive(Debug, PartialEq)]
+/// struct Foo(Cell<usize>);
+/// let mut soa = soa![Foo(Cell::new(10)), Foo(Cell::new(20))];
+/// let slice = soa.idx_mut(..1);
+/// std::thread::scope(|s| {
+///     s.spawn(move || slice.f0()[0].set(30));
+/// });
+/// let iter = soa.iter_mut();
+/// std::thread::scope(|s| {
+///     s.spawn(move || iter.for_each(|el| el.0.set(40)));
+/// });
+/// let drain = soa.drain(..1);
+/// std::thread::scope(|s| {
+///     s.spawn(move || drain.count());
+/// });
+/// let into_iter = soa.into_iter();
+/// std::thread::spawn(move || into_iter.count()).join().unwrap();
+/// ```
+mod send_mut {
+    /// ```compile_fail
+    /// use soa_rs::{Soa, Soars, soa};
+    /// use std::rc::Rc;
+    /// #[derive(Soars)]
+    /// #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(Rc<usize>);
+    /// let mut soa = soa![Foo(Rc::new(10)), Foo(Rc::new(20))];
+    /// let slice = soa.idx_mut(..);
+    /// std::thread::scope(|s| {
+    ///     s.spawn(move || slice.len());
+    /// });
+    /// ```
+    mod slice_mut {}
+
+    /// ```compile_fail
+    /// use soa_rs::{Soa, Soars, soa};
+    /// use std::rc::Rc;
+    /// #[derive(Soars)]
+    /// #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(Rc<usize>);
+    /// let soa = soa![Foo(Rc::new(10)), Foo(Rc::new(20))];
+    /// let into_iter = soa.into_iter();
+    /// std::thread::spawn(move || into_iter.count());
+    /// ```
+    mod into_iter {}
+
+    /// ```compile_fail
+    /// use soa_rs::{Soa, Soars, soa};
+    /// use std::rc::Rc;
+    /// #[derive(Soars)]
+    /// #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(Rc<usize>);
+    /// let mut soa = soa![Foo(Rc::new(10)), Foo(Rc::new(20))];
+    /// let drain = soa.drain(..);
+    /// std::thread::scope(|s| {
+    ///     s.spawn(move || drain.count());
+    /// });
+    /// ```
+    mod drain {}
+}
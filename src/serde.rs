@@ -0,0 +1,229 @@
+//! Serialization with [serde], enabled by the `serde` feature.
+//!
+//! By default, [`Soa`] and [`Slice`] serialize as a sequence of rows, exactly
+//! like `Vec<T>` and `[T]` would. This keeps the format interchangeable with
+//! the array-of-structures representation. Tagging the item with
+//! `#[soa(serde)]` implements the same for the types generated by [`Soars`].
+//! Its array type deserializes from a sequence of its exact length.
+//!
+//! ```
+//! # use soa_rs::{soa, Soa, Soars};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Soars, Serialize, Deserialize, Debug, PartialEq)]
+//! #[soa_derive(Debug, PartialEq)]
+//! #[soa(serde)]
+//! struct Foo {
+//!     a: u8,
+//!     b: bool,
+//! }
+//!
+//! let soa = soa![Foo { a: 1, b: true }, Foo { a: 2, b: false }];
+//! let json = serde_json::to_string(&soa).unwrap();
+//! assert_eq!(json, r#"[{"a":1,"b":true},{"a":2,"b":false}]"#);
+//! let de: Soa<Foo> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(de, soa);
+//! ```
+//!
+//! The [`columnar`] module serializes a struct's fields as a map from field
+//! name to an array of that field's values instead.
+//!
+//! Without `#[soa(serde)]`, rows can still be serialized through a
+//! `Serialize` implementation of the reference type listed in `soa_derive`.
+//! This only applies to enums and unit structs, since serde does not
+//! implement its traits for the arrays of arbitrary length in the array and
+//! chunk types of other structs.
+//!
+//! [serde]: ::serde
+//! [`Soars`]: crate::Soars
+
+use crate::{Allocator, Slice, SliceMut, SliceRef, Soa, SoaRaw, Soars};
+use ::serde::{
+    de::{SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use core::{
+    fmt::{self, Formatter},
+    marker::PhantomData,
+    mem::size_of,
+};
+
+pub mod columnar;
+
+impl<T> Serialize for Slice<T>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<T> Serialize for SliceRef<'_, T>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+impl<T> Serialize for SliceMut<'_, T>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+impl<T, A> Serialize for Soa<T, A>
+where
+    T: Soars,
+    A: Allocator,
+    for<'a> T::Ref<'a>: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Soa<T>
+where
+    T: Soars + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RowsVisitor(PhantomData))
+    }
+}
+
+struct RowsVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for RowsVisitor<T>
+where
+    T: Soars + Deserialize<'de>,
+{
+    type Value = Soa<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut out = Soa::<T>::with_capacity(cautious_capacity::<T>(seq.size_hint()));
+        while let Some(element) = seq.next_element()? {
+            out.push(element);
+        }
+        Ok(out)
+    }
+}
+
+/// Limits preallocation from an untrusted size hint to about a megabyte, the
+/// same as serde does for `Vec`.
+fn cautious_capacity<T>(hint: Option<usize>) -> usize {
+    const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+    match size_of::<T>() {
+        0 => 0,
+        size => hint.unwrap_or(0).min(MAX_PREALLOC_BYTES / size),
+    }
+}
+
+/// Items used by the code generated by [`Soars`](crate::Soars). Not public
+/// API.
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+    use ::serde::de::{self, Expected};
+    use core::mem::MaybeUninit;
+
+    pub use ::serde;
+    pub use alloc::vec::Vec;
+
+    /// Deserializes the columns of a struct. Implemented by the derive macro
+    /// when the `serde` feature is enabled.
+    pub trait SoarsColumns: Soars + Sized {
+        /// The struct name.
+        const NAME: &'static str;
+        /// The field names, in declaration order.
+        const FIELDS: &'static [&'static str];
+        /// A tuple with an optional column for each field.
+        type Columns;
+
+        /// Returns the columns with none of them deserialized yet.
+        fn columns() -> Self::Columns;
+
+        /// Deserializes the column for the field at `index` from `source`.
+        /// Returns `false` if the source has no more columns.
+        fn next_column<'de, S>(
+            columns: &mut Self::Columns,
+            index: usize,
+            source: &mut S,
+        ) -> Result<bool, S::Error>
+        where
+            S: ColumnSource<'de>;
+
+        /// Zips the columns into rows.
+        fn assemble<E>(columns: Self::Columns) -> Result<Soa<Self>, E>
+        where
+            E: de::Error;
+    }
+
+    /// Abstracts over the map and sequence forms of a columnar struct.
+    pub trait ColumnSource<'de> {
+        /// The deserializer error type.
+        type Error: de::Error;
+
+        /// Deserializes the next column.
+        fn next_column<C>(&mut self) -> Result<Option<C>, Self::Error>
+        where
+            C: Deserialize<'de>;
+    }
+
+    /// Moves the elements of `soa` into an array of the same length.
+    pub fn array_from_soa<T, const N: usize, E>(mut soa: Soa<T>) -> Result<T::Array<N>, E>
+    where
+        T: Soars,
+        E: de::Error,
+    {
+        if soa.len() != N {
+            return Err(E::invalid_length(soa.len(), &ArrayLen(N)));
+        }
+        let mut out = MaybeUninit::<T::Array<N>>::uninit();
+        // SAFETY: The array has room for exactly the N elements in the SoA.
+        // Setting the length to zero afterwards transfers their ownership.
+        unsafe {
+            let dst = T::Raw::from_array::<N>(out.as_mut_ptr());
+            soa.raw().copy_to(dst, N);
+            soa.len = 0;
+            Ok(out.assume_init())
+        }
+    }
+
+    struct ArrayLen(usize);
+
+    impl Expected for ArrayLen {
+        fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+            write!(formatter, "an array of length {}", self.0)
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Serializes a struct's fields as a map from field name to an array of that
+//! field's values.
+//!
+//! Use this module with `#[serde(with = "soa_rs::serde::columnar")]` on a
+//! field, or wrap a value in [`Columnar`] to serialize it directly. The item
+//! type must be tagged with `#[soa(serde)]`. Nested fields are serialized as
+//! columns of their own. Columnar serialization is
+//! only available for structs with fields, since the payloads of an enum
+//! don't form complete columns.
+//!
+//! ```
+//! # use soa_rs::{soa, Soa, Soars};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Soars, Debug, PartialEq)]
+//! #[soa_derive(Debug, PartialEq)]
+//! #[soa(serde)]
+//! struct Foo {
+//!     a: u8,
+//!     b: bool,
+//! }
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Scene {
+//!     #[serde(with = "soa_rs::serde::columnar")]
+//!     foos: Soa<Foo>,
+//! }
+//!
+//! let scene = Scene {
+//!     foos: soa![Foo { a: 1, b: true }, Foo { a: 2, b: false }],
+//! };
+//! let json = serde_json::to_string(&scene).unwrap();
+//! assert_eq!(json, r#"{"foos":{"a":[1,2],"b":[true,false]}}"#);
+//! let de: Scene = serde_json::from_str(&json).unwrap();
+//! assert_eq!(de, scene);
+//! ```
+//!
+//! Deserialization fails if a column is missing, if a column appears twice,
+//! or if the columns have different lengths. Unknown columns are ignored.
+
+use super::__private::{ColumnSource, SoarsColumns};
+use crate::{Allocator, Slice, SliceMut, SliceRef, Soa, Soars};
+use ::serde::{
+    de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use alloc::vec;
+use core::{
+    fmt::{self, Formatter},
+    marker::PhantomData,
+};
+
+/// Serializes `value` as columns. For use with `#[serde(with = "...")]`.
+pub fn serialize<V, S>(value: &V, serializer: S) -> Result<S::Ok, S::Error>
+where
+    V: ?Sized + SerializeColumns,
+    S: Serializer,
+{
+    value.serialize_columns(serializer)
+}
+
+/// Deserializes a value from columns. For use with `#[serde(with = "...")]`.
+pub fn deserialize<'de, V, D>(deserializer: D) -> Result<V, D::Error>
+where
+    V: DeserializeColumns,
+    D: Deserializer<'de>,
+{
+    V::deserialize_columns(deserializer)
+}
+
+/// A type that can be serialized as columns.
+pub trait SerializeColumns {
+    /// Serializes `self` as a map from field name to an array of that field's
+    /// values.
+    fn serialize_columns<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+}
+
+/// A type that can be deserialized from columns.
+pub trait DeserializeColumns: Sized {
+    /// Deserializes `Self` from a map from field name to an array of that
+    /// field's values.
+    fn deserialize_columns<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// Serializes and deserializes the wrapped value as columns.
+///
+/// This is the equivalent of `#[serde(with = "soa_rs::serde::columnar")]` for
+/// values that aren't a field of a serde-derived type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Columnar<V>(pub V);
+
+impl<V> Serialize for Columnar<V>
+where
+    V: SerializeColumns,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize_columns(serializer)
+    }
+}
+
+impl<'de, V> Deserialize<'de> for Columnar<V>
+where
+    V: DeserializeColumns,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        V::deserialize_columns(deserializer).map(Self)
+    }
+}
+
+impl<V> SerializeColumns for &V
+where
+    V: ?Sized + SerializeColumns,
+{
+    fn serialize_columns<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (**self).serialize_columns(serializer)
+    }
+}
+
+impl<T> SerializeColumns for Slice<T>
+where
+    T: Soars,
+    for<'a> T::Slices<'a>: Serialize,
+{
+    fn serialize_columns<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.slices().serialize(serializer)
+    }
+}
+
+impl<T> SerializeColumns for SliceRef<'_, T>
+where
+    T: Soars,
+    for<'a> T::Slices<'a>: Serialize,
+{
+    fn serialize_columns<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_ref().serialize_columns(serializer)
+    }
+}
+
+impl<T> SerializeColumns for SliceMut<'_, T>
+where
+    T: Soars,
+    for<'a> T::Slices<'a>: Serialize,
+{
+    fn serialize_columns<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_ref().serialize_columns(serializer)
+    }
+}
+
+impl<T, A> SerializeColumns for Soa<T, A>
+where
+    T: Soars,
+    A: Allocator,
+    for<'a> T::Slices<'a>: Serialize,
+{
+    fn serialize_columns<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_slice().serialize_columns(serializer)
+    }
+}
+
+impl<T> DeserializeColumns for Soa<T>
+where
+    T: SoarsColumns,
+{
+    fn deserialize_columns<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(T::NAME, T::FIELDS, ColumnsVisitor(PhantomData))
+    }
+}
+
+struct ColumnsVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for ColumnsVisitor<T>
+where
+    T: SoarsColumns,
+{
+    type Value = Soa<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "columns of struct {}", T::NAME)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut columns = T::columns();
+        let mut seen = vec![false; T::FIELDS.len()];
+        while let Some(key) = map.next_key_seed(FieldSeed(T::FIELDS))? {
+            match key {
+                Some(index) => {
+                    if seen[index] {
+                        return Err(de::Error::duplicate_field(T::FIELDS[index]));
+                    }
+                    seen[index] = true;
+                    T::next_column(&mut columns, index, &mut MapSource(&mut map))?;
+                }
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        T::assemble(columns)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut columns = T::columns();
+        for index in 0..T::FIELDS.len() {
+            if !T::next_column(&mut columns, index, &mut SeqSource(&mut seq))? {
+                return Err(de::Error::invalid_length(index, &self));
+            }
+        }
+        T::assemble(columns)
+    }
+}
+
+/// Deserializes a field name into its index, or `None` for unknown fields.
+struct FieldSeed(&'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for FieldSeed {
+    type Value = Option<usize>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldSeed {
+    type Value = Option<usize>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a field identifier")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(usize::try_from(v).ok().filter(|&i| i < self.0.len()))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(self.0.iter().position(|&field| field == v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(self.0.iter().position(|&field| field.as_bytes() == v))
+    }
+}
+
+struct MapSource<'a, A>(&'a mut A);
+
+impl<'de, A> ColumnSource<'de> for MapSource<'_, A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_column<C>(&mut self) -> Result<Option<C>, Self::Error>
+    where
+        C: Deserialize<'de>,
+    {
+        self.0.next_value().map(Some)
+    }
+}
+
+struct SeqSource<'a, A>(&'a mut A);
+
+impl<'de, A> ColumnSource<'de> for SeqSource<'_, A>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_column<C>(&mut self) -> Result<Option<C>, Self::Error>
+    where
+        C: Deserialize<'de>,
+    {
+        self.0.next_element()
+    }
+}
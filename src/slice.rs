@@ -1,13 +1,26 @@
 use crate::{
-    chunks_exact::ChunksExact, index::SoaIndex, iter_raw::IterRaw, AsSoaRef, Iter, IterMut,
-    SliceMut, SliceRef, Soa, SoaDeref, SoaRaw, Soars,
+    chunk_by::ChunkBy,
+    chunks::Chunks,
+    chunks_exact::ChunksExact,
+    chunks_exact_mut::ChunksExactMut,
+    chunks_lanes::{ChunksLanes, ChunksLanesMut},
+    chunks_mut::ChunksMut,
+    index::{slice_range, SoaIndex},
+    iter_raw::IterRaw,
+    rchunks::RChunks,
+    rchunks_exact::RChunksExact,
+    windows::Windows,
+    Allocator, AsSoaRef, GetDisjointMutError, Iter, IterMut, SliceMut, SliceRef, Soa, SoaDeref,
+    SoaRaw, SoaRawClone, Soars,
 };
-use std::{
+use alloc::vec::Vec;
+use core::{
     cmp::Ordering,
     fmt::{self, Debug, Formatter},
     hash::{Hash, Hasher},
     marker::PhantomData,
-    ops::{ControlFlow, Deref, DerefMut},
+    mem::MaybeUninit,
+    ops::{ControlFlow, Deref, DerefMut, Range, RangeBounds},
 };
 
 /// A dynamically-sized view into the contents of a [`Soa`].
@@ -59,9 +72,9 @@ where
     ///
     /// - `length` must be valid for the underlying type `T`.
     /// - The lifetime of the returned reference is unconstrained. Ensure that
-    /// the right lifetimes are applied.
+    ///   the right lifetimes are applied.
     pub(crate) unsafe fn as_unsized_mut<'a>(&mut self, len: usize) -> &'a mut Slice<T> {
-        &mut *(std::ptr::slice_from_raw_parts_mut(self, len) as *mut Slice<T>)
+        &mut *(core::ptr::slice_from_raw_parts_mut(self, len) as *mut Slice<T>)
     }
 
     /// Converts to an unsized variant.
@@ -70,9 +83,9 @@ where
     ///
     /// - `length` must be valid for the underlying type `T`.
     /// - The lifetime of the returned reference is unconstrained. Ensure that
-    /// the right lifetimes are applied.
+    ///   the right lifetimes are applied.
     pub(crate) unsafe fn as_unsized<'a>(&self, len: usize) -> &'a Slice<T> {
-        &*(std::ptr::slice_from_raw_parts(self, len) as *const Slice<T>)
+        &*(core::ptr::slice_from_raw_parts(self, len) as *const Slice<T>)
     }
 }
 
@@ -148,7 +161,7 @@ where
     /// assert_eq!(iter.next().unwrap(), Foo(4));
     /// assert_eq!(iter.next(), None);
     /// ```
-    pub const fn iter(&self) -> Iter<T> {
+    pub const fn iter(&self) -> Iter<'_, T> {
         Iter {
             iter_raw: IterRaw {
                 slice: unsafe { self.as_sized() },
@@ -177,7 +190,7 @@ where
     /// }
     /// assert_eq!(soa, [Foo(2), Foo(4), Foo(8)]);
     /// ```
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
             iter_raw: IterRaw {
                 slice: unsafe { self.as_sized() },
@@ -192,10 +205,10 @@ where
     /// index.
     ///
     /// - If given a position, returns a reference to the element at that
-    /// position or None if out of bounds.
+    ///   position or None if out of bounds.
     ///
     /// - If given a range, returns the subslice corresponding to that range, or
-    /// None if out of bounds.
+    ///   None if out of bounds.
     ///
     /// # Examples
     ///
@@ -312,6 +325,59 @@ where
         self.get_mut(index).expect("index out of bounds")
     }
 
+    /// Returns mutable references to many indices at once.
+    ///
+    /// This is the SoA equivalent of [`slice::get_disjoint_mut`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any index is out-of-bounds or if the same index
+    /// appears more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{GetDisjointMutError, Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// if let Ok([a, b]) = soa.get_disjoint_mut([0, 2]) {
+    ///     std::mem::swap(a.0, b.0);
+    /// }
+    /// assert_eq!(soa, [Foo(3), Foo(2), Foo(1)]);
+    ///
+    /// assert_eq!(
+    ///     soa.get_disjoint_mut([1, 1]).err(),
+    ///     Some(GetDisjointMutError::OverlappingIndices),
+    /// );
+    /// assert_eq!(
+    ///     soa.get_disjoint_mut([0, 3]).err(),
+    ///     Some(GetDisjointMutError::IndexOutOfBounds),
+    /// );
+    /// ```
+    ///
+    /// [`slice::get_disjoint_mut`]: prim@slice#method.get_disjoint_mut
+    pub fn get_disjoint_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Result<[T::RefMut<'_>; N], GetDisjointMutError> {
+        let len = self.len();
+        for (i, &index) in indices.iter().enumerate() {
+            if index >= len {
+                return Err(GetDisjointMutError::IndexOutOfBounds);
+            }
+            if indices[..i].contains(&index) {
+                return Err(GetDisjointMutError::OverlappingIndices);
+            }
+        }
+
+        let raw = self.raw();
+        // SAFETY: The indices are in bounds and pairwise distinct, so the
+        // returned references do not alias.
+        Ok(indices.map(|index| unsafe { raw.offset(index).get_mut() }))
+    }
+
     /// Swaps the position of two elements.
     ///
     /// # Arguments
@@ -330,25 +396,1075 @@ where
     /// # #[derive(Soars, Debug, PartialEq)]
     /// # #[soa_derive(Debug, PartialEq)]
     /// # struct Foo(usize);
-    /// let mut soa = soa![Foo(0), Foo(1), Foo(2), Foo(3), Foo(4)];
-    /// soa.swap(2, 4);
-    /// assert_eq!(soa, [Foo(0), Foo(1), Foo(4), Foo(3), Foo(2)]);
+    /// let mut soa = soa![Foo(0), Foo(1), Foo(2), Foo(3), Foo(4)];
+    /// soa.swap(2, 4);
+    /// assert_eq!(soa, [Foo(0), Foo(1), Foo(4), Foo(3), Foo(2)]);
+    /// ```
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a >= self.len() || b >= self.len() {
+            panic!("index out of bounds");
+        }
+
+        unsafe {
+            let a = self.raw().offset(a);
+            let b = self.raw().offset(b);
+            let tmp = a.get();
+            b.copy_to(a, 1);
+            b.set(tmp);
+        }
+    }
+
+    /// Reverses the order of elements in the slice, in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// soa.reverse();
+    /// assert_eq!(soa, [Foo(3), Foo(2), Foo(1)]);
+    /// ```
+    pub fn reverse(&mut self) {
+        let mut tmp = MaybeUninit::<T::Array<1>>::uninit();
+        let tmp = unsafe { T::Raw::from_array::<1>(tmp.as_mut_ptr()) };
+        // SAFETY: The whole slice is in bounds.
+        unsafe { Self::reverse_raw(self.raw(), self.len(), tmp) };
+    }
+
+    /// Rotates the slice in-place such that the first `mid` elements of the
+    /// slice move to the end while the last `self.len() - mid` elements move to
+    /// the front.
+    ///
+    /// After calling `rotate_left`, the element previously at index `mid` will
+    /// become the first element in the slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4), Foo(5)];
+    /// soa.rotate_left(2);
+    /// assert_eq!(soa, [Foo(3), Foo(4), Foo(5), Foo(1), Foo(2)]);
+    /// ```
+    pub fn rotate_left(&mut self, mid: usize) {
+        assert!(mid <= self.len(), "mid > len");
+        let len = self.len();
+        let raw = self.raw();
+        let mut tmp = MaybeUninit::<T::Array<1>>::uninit();
+        let tmp = unsafe { T::Raw::from_array::<1>(tmp.as_mut_ptr()) };
+        // SAFETY: Both halves and the whole slice are in bounds.
+        unsafe {
+            Self::reverse_raw(raw, mid, tmp);
+            Self::reverse_raw(raw.offset(mid), len - mid, tmp);
+            Self::reverse_raw(raw, len, tmp);
+        }
+    }
+
+    /// Rotates the slice in-place such that the first `self.len() - k`
+    /// elements of the slice move to the end while the last `k` elements move
+    /// to the front.
+    ///
+    /// After calling `rotate_right`, the element previously at index
+    /// `self.len() - k` will become the first element in the slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4), Foo(5)];
+    /// soa.rotate_right(2);
+    /// assert_eq!(soa, [Foo(4), Foo(5), Foo(1), Foo(2), Foo(3)]);
+    /// ```
+    pub fn rotate_right(&mut self, k: usize) {
+        assert!(k <= self.len(), "k > len");
+        self.rotate_left(self.len() - k);
+    }
+
+    /// Swaps all elements in `self` with those in `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two slices have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut front = soa![Foo(1), Foo(2)];
+    /// let mut back = soa![Foo(3), Foo(4), Foo(5)];
+    /// front.swap_with_slice(back.idx_mut(1..).as_mut());
+    /// assert_eq!(front, [Foo(4), Foo(5)]);
+    /// assert_eq!(back, [Foo(3), Foo(1), Foo(2)]);
+    /// ```
+    pub fn swap_with_slice(&mut self, other: &mut Slice<T>) {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "destination and source slices have different lengths"
+        );
+        let mut tmp = MaybeUninit::<T::Array<1>>::uninit();
+        let tmp = unsafe { T::Raw::from_array::<1>(tmp.as_mut_ptr()) };
+        for i in 0..self.len() {
+            // SAFETY: The index is in bounds of both slices, which cannot
+            // overlap because they are both mutably borrowed.
+            unsafe { Self::swap_raw(self.raw().offset(i), other.raw().offset(i), tmp) };
+        }
+    }
+
+    /// Copies elements from one part of the slice to another part of itself.
+    ///
+    /// `src` is the range within `self` to copy from. `dest` is the starting
+    /// index of the range within `self` to copy to, which will have the same
+    /// length as `src`. The two ranges may overlap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either range exceeds the end of the slice, or if the end of
+    /// `src` is before the start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq, Clone, Copy)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4), Foo(5)];
+    /// soa.copy_within(1..4, 2);
+    /// assert_eq!(soa, [Foo(1), Foo(2), Foo(2), Foo(3), Foo(4)]);
+    /// ```
+    pub fn copy_within<R>(&mut self, src: R, dest: usize)
+    where
+        R: RangeBounds<usize>,
+        T: Copy,
+    {
+        let Range { start, end } = slice_range(src, self.len());
+        let count = end - start;
+        assert!(dest <= self.len() - count, "dest is out of bounds");
+        // SAFETY: Both ranges are in bounds and copy_to allows overlap.
+        unsafe {
+            self.raw()
+                .offset(start)
+                .copy_to(self.raw().offset(dest), count)
+        };
+    }
+
+    /// Fills the slice with elements by cloning `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// soa.fill(Foo(0));
+    /// assert_eq!(soa, [Foo(0), Foo(0), Foo(0)]);
+    /// ```
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        let Some(last) = self.len().checked_sub(1) else {
+            return;
+        };
+        for i in 0..last {
+            // SAFETY: The index is in bounds.
+            unsafe { Self::replace_raw(self.raw().offset(i), value.clone()) };
+        }
+        unsafe { Self::replace_raw(self.raw().offset(last), value) };
+    }
+
+    /// Fills the slice with elements returned by calling a closure
+    /// repeatedly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// let mut i = 10;
+    /// soa.fill_with(|| {
+    ///     i += 1;
+    ///     Foo(i)
+    /// });
+    /// assert_eq!(soa, [Foo(11), Foo(12), Foo(13)]);
+    /// ```
+    pub fn fill_with<F>(&mut self, mut f: F)
+    where
+        F: FnMut() -> T,
+    {
+        for i in 0..self.len() {
+            // SAFETY: The index is in bounds.
+            unsafe { Self::replace_raw(self.raw().offset(i), f()) };
+        }
+    }
+
+    /// Returns the first element of the slice, or None if empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(10), Foo(40), Foo(30)];
+    /// assert_eq!(soa.first().unwrap(), Foo(10));
+    ///
+    /// let soa = Soa::<Foo>::new();
+    /// assert_eq!(soa.first(), None);
+    /// ```
+    pub fn first(&self) -> Option<T::Ref<'_>> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the first element of the slice, or None if empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(0), Foo(1), Foo(2)];
+    /// if let Some(mut first) = soa.first_mut() {
+    ///     *first.0 = 5;
+    /// }
+    /// assert_eq!(soa, [Foo(5), Foo(1), Foo(2)]);
+    /// ```
+    pub fn first_mut(&mut self) -> Option<T::RefMut<'_>> {
+        self.get_mut(0)
+    }
+
+    /// Returns the last element of the slice, or None if empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(10), Foo(40), Foo(30)];
+    /// assert_eq!(soa.last().unwrap(), Foo(30));
+    ///
+    /// let soa = Soa::<Foo>::new();
+    /// assert_eq!(soa.last(), None);
+    /// ```
+    pub fn last(&self) -> Option<T::Ref<'_>> {
+        self.get(self.len().saturating_sub(1))
+    }
+
+    /// Returns a mutable reference to the last element of the slice, or None if empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(0), Foo(1), Foo(2)];
+    /// if let Some(mut last) = soa.last_mut() {
+    ///     *last.0 = 5;
+    /// }
+    /// assert_eq!(soa, [Foo(0), Foo(1), Foo(5)]);
+    /// ```
+    pub fn last_mut(&mut self) -> Option<T::RefMut<'_>> {
+        self.get_mut(self.len().saturating_sub(1))
+    }
+
+    /// Divides one slice into two at an index.
+    ///
+    /// The first will contain all indices from `[0, mid)` and the second will
+    /// contain all indices from `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// let (left, right) = soa.split_at(1);
+    /// assert_eq!(left, [Foo(1)]);
+    /// assert_eq!(right, [Foo(2), Foo(3)]);
+    /// ```
+    pub fn split_at(&self, mid: usize) -> (SliceRef<'_, T>, SliceRef<'_, T>) {
+        assert!(mid <= self.len(), "mid > len");
+        let raw = self.raw();
+        // SAFETY: Both halves are in bounds and borrow from self.
+        unsafe {
+            (
+                SliceRef::from_slice(Slice::with_raw(raw), mid),
+                SliceRef::from_slice(Slice::with_raw(raw.offset(mid)), self.len() - mid),
+            )
+        }
+    }
+
+    /// Divides one mutable slice into two at an index.
+    ///
+    /// The first will contain all indices from `[0, mid)` and the second will
+    /// contain all indices from `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// let (mut left, mut right) = soa.split_at_mut(1);
+    /// *left.idx_mut(0).0 = 10;
+    /// *right.idx_mut(1).0 = 30;
+    /// assert_eq!(soa, [Foo(10), Foo(2), Foo(30)]);
+    /// ```
+    pub fn split_at_mut(&mut self, mid: usize) -> (SliceMut<'_, T>, SliceMut<'_, T>) {
+        assert!(mid <= self.len(), "mid > len");
+        let raw = self.raw();
+        // SAFETY: Both halves are in bounds, do not overlap, and borrow
+        // mutably from self.
+        unsafe {
+            (
+                SliceMut::from_slice(Slice::with_raw(raw), mid),
+                SliceMut::from_slice(Slice::with_raw(raw.offset(mid)), self.len() - mid),
+            )
+        }
+    }
+
+    /// Returns the first element and the rest of the slice, or None if empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// let (first, rest) = soa.split_first().unwrap();
+    /// assert_eq!(first, Foo(1));
+    /// assert_eq!(rest, [Foo(2), Foo(3)]);
+    /// ```
+    pub fn split_first(&self) -> Option<(T::Ref<'_>, SliceRef<'_, T>)> {
+        if self.is_empty() {
+            return None;
+        }
+        let (first, rest) = self.split_at(1);
+        // SAFETY: first has exactly one element.
+        let first = unsafe { first.raw().get_ref() };
+        Some((first, rest))
+    }
+
+    /// Returns the first element and the rest of the slice mutably, or None if
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// if let Some((first, mut rest)) = soa.split_first_mut() {
+    ///     *first.0 = 10;
+    ///     *rest.idx_mut(0).0 = 20;
+    /// }
+    /// assert_eq!(soa, [Foo(10), Foo(20), Foo(3)]);
+    /// ```
+    pub fn split_first_mut(&mut self) -> Option<(T::RefMut<'_>, SliceMut<'_, T>)> {
+        if self.is_empty() {
+            return None;
+        }
+        let (first, rest) = self.split_at_mut(1);
+        // SAFETY: first has exactly one element.
+        let first = unsafe { first.raw().get_mut() };
+        Some((first, rest))
+    }
+
+    /// Returns the last element and the rest of the slice, or None if empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// let (last, rest) = soa.split_last().unwrap();
+    /// assert_eq!(last, Foo(3));
+    /// assert_eq!(rest, [Foo(1), Foo(2)]);
+    /// ```
+    pub fn split_last(&self) -> Option<(T::Ref<'_>, SliceRef<'_, T>)> {
+        let mid = self.len().checked_sub(1)?;
+        let (rest, last) = self.split_at(mid);
+        // SAFETY: last has exactly one element.
+        let last = unsafe { last.raw().get_ref() };
+        Some((last, rest))
+    }
+
+    /// Returns the last element and the rest of the slice mutably, or None if
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// if let Some((last, mut rest)) = soa.split_last_mut() {
+    ///     *last.0 = 30;
+    ///     *rest.idx_mut(0).0 = 10;
+    /// }
+    /// assert_eq!(soa, [Foo(10), Foo(2), Foo(30)]);
+    /// ```
+    pub fn split_last_mut(&mut self) -> Option<(T::RefMut<'_>, SliceMut<'_, T>)> {
+        let mid = self.len().checked_sub(1)?;
+        let (rest, last) = self.split_at_mut(mid);
+        // SAFETY: last has exactly one element.
+        let last = unsafe { last.raw().get_mut() };
+        Some((last, rest))
+    }
+
+    /// Sorts the slice with a comparator function, preserving the initial
+    /// order of equal elements.
+    ///
+    /// The sort is performed on a permutation of indices which is then applied
+    /// to every field. This allocates `len` indices. If `compare` panics, the
+    /// slice is left unmodified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize, char);
+    /// let mut soa = soa![Foo(3, 'a'), Foo(1, 'b'), Foo(2, 'c'), Foo(1, 'd')];
+    /// soa.sort_by(|a, b| a.0.cmp(b.0));
+    /// assert_eq!(soa, [Foo(1, 'b'), Foo(1, 'd'), Foo(2, 'c'), Foo(3, 'a')]);
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(T::Ref<'_>, T::Ref<'_>) -> Ordering,
+    {
+        let raw = self.raw();
+        let mut permutation: Vec<usize> = (0..self.len()).collect();
+        // SAFETY: The indices are in bounds.
+        permutation
+            .sort_by(|&a, &b| unsafe { compare(raw.offset(a).get_ref(), raw.offset(b).get_ref()) });
+        // SAFETY: The indices are a permutation of 0..len.
+        unsafe { self.apply_permutation(&mut permutation) };
+    }
+
+    /// Sorts the slice with a key extraction function, preserving the initial
+    /// order of equal elements.
+    ///
+    /// The key function is called multiple times per element. See
+    /// [`sort_by_cached_key`] for expensive key functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(i32, char);
+    /// let mut soa = soa![Foo(-3, 'a'), Foo(1, 'b'), Foo(2, 'c'), Foo(-1, 'd')];
+    /// soa.sort_by_key(|foo| foo.0.abs());
+    /// assert_eq!(soa, [Foo(1, 'b'), Foo(-1, 'd'), Foo(2, 'c'), Foo(-3, 'a')]);
+    /// ```
+    ///
+    /// [`sort_by_cached_key`]: Slice::sort_by_cached_key
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(T::Ref<'_>) -> K,
+        K: Ord,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Sorts the slice with a comparator function, but might not preserve the
+    /// order of equal elements.
+    ///
+    /// Like [`sort_by`], this sorts a permutation of indices which is then
+    /// applied to every field. If `compare` panics, the slice is left
+    /// unmodified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(5), Foo(4), Foo(1), Foo(3), Foo(2)];
+    /// soa.sort_unstable_by(|a, b| b.0.cmp(a.0));
+    /// assert_eq!(soa, [Foo(5), Foo(4), Foo(3), Foo(2), Foo(1)]);
+    /// ```
+    ///
+    /// [`sort_by`]: Slice::sort_by
+    pub fn sort_unstable_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(T::Ref<'_>, T::Ref<'_>) -> Ordering,
+    {
+        let raw = self.raw();
+        let mut permutation: Vec<usize> = (0..self.len()).collect();
+        // SAFETY: The indices are in bounds.
+        permutation.sort_unstable_by(|&a, &b| unsafe {
+            compare(raw.offset(a).get_ref(), raw.offset(b).get_ref())
+        });
+        // SAFETY: The indices are a permutation of 0..len.
+        unsafe { self.apply_permutation(&mut permutation) };
+    }
+
+    /// Sorts the slice with a key extraction function, but might not preserve
+    /// the order of equal elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(i32);
+    /// let mut soa = soa![Foo(-5), Foo(4), Foo(1), Foo(-3), Foo(2)];
+    /// soa.sort_unstable_by_key(|foo| foo.0.abs());
+    /// assert_eq!(soa, [Foo(1), Foo(2), Foo(-3), Foo(4), Foo(-5)]);
+    /// ```
+    pub fn sort_unstable_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(T::Ref<'_>) -> K,
+        K: Ord,
+    {
+        self.sort_unstable_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Sorts the slice with a key extraction function, preserving the initial
+    /// order of equal elements.
+    ///
+    /// The key function is called only once per element, with the keys cached
+    /// for the duration of the sort.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(i32);
+    /// let mut soa = soa![Foo(-5), Foo(4), Foo(32), Foo(-3), Foo(2)];
+    /// soa.sort_by_cached_key(|foo| foo.0.to_string());
+    /// assert_eq!(soa, [Foo(-3), Foo(-5), Foo(2), Foo(32), Foo(4)]);
+    /// ```
+    pub fn sort_by_cached_key<K, F>(&mut self, f: F)
+    where
+        F: FnMut(T::Ref<'_>) -> K,
+        K: Ord,
+    {
+        // Pairing each key with its index makes the unstable sort stable.
+        let mut keys: Vec<_> = self.iter().map(f).zip(0..).collect();
+        keys.sort_unstable();
+        let mut permutation: Vec<usize> = keys.into_iter().map(|(_, i)| i).collect();
+        // SAFETY: The indices are a permutation of 0..len.
+        unsafe { self.apply_permutation(&mut permutation) };
+    }
+
+    /// Reorders the slice in place such that the element previously at
+    /// `indices[i]` moves to index `i`.
+    ///
+    /// This applies the same permutation to every field. It is useful for
+    /// reordering the slice according to an argsort computed on one of its
+    /// fields. Elements are moved by following the cycles of the permutation,
+    /// which allocates a copy of `indices`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` is not a permutation of `0..self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo { key: u32, name: &'static str }
+    /// let mut soa = soa![
+    ///     Foo { key: 3, name: "c" },
+    ///     Foo { key: 1, name: "a" },
+    ///     Foo { key: 2, name: "b" },
+    /// ];
+    /// let mut indices: Vec<_> = (0..soa.len()).collect();
+    /// indices.sort_by_key(|&i| soa.key()[i]);
+    /// soa.permute(&indices);
+    /// assert_eq!(soa.name(), ["a", "b", "c"]);
+    /// ```
+    pub fn permute(&mut self, indices: &[usize]) {
+        assert_eq!(
+            indices.len(),
+            self.len(),
+            "permutation length does not match slice length"
+        );
+        let mut seen = alloc::vec![false; indices.len()];
+        for &index in indices {
+            let seen = seen
+                .get_mut(index)
+                .expect("permutation index out of bounds");
+            assert!(!*seen, "permutation contains duplicate indices");
+            *seen = true;
+        }
+
+        let mut permutation = indices.to_vec();
+        // SAFETY: The indices were checked to be a permutation of 0..len.
+        unsafe { self.apply_permutation(&mut permutation) };
+    }
+
+    /// Writes clones of the elements of `src` to the given indices of `self`,
+    /// such that the element at `src[i]` is cloned to `self[indices[i]]`.
+    ///
+    /// The replaced elements are dropped. If an index repeats, the last write
+    /// wins. This is the inverse of [`Soa::gather`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` and `src` have different lengths or if any index is
+    /// out-of-bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(0), Foo(0), Foo(0), Foo(0)];
+    /// soa.scatter(&soa![Foo(10), Foo(30)], &[1, 3]);
+    /// assert_eq!(soa, [Foo(0), Foo(10), Foo(0), Foo(30)]);
+    /// ```
+    pub fn scatter(&mut self, src: &Slice<T>, indices: &[usize])
+    where
+        T: Clone,
+        T::Raw: SoaRawClone,
+    {
+        assert_eq!(
+            indices.len(),
+            src.len(),
+            "source and index slices have different lengths"
+        );
+        let mut tmp = MaybeUninit::<T::Array<1>>::uninit();
+        let tmp = unsafe { T::Raw::from_array::<1>(tmp.as_mut_ptr()) };
+        for (i, &index) in indices.iter().enumerate() {
+            assert!(index < self.len(), "index out of bounds");
+            let dst = unsafe { self.raw().offset(index) };
+            // SAFETY: The clone is made before anything in self is modified,
+            // so a panicking clone leaves self valid. The old element is
+            // dropped only after the new one is in place.
+            unsafe {
+                src.raw().offset(i).clone_to(tmp);
+                let old = dst.get();
+                tmp.copy_to(dst, 1);
+                drop(old);
+            }
+        }
+    }
+
+    /// Returns a new [`Soa`] with clones of the elements for which the
+    /// corresponding entry of `mask` is `true`.
+    ///
+    /// The clones are written directly into the columns, field by field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mask` and the slice have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(1), Foo(2), Foo(3), Foo(4)];
+    /// let mask: Vec<_> = soa.f0().iter().map(|x| *x > 2).collect();
+    /// assert_eq!(soa.filter_by_mask(&mask), [Foo(3), Foo(4)]);
+    /// ```
+    pub fn filter_by_mask(&self, mask: &[bool]) -> Soa<T>
+    where
+        T: Clone,
+        T::Raw: SoaRawClone,
+    {
+        assert_eq!(
+            mask.len(),
+            self.len(),
+            "mask and slice have different lengths"
+        );
+        let count = mask.iter().filter(|&&keep| keep).count();
+        let mut out = Soa::<T>::with_capacity(count);
+        for (i, &keep) in mask.iter().enumerate() {
+            if keep {
+                // SAFETY: The source element is initialized and the
+                // destination is within the capacity and past the length.
+                unsafe { self.raw().offset(i).clone_to(out.raw().offset(out.len)) };
+                out.len += 1;
+            }
+        }
+        out
+    }
+
+    /// Binary searches this slice with a comparator function.
+    ///
+    /// The comparator function should return an order code that indicates
+    /// whether its argument is `Less`, `Equal` or `Greater` the desired target.
+    /// If the slice is not sorted or if the comparator function does not
+    /// implement an order consistent with the sort order of the underlying
+    /// slice, the returned result is unspecified and meaningless.
+    ///
+    /// If the value is found then [`Result::Ok`] is returned, containing the
+    /// index of the matching element. If there are multiple matches, then any
+    /// one of the matches could be returned. If the value is not found then
+    /// [`Result::Err`] is returned, containing the index where a matching
+    /// element could be inserted while maintaining sorted order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(1), Foo(3), Foo(5), Foo(7)];
+    /// assert_eq!(soa.binary_search_by(|foo| foo.0.cmp(&5)), Ok(2));
+    /// assert_eq!(soa.binary_search_by(|foo| foo.0.cmp(&4)), Err(2));
+    /// assert_eq!(soa.binary_search_by(|foo| foo.0.cmp(&8)), Err(4));
+    /// ```
+    pub fn binary_search_by<'a, F>(&'a self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(T::Ref<'a>) -> Ordering,
+    {
+        let mut size = self.len();
+        if size == 0 {
+            return Err(0);
+        }
+
+        let raw = self.raw();
+        // SAFETY: base + size <= len is maintained by the loop.
+        let mut compare = |i: usize| f(unsafe { raw.offset(i).get_ref() });
+        let mut base = 0;
+        while size > 1 {
+            let half = size / 2;
+            let mid = base + half;
+            if compare(mid) != Ordering::Greater {
+                base = mid;
+            }
+            size -= half;
+        }
+
+        match compare(base) {
+            Ordering::Equal => Ok(base),
+            Ordering::Less => Err(base + 1),
+            Ordering::Greater => Err(base),
+        }
+    }
+
+    /// Binary searches this slice with a key extraction function.
+    ///
+    /// Assumes that the slice is sorted by the key. See [`binary_search_by`]
+    /// for the meaning of the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Sample { time: u64, value: f32 }
+    /// let soa = soa![
+    ///     Sample { time: 10, value: 0.5 },
+    ///     Sample { time: 20, value: 0.25 },
+    ///     Sample { time: 30, value: 0.75 },
+    /// ];
+    /// assert_eq!(soa.binary_search_by_key(&20, |s| *s.time), Ok(1));
+    /// assert_eq!(soa.binary_search_by_key(&25, |s| *s.time), Err(2));
+    /// ```
+    ///
+    /// [`binary_search_by`]: Slice::binary_search_by
+    pub fn binary_search_by_key<'a, B, F>(&'a self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(T::Ref<'a>) -> B,
+        B: Ord,
+    {
+        self.binary_search_by(|el| f(el).cmp(b))
+    }
+
+    /// Returns the index of the partition point according to the given
+    /// predicate (the index of the first element of the second partition).
+    ///
+    /// The slice is assumed to be partitioned according to the given
+    /// predicate. This means that all elements for which the predicate returns
+    /// true are at the start of the slice and all elements for which the
+    /// predicate returns false are at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(1), Foo(2), Foo(3), Foo(5), Foo(8)];
+    /// let i = soa.partition_point(|foo| *foo.0 < 4);
+    /// assert_eq!(i, 3);
+    /// assert_eq!(soa.idx(..i), [Foo(1), Foo(2), Foo(3)]);
+    /// ```
+    pub fn partition_point<'a, P>(&'a self, mut pred: P) -> usize
+    where
+        P: FnMut(T::Ref<'a>) -> bool,
+    {
+        self.binary_search_by(|el| {
+            if pred(el) {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        })
+        .unwrap_or_else(|i| i)
+    }
+
+    /// Reorders the slice in place such that all elements for which `pred`
+    /// returns `true` precede all those for which it returns `false`.
+    ///
+    /// Returns the number of elements for which `pred` returned `true`. The
+    /// relative order of those elements is preserved, but the order of the
+    /// others is not. Elements are moved field by field and `pred` is called
+    /// exactly once per element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4), Foo(6)];
+    /// let evens = soa.partition(|foo| *foo.0 % 2 == 0);
+    /// assert_eq!(evens, 3);
+    /// assert_eq!(soa.idx(..evens), [Foo(2), Foo(4), Foo(6)]);
+    /// ```
+    pub fn partition<F>(&mut self, mut pred: F) -> usize
+    where
+        F: FnMut(T::Ref<'_>) -> bool,
+    {
+        let raw = self.raw();
+        let mut tmp = MaybeUninit::<T::Array<1>>::uninit();
+        let tmp = unsafe { T::Raw::from_array::<1>(tmp.as_mut_ptr()) };
+        let mut matched = 0;
+        for i in 0..self.len() {
+            // SAFETY: Both indices are in bounds and every swap leaves the
+            // slice valid, even if a later call to pred panics.
+            unsafe {
+                if pred(raw.offset(i).get_ref()) {
+                    Self::swap_raw(raw.offset(matched), raw.offset(i), tmp);
+                    matched += 1;
+                }
+            }
+        }
+        matched
+    }
+
+    /// Returns `true` if the slice contains an element with the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(10), Foo(40), Foo(30)];
+    /// assert!(soa.contains(&Foo(30)));
+    /// assert!(!soa.contains(&Foo(50)));
+    /// ```
+    pub fn contains<U>(&self, x: &U) -> bool
+    where
+        U: AsSoaRef<Item = T>,
+        for<'a> T::Ref<'a>: PartialEq,
+    {
+        let x = x.as_soa_ref();
+        self.iter().any(|el| el == x)
+    }
+
+    /// Returns `true` if `needle` is a prefix of the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(10), Foo(40), Foo(30)];
+    /// assert!(soa.starts_with(&[Foo(10), Foo(40)]));
+    /// assert!(!soa.starts_with(&[Foo(40)]));
+    /// assert!(soa.starts_with(&[]));
+    /// ```
+    pub fn starts_with(&self, needle: &[T]) -> bool
+    where
+        for<'a> T::Ref<'a>: PartialEq,
+    {
+        let n = needle.len();
+        self.len() >= n && self.idx(..n) == *needle
+    }
+
+    /// Returns `true` if `needle` is a suffix of the slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(10), Foo(40), Foo(30)];
+    /// assert!(soa.ends_with(&[Foo(40), Foo(30)]));
+    /// assert!(!soa.ends_with(&[Foo(40)]));
+    /// assert!(soa.ends_with(&[]));
     /// ```
-    pub fn swap(&mut self, a: usize, b: usize) {
-        if a >= self.len() || b >= self.len() {
-            panic!("index out of bounds");
+    pub fn ends_with(&self, needle: &[T]) -> bool
+    where
+        for<'a> T::Ref<'a>: PartialEq,
+    {
+        let (len, n) = (self.len(), needle.len());
+        len >= n && self.idx(len - n..) == *needle
+    }
+
+    /// Moves the element at `permutation[i]` to index `i` for every index.
+    ///
+    /// Elements are moved one field at a time through a single-element SoA
+    /// array, so no values of `T` are created and nothing can panic midway.
+    /// The contents of `permutation` are unspecified afterwards.
+    ///
+    /// # Safety
+    ///
+    /// `permutation` must contain each index in `0..self.len()` exactly once.
+    unsafe fn apply_permutation(&mut self, permutation: &mut [usize]) {
+        debug_assert_eq!(permutation.len(), self.len());
+        let raw = self.raw();
+        let mut tmp = MaybeUninit::<T::Array<1>>::uninit();
+        let tmp = unsafe { T::Raw::from_array::<1>(tmp.as_mut_ptr()) };
+        for start in 0..permutation.len() {
+            if permutation[start] == start {
+                continue;
+            }
+
+            // Follow the cycle through start, marking each visited index as
+            // done by making it a fixed point.
+            unsafe { raw.offset(start).copy_to(tmp, 1) };
+            let mut current = start;
+            loop {
+                let next = permutation[current];
+                permutation[current] = current;
+                if next == start {
+                    unsafe { tmp.copy_to(raw.offset(current), 1) };
+                    break;
+                }
+                unsafe { raw.offset(next).copy_to(raw.offset(current), 1) };
+                current = next;
+            }
         }
+    }
 
+    /// Swaps the elements at `a` and `b` by moving their fields through `tmp`.
+    ///
+    /// # Safety
+    ///
+    /// `a` and `b` must point to initialized elements and `tmp` must have room
+    /// for one element.
+    unsafe fn swap_raw(a: T::Raw, b: T::Raw, tmp: T::Raw) {
         unsafe {
-            let a = self.raw().offset(a);
-            let b = self.raw().offset(b);
-            let tmp = a.get();
+            a.copy_to(tmp, 1);
             b.copy_to(a, 1);
-            b.set(tmp);
+            tmp.copy_to(b, 1);
         }
     }
 
-    /// Returns the first element of the slice, or None if empty.
+    /// Reverses the `len` elements starting at `raw`, using `tmp` as for
+    /// [`Slice::swap_raw`].
+    ///
+    /// # Safety
+    ///
+    /// The `len` elements must be initialized and `tmp` must have room for one
+    /// element.
+    unsafe fn reverse_raw(raw: T::Raw, len: usize, tmp: T::Raw) {
+        for i in 0..len / 2 {
+            unsafe { Self::swap_raw(raw.offset(i), raw.offset(len - 1 - i), tmp) };
+        }
+    }
+
+    /// Replaces the element at `raw` with `value`, dropping the old element.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must point to an initialized element.
+    unsafe fn replace_raw(raw: T::Raw, value: T) {
+        // The new value is written before the old one is dropped so that a
+        // panic in drop does not leave a dropped element in the slice.
+        let old = unsafe { raw.get() };
+        unsafe { raw.set(value) };
+        drop(old);
+    }
+
+    /// Returns an iterator over `chunk_size` elements of the slice at a time,
+    /// starting at the beginning of the slice.
+    ///
+    /// The chunks are slices and do not overlap. If `chunk_size` does not divide
+    /// the length of the slice, then the last up to `chunk_size-1` elements will
+    /// be omitted and can be retrieved from the [`remainder`] function of the
+    /// iterator.
+    ///
+    /// Due to each chunk having exactly `chunk_size` elements, the compiler can
+    /// often optimize the resulting code better than in the case of chunks.
+    ///
+    /// [`remainder`]: ChunksExact::remainder
     ///
     /// # Examples
     ///
@@ -356,18 +1472,72 @@ where
     /// # use soa_rs::{Soa, Soars, soa};
     /// # #[derive(Soars, Debug, PartialEq)]
     /// # #[soa_derive(Debug, PartialEq)]
-    /// # struct Foo(usize);
-    /// let soa = soa![Foo(10), Foo(40), Foo(30)];
-    /// assert_eq!(soa.first().unwrap(), Foo(10));
+    /// # struct Foo(char);
+    /// let soa = soa![Foo('l'), Foo('o'), Foo('r'), Foo('e'), Foo('m')];
+    /// let mut iter = soa.chunks_exact(2);
+    /// assert_eq!(iter.next().unwrap(), [Foo('l'), Foo('o')]);
+    /// assert_eq!(iter.next().unwrap(), [Foo('r'), Foo('e')]);
+    /// assert!(iter.next().is_none());
+    /// assert_eq!(iter.remainder(), [Foo('m')]);
+    /// ```
+    pub fn chunks_exact(&self, chunk_size: usize) -> ChunksExact<'_, T> {
+        if chunk_size == 0 {
+            panic!("chunk size must be nonzero")
+        }
+
+        ChunksExact::new(self, chunk_size)
+    }
+
+    /// Returns an iterator over `chunk_size` elements of the slice at a time,
+    /// starting at the beginning of the slice.
+    ///
+    /// The chunks are slices and do not overlap. If `chunk_size` does not divide
+    /// the length of the slice, then the last chunk will not have length
+    /// `chunk_size`.
+    ///
+    /// See [`chunks_exact`] for a variant of this iterator that returns chunks
+    /// of always exactly `chunk_size` elements, and [`rchunks`] for the same
+    /// iterator but starting at the end of the slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    ///
+    /// [`chunks_exact`]: Slice::chunks_exact
+    /// [`rchunks`]: Slice::rchunks
+    ///
+    /// # Examples
     ///
-    /// let soa = Soa::<Foo>::new();
-    /// assert_eq!(soa.first(), None);
     /// ```
-    pub fn first(&self) -> Option<T::Ref<'_>> {
-        self.get(0)
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(char);
+    /// let soa = soa![Foo('l'), Foo('o'), Foo('r'), Foo('e'), Foo('m')];
+    /// let mut iter = soa.chunks(2);
+    /// assert_eq!(iter.next().unwrap(), [Foo('l'), Foo('o')]);
+    /// assert_eq!(iter.next().unwrap(), [Foo('r'), Foo('e')]);
+    /// assert_eq!(iter.next().unwrap(), [Foo('m')]);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn chunks(&self, chunk_size: usize) -> Chunks<'_, T> {
+        if chunk_size == 0 {
+            panic!("chunk size must be nonzero")
+        }
+
+        Chunks::new(self, chunk_size)
     }
 
-    /// Returns a mutable reference to the first element of the slice, or None if empty.
+    /// Returns an iterator over `chunk_size` elements of the slice at a time,
+    /// starting at the beginning of the slice.
+    ///
+    /// The chunks are mutable slices and do not overlap. If `chunk_size` does
+    /// not divide the length of the slice, then the last chunk will not have
+    /// length `chunk_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
     ///
     /// # Examples
     ///
@@ -376,17 +1546,33 @@ where
     /// # #[derive(Soars, Debug, PartialEq)]
     /// # #[soa_derive(Debug, PartialEq)]
     /// # struct Foo(usize);
-    /// let mut soa = soa![Foo(0), Foo(1), Foo(2)];
-    /// if let Some(mut first) = soa.first_mut() {
-    ///     *first.0 = 5;
+    /// let mut soa = soa![Foo(0), Foo(0), Foo(0), Foo(0), Foo(0)];
+    /// for (i, mut chunk) in soa.chunks_mut(2).enumerate() {
+    ///     chunk.f0_mut().fill(i);
     /// }
-    /// assert_eq!(soa, [Foo(5), Foo(1), Foo(2)]);
+    /// assert_eq!(soa.f0(), [0, 0, 1, 1, 2]);
     /// ```
-    pub fn first_mut(&mut self) -> Option<T::RefMut<'_>> {
-        self.get_mut(0)
+    pub fn chunks_mut(&mut self, chunk_size: usize) -> ChunksMut<'_, T> {
+        if chunk_size == 0 {
+            panic!("chunk size must be nonzero")
+        }
+
+        ChunksMut::new(self, chunk_size)
     }
 
-    /// Returns the last element of the slice, or None if empty.
+    /// Returns an iterator over `chunk_size` elements of the slice at a time,
+    /// starting at the beginning of the slice.
+    ///
+    /// The chunks are mutable slices and do not overlap. If `chunk_size` does
+    /// not divide the length of the slice, then the last up to `chunk_size-1`
+    /// elements will be omitted and can be retrieved from the
+    /// [`into_remainder`] function of the iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    ///
+    /// [`into_remainder`]: ChunksExactMut::into_remainder
     ///
     /// # Examples
     ///
@@ -395,17 +1581,32 @@ where
     /// # #[derive(Soars, Debug, PartialEq)]
     /// # #[soa_derive(Debug, PartialEq)]
     /// # struct Foo(usize);
-    /// let soa = soa![Foo(10), Foo(40), Foo(30)];
-    /// assert_eq!(soa.last().unwrap(), Foo(30));
-    ///
-    /// let soa = Soa::<Foo>::new();
-    /// assert_eq!(soa.last(), None);
+    /// let mut soa = soa![Foo(0), Foo(0), Foo(0), Foo(0), Foo(0)];
+    /// let mut iter = soa.chunks_exact_mut(2);
+    /// for (i, mut chunk) in (&mut iter).enumerate() {
+    ///     chunk.f0_mut().fill(i);
+    /// }
+    /// *iter.into_remainder().idx_mut(0).0 = 9;
+    /// assert_eq!(soa.f0(), [0, 0, 1, 1, 9]);
     /// ```
-    pub fn last(&self) -> Option<T::Ref<'_>> {
-        self.get(self.len().saturating_sub(1))
+    pub fn chunks_exact_mut(&mut self, chunk_size: usize) -> ChunksExactMut<'_, T> {
+        if chunk_size == 0 {
+            panic!("chunk size must be nonzero")
+        }
+
+        ChunksExactMut::new(self, chunk_size)
     }
 
-    /// Returns a mutable reference to the last element of the slice, or None if empty.
+    /// Returns an iterator over `chunk_size` elements of the slice at a time,
+    /// starting at the end of the slice.
+    ///
+    /// The chunks are slices and do not overlap. If `chunk_size` does not divide
+    /// the length of the slice, then the last chunk will not have length
+    /// `chunk_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
     ///
     /// # Examples
     ///
@@ -413,29 +1614,35 @@ where
     /// # use soa_rs::{Soa, Soars, soa};
     /// # #[derive(Soars, Debug, PartialEq)]
     /// # #[soa_derive(Debug, PartialEq)]
-    /// # struct Foo(usize);
-    /// let mut soa = soa![Foo(0), Foo(1), Foo(2)];
-    /// if let Some(mut last) = soa.last_mut() {
-    ///     *last.0 = 5;
-    /// }
-    /// assert_eq!(soa, [Foo(0), Foo(1), Foo(5)]);
+    /// # struct Foo(char);
+    /// let soa = soa![Foo('l'), Foo('o'), Foo('r'), Foo('e'), Foo('m')];
+    /// let mut iter = soa.rchunks(2);
+    /// assert_eq!(iter.next().unwrap(), [Foo('e'), Foo('m')]);
+    /// assert_eq!(iter.next().unwrap(), [Foo('o'), Foo('r')]);
+    /// assert_eq!(iter.next().unwrap(), [Foo('l')]);
+    /// assert!(iter.next().is_none());
     /// ```
-    pub fn last_mut(&mut self) -> Option<T::RefMut<'_>> {
-        self.get_mut(self.len().saturating_sub(1))
+    pub fn rchunks(&self, chunk_size: usize) -> RChunks<'_, T> {
+        if chunk_size == 0 {
+            panic!("chunk size must be nonzero")
+        }
+
+        RChunks::new(self, chunk_size)
     }
 
     /// Returns an iterator over `chunk_size` elements of the slice at a time,
-    /// starting at the beginning of the slice.
+    /// starting at the end of the slice.
     ///
     /// The chunks are slices and do not overlap. If `chunk_size` does not divide
-    /// the length of the slice, then the last up to `chunk_size-1` elements will
-    /// be omitted and can be retrieved from the [`remainder`] function of the
-    /// iterator.
+    /// the length of the slice, then the first up to `chunk_size-1` elements
+    /// will be omitted and can be retrieved from the [`remainder`] function of
+    /// the iterator.
     ///
-    /// Due to each chunk having exactly `chunk_size` elements, the compiler can
-    /// often optimize the resulting code better than in the case of chunks.
+    /// # Panics
     ///
-    /// [`remainder`]: ChunksExact::remainder
+    /// Panics if `chunk_size` is 0.
+    ///
+    /// [`remainder`]: RChunksExact::remainder
     ///
     /// # Examples
     ///
@@ -445,18 +1652,167 @@ where
     /// # #[soa_derive(Debug, PartialEq)]
     /// # struct Foo(char);
     /// let soa = soa![Foo('l'), Foo('o'), Foo('r'), Foo('e'), Foo('m')];
-    /// let mut iter = soa.chunks_exact(2);
-    /// assert_eq!(iter.next().unwrap(), [Foo('l'), Foo('o')]);
-    /// assert_eq!(iter.next().unwrap(), [Foo('r'), Foo('e')]);
+    /// let mut iter = soa.rchunks_exact(2);
+    /// assert_eq!(iter.next().unwrap(), [Foo('e'), Foo('m')]);
+    /// assert_eq!(iter.next().unwrap(), [Foo('o'), Foo('r')]);
     /// assert!(iter.next().is_none());
-    /// assert_eq!(iter.remainder(), [Foo('m')]);
+    /// assert_eq!(iter.remainder(), [Foo('l')]);
     /// ```
-    pub fn chunks_exact(&self, chunk_size: usize) -> ChunksExact<'_, T> {
+    pub fn rchunks_exact(&self, chunk_size: usize) -> RChunksExact<'_, T> {
         if chunk_size == 0 {
             panic!("chunk size must be nonzero")
         }
 
-        ChunksExact::new(self, chunk_size)
+        RChunksExact::new(self, chunk_size)
+    }
+
+    /// Returns an iterator over all contiguous windows of length `size`.
+    ///
+    /// The windows overlap. If the slice is shorter than `size`, the iterator
+    /// returns no values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(char);
+    /// let soa = soa![Foo('r'), Foo('u'), Foo('s'), Foo('t')];
+    /// let mut iter = soa.windows(2);
+    /// assert_eq!(iter.next().unwrap(), [Foo('r'), Foo('u')]);
+    /// assert_eq!(iter.next().unwrap(), [Foo('u'), Foo('s')]);
+    /// assert_eq!(iter.next().unwrap(), [Foo('s'), Foo('t')]);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn windows(&self, size: usize) -> Windows<'_, T> {
+        if size == 0 {
+            panic!("window size must be nonzero")
+        }
+
+        Windows::new(self, size)
+    }
+
+    /// Returns an iterator over the slice producing non-overlapping runs of
+    /// elements using the predicate to separate them.
+    ///
+    /// The predicate is called for every pair of consecutive elements, meaning
+    /// that it is called on `slice[0]` and `slice[1]`, followed by `slice[1]`
+    /// and `slice[2]`, and so on. A new run starts wherever it returns
+    /// `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = soa![Foo(1), Foo(1), Foo(2), Foo(3), Foo(3)];
+    /// let mut iter = soa.chunk_by(|a, b| a.0 == b.0);
+    /// assert_eq!(iter.next().unwrap(), [Foo(1), Foo(1)]);
+    /// assert_eq!(iter.next().unwrap(), [Foo(2)]);
+    /// assert_eq!(iter.next().unwrap(), [Foo(3), Foo(3)]);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn chunk_by<F>(&self, f: F) -> ChunkBy<'_, T, F>
+    where
+        F: FnMut(T::Ref<'_>, T::Ref<'_>) -> bool,
+    {
+        ChunkBy::new(self, f)
+    }
+
+    /// Returns an iterator over `N` elements of the slice at a time, where each
+    /// chunk has a fixed-size array for each field.
+    ///
+    /// For a type `Foo`, the chunks are of the generated type `FooChunk`, which
+    /// has a field of type `&[F; N]` for each field of type `F`. Because the
+    /// lengths are known at compile time, loops over the fields of a chunk
+    /// can be reliably vectorized, particularly when the columns are aligned
+    /// with the `#[align]` attribute. If `N` does not divide the length of the
+    /// slice, then the last up to `N-1` elements will be omitted and can be
+    /// retrieved from the [`remainder`] or [`remainder_padded`] functions of
+    /// the iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// [`remainder`]: ChunksLanes::remainder
+    /// [`remainder_padded`]: ChunksLanes::remainder_padded
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Vec2 {
+    /// #     x: f32,
+    /// #     y: f32,
+    /// # }
+    /// let soa: Soa<Vec2> = (0..10)
+    ///     .map(|i| Vec2 {
+    ///         x: i as f32,
+    ///         y: 2.0,
+    ///     })
+    ///     .collect();
+    ///
+    /// let mut chunks = soa.chunks_lanes::<4>();
+    /// let mut sums = [0.0; 4];
+    /// for chunk in &mut chunks {
+    ///     for i in 0..4 {
+    ///         sums[i] += chunk.x[i] * chunk.y[i];
+    ///     }
+    /// }
+    /// assert_eq!(sums, [8.0, 12.0, 16.0, 20.0]);
+    /// assert_eq!(chunks.remainder().x(), [8.0, 9.0]);
+    /// ```
+    pub fn chunks_lanes<const N: usize>(&self) -> ChunksLanes<'_, T, N> {
+        if N == 0 {
+            panic!("chunk size must be nonzero")
+        }
+
+        ChunksLanes::new(self)
+    }
+
+    /// Returns an iterator over `N` elements of the slice at a time, where each
+    /// chunk has a fixed-size mutable array for each field.
+    ///
+    /// For a type `Foo`, the chunks are of the generated type `FooChunkMut`.
+    /// See [`Slice::chunks_lanes`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(u32);
+    /// let mut soa: Soa<Foo> = (0..5).map(Foo).collect();
+    /// let mut chunks = soa.chunks_lanes_mut::<2>();
+    /// for chunk in &mut chunks {
+    ///     for x in chunk.0 {
+    ///         *x *= 10;
+    ///     }
+    /// }
+    /// *chunks.into_remainder().idx_mut(0).0 = 0;
+    /// assert_eq!(soa.f0(), [0, 10, 20, 30, 0]);
+    /// ```
+    pub fn chunks_lanes_mut<const N: usize>(&mut self) -> ChunksLanesMut<'_, T, N> {
+        if N == 0 {
+            panic!("chunk size must be nonzero")
+        }
+
+        ChunksLanesMut::new(self)
     }
 
     /// Returns a collection of slices for each field of the slice.
@@ -520,7 +1876,7 @@ where
     ///
     /// [`PhantomData`]: std::marker::PhantomData
     pub(crate) const unsafe fn as_sized(&self) -> Slice<T, ()> {
-        *(std::ptr::from_ref(self).cast())
+        *(core::ptr::from_ref(self).cast())
     }
 }
 
@@ -535,6 +1891,21 @@ where
 
 impl<T> Copy for Slice<T, ()> where T: Soars {}
 
+// SAFETY: Slice is Send and Sync under the same conditions as [T].
+unsafe impl<T, D> Send for Slice<T, D>
+where
+    T: Send + Soars,
+    D: ?Sized + Send,
+{
+}
+
+unsafe impl<T, D> Sync for Slice<T, D>
+where
+    T: Sync + Soars,
+    D: ?Sized + Sync,
+{
+}
+
 impl<'a, T> IntoIterator for &'a Slice<T>
 where
     T: Soars,
@@ -668,7 +2039,7 @@ macro_rules! eq_for_slice_ref {
         eq_for_slice_ref!($t, Slice<T>);
         eq_for_slice_ref!($t, SliceRef<'_, T>);
         eq_for_slice_ref!($t, SliceMut<'_, T>);
-        eq_for_slice_ref!($t, Soa<T>);
+        eq_for_slice_ref!($t, Soa<T, A>, A: Allocator);
     };
 
     ($t:ty, $s:ty $(,$($b:tt)+)?) => {
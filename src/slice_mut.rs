@@ -1,5 +1,5 @@
 use crate::{eq_impl, iter_raw::IterRaw, IterMut, Slice, SliceRef, Soa, Soars};
-use std::{
+use core::{
     cmp::Ordering,
     fmt::{self, Debug, Formatter},
     hash::{Hash, Hasher},
@@ -43,6 +43,10 @@ where
     }
 }
 
+// SAFETY: SliceMut is Send and Sync under the same conditions as &mut [T].
+unsafe impl<'a, T> Send for SliceMut<'a, T> where T: 'a + Send + Soars {}
+unsafe impl<'a, T> Sync for SliceMut<'a, T> where T: 'a + Sync + Soars {}
+
 impl<'a, T> AsRef<Slice<T>> for SliceMut<'a, T>
 where
     T: Soars,
@@ -1,5 +1,5 @@
 use crate::{eq_impl, iter_raw::IterRaw, Iter, Slice, SliceMut, Soa, Soars};
-use std::{
+use core::{
     cmp::Ordering,
     fmt::{self, Debug, Formatter},
     hash::{Hash, Hasher},
@@ -54,6 +54,10 @@ where
 
 impl<'a, T> Copy for SliceRef<'a, T> where T: 'a + Soars {}
 
+// SAFETY: SliceRef is Send and Sync under the same conditions as &[T].
+unsafe impl<'a, T> Send for SliceRef<'a, T> where T: 'a + Sync + Soars {}
+unsafe impl<'a, T> Sync for SliceRef<'a, T> where T: 'a + Sync + Soars {}
+
 impl<'a, T> AsRef<Slice<T>> for SliceRef<'a, T>
 where
     T: Soars,
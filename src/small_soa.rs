@@ -0,0 +1,603 @@
+use crate::{
+    eq_impl, ArraySoa, Iter, IterMut, Slice, SliceMut, SliceRef, Soa, SoaRaw, SoaRawClone, Soars,
+};
+use core::{
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    mem,
+    ops::{Deref, DerefMut},
+};
+
+/// A SoA container that stores up to `N` elements inline before spilling to
+/// the heap.
+///
+/// What [`Soa`] is to `Vec<T>`, `SmallSoa` is to `SmallVec<[T; N]>`. While it
+/// holds at most `N` elements, its columns live in an [`ArraySoa`]. Once it
+/// overflows, the elements are moved into a [`Soa`]. Either way, it
+/// dereferences to [`Slice`], so the field getters work the same.
+///
+/// Like [`ArraySoa`], `SmallSoa` is [`Send`] but not [`Sync`].
+///
+/// # Examples
+///
+/// ```
+/// # use soa_rs::{SmallSoa, Soars};
+/// # #[derive(Soars, Debug, PartialEq)]
+/// # #[soa_derive(Debug, PartialEq)]
+/// # struct Foo(usize);
+/// let mut soa = SmallSoa::<Foo, 2>::new();
+/// soa.push(Foo(1));
+/// soa.push(Foo(2));
+/// assert!(!soa.spilled());
+/// soa.push(Foo(3));
+/// assert!(soa.spilled());
+/// assert_eq!(soa.f0(), [1, 2, 3]);
+/// ```
+pub struct SmallSoa<T, const N: usize>
+where
+    T: Soars,
+{
+    data: Data<T, N>,
+}
+
+enum Data<T, const N: usize>
+where
+    T: Soars,
+{
+    Inline(ArraySoa<T, N>),
+    Heap(Soa<T>),
+}
+
+impl<T, const N: usize> SmallSoa<T, N>
+where
+    T: Soars,
+{
+    /// Constructs a new, empty `SmallSoa<T, N>`.
+    ///
+    /// The container will not allocate until more than `N` elements are
+    /// added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars, Copy, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo;
+    /// let soa = SmallSoa::<Foo, 4>::new();
+    /// assert!(soa.is_empty());
+    /// assert!(!soa.spilled());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            data: Data::Inline(ArraySoa::new()),
+        }
+    }
+
+    /// Constructs a new, empty `SmallSoa<T, N>` with at least the specified
+    /// capacity.
+    ///
+    /// The container only allocates if `capacity` is greater than `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa = SmallSoa::<Foo, 4>::with_capacity(2);
+    /// assert!(!soa.spilled());
+    /// let soa = SmallSoa::<Foo, 4>::with_capacity(8);
+    /// assert!(soa.spilled());
+    /// assert!(soa.capacity() >= 8);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity > N {
+            Self {
+                data: Data::Heap(Soa::with_capacity(capacity)),
+            }
+        } else {
+            Self::new()
+        }
+    }
+
+    /// Returns `true` if the elements have been moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    /// Returns the total number of elements the container can hold without
+    /// reallocating.
+    ///
+    /// This is `N` until the container spills.
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline(_) => N,
+            Data::Heap(soa) => soa.capacity(),
+        }
+    }
+
+    /// Appends an element to the back of the container, spilling to the heap
+    /// if the inline storage is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = SmallSoa::<Foo, 1>::new();
+    /// soa.push(Foo(1));
+    /// soa.push(Foo(2));
+    /// assert_eq!(soa, [Foo(1), Foo(2)]);
+    /// ```
+    pub fn push(&mut self, element: T) {
+        self.reserve(1);
+        match &mut self.data {
+            Data::Inline(array) => array.push(element),
+            Data::Heap(soa) => soa.push(element),
+        }
+    }
+
+    /// Removes the last element and returns it, or [`None`] if the container
+    /// is empty.
+    ///
+    /// This never moves the elements back inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: SmallSoa<Foo, 2> = [Foo(1), Foo(2)].into_iter().collect();
+    /// assert_eq!(soa.pop(), Some(Foo(2)));
+    /// assert_eq!(soa, [Foo(1)]);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        match &mut self.data {
+            Data::Inline(array) => array.pop(),
+            Data::Heap(soa) => soa.pop(),
+        }
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after it
+    /// to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: SmallSoa<Foo, 2> = [Foo(1), Foo(2)].into_iter().collect();
+    /// soa.insert(1, Foo(3));
+    /// assert_eq!(soa, [Foo(1), Foo(3), Foo(2)]);
+    /// ```
+    pub fn insert(&mut self, index: usize, element: T) {
+        assert!(index <= self.len(), "index out of bounds");
+        self.reserve(1);
+        match &mut self.data {
+            Data::Inline(array) => array.insert(index, element),
+            Data::Heap(soa) => soa.insert(index, element),
+        }
+    }
+
+    /// Removes and returns the element at position `index`, shifting all
+    /// elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: SmallSoa<Foo, 2> = [Foo(1), Foo(2), Foo(3)].into_iter().collect();
+    /// assert_eq!(soa.remove(1), Foo(2));
+    /// assert_eq!(soa, [Foo(1), Foo(3)]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        match &mut self.data {
+            Data::Inline(array) => array.remove(index),
+            Data::Heap(soa) => soa.remove(index),
+        }
+    }
+
+    /// Removes an element and returns it, replacing it with the last element.
+    ///
+    /// This does not preserve ordering, but is O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        match &mut self.data {
+            Data::Inline(array) => array.swap_remove(index),
+            Data::Heap(soa) => soa.swap_remove(index),
+        }
+    }
+
+    /// Shortens the container, keeping the first `len` elements and dropping
+    /// the rest.
+    pub fn truncate(&mut self, len: usize) {
+        match &mut self.data {
+            Data::Inline(array) => array.truncate(len),
+            Data::Heap(soa) => soa.truncate(len),
+        }
+    }
+
+    /// Clears the container, removing all values.
+    ///
+    /// This keeps the heap allocation, if there is one.
+    pub fn clear(&mut self) {
+        match &mut self.data {
+            Data::Inline(array) => array.clear(),
+            Data::Heap(soa) => soa.clear(),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: SmallSoa<Foo, 2> = (1..=4).map(Foo).collect();
+    /// soa.retain(|foo| foo.0 % 2 == 0);
+    /// assert_eq!(soa, [Foo(2), Foo(4)]);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(T::Ref<'_>) -> bool,
+    {
+        match &mut self.data {
+            Data::Inline(array) => array.retain(f),
+            Data::Heap(soa) => soa.retain(f),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable
+    /// reference to it.
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(T::RefMut<'_>) -> bool,
+    {
+        match &mut self.data {
+            Data::Inline(array) => array.retain_mut(f),
+            Data::Heap(soa) => soa.retain_mut(f),
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more elements, spilling to
+    /// the heap if they don't fit inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = SmallSoa::<Foo, 4>::new();
+    /// soa.reserve(4);
+    /// assert!(!soa.spilled());
+    /// soa.reserve(5);
+    /// assert!(soa.spilled());
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        match &mut self.data {
+            Data::Inline(array) => {
+                let required = array.len().checked_add(additional);
+                let required = required.expect("capacity overflow");
+                if required > N {
+                    self.spill(required.max(N * 2));
+                }
+            }
+            Data::Heap(soa) => soa.reserve(additional),
+        }
+    }
+
+    /// Moves the elements back inline if they fit, or otherwise shrinks the
+    /// heap allocation as much as possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa: SmallSoa<Foo, 2> = (1..=3).map(Foo).collect();
+    /// assert!(soa.spilled());
+    /// soa.pop();
+    /// soa.shrink_to_fit();
+    /// assert!(!soa.spilled());
+    /// assert_eq!(soa, [Foo(1), Foo(2)]);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let Data::Heap(soa) = &mut self.data else {
+            return;
+        };
+        if soa.len > N {
+            soa.shrink_to_fit();
+            return;
+        }
+
+        let mut array = ArraySoa::<T, N>::new();
+        unsafe {
+            soa.raw().copy_to(array.raw_mut(), soa.len);
+        }
+        array.len = soa.len;
+        // The elements were moved into the array
+        soa.len = 0;
+        self.data = Data::Inline(array);
+    }
+
+    /// Converts the container into a [`Soa`], allocating if the elements are
+    /// stored inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SmallSoa, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let soa: SmallSoa<Foo, 2> = [Foo(1), Foo(2)].into_iter().collect();
+    /// assert_eq!(soa.into_soa(), [Foo(1), Foo(2)]);
+    /// ```
+    pub fn into_soa(mut self) -> Soa<T> {
+        if let Data::Inline(array) = &self.data {
+            self.spill(array.len());
+        }
+        match mem::replace(&mut self.data, Data::Inline(ArraySoa::new())) {
+            Data::Heap(soa) => soa,
+            Data::Inline(_) => unreachable!(),
+        }
+    }
+
+    /// Returns a [`SliceRef`] over the elements.
+    pub fn as_slice(&self) -> SliceRef<'_, T> {
+        match &self.data {
+            Data::Inline(array) => array.as_slice(),
+            Data::Heap(soa) => soa.idx(..),
+        }
+    }
+
+    /// Returns a [`SliceMut`] over the elements.
+    pub fn as_mut_slice(&mut self) -> SliceMut<'_, T> {
+        match &mut self.data {
+            Data::Inline(array) => array.as_mut_slice(),
+            Data::Heap(soa) => soa.idx_mut(..),
+        }
+    }
+
+    /// Moves the inline elements into a new heap allocation with room for
+    /// `capacity` elements.
+    fn spill(&mut self, capacity: usize) {
+        let Data::Inline(array) = &mut self.data else {
+            return;
+        };
+        let mut soa = Soa::<T>::with_capacity(capacity);
+        unsafe {
+            array.raw_mut().copy_to(soa.raw(), array.len);
+        }
+        soa.len = array.len;
+        // The elements were moved into the Soa
+        array.len = 0;
+        self.data = Data::Heap(soa);
+    }
+}
+
+// SAFETY: SmallSoa is Send under the same conditions as ArraySoa and Soa.
+unsafe impl<T, const N: usize> Send for SmallSoa<T, N> where T: Send + Soars {}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallSoa<T, N>
+where
+    T: Soars,
+{
+    type Item = T::Ref<'a>;
+
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref().into_iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallSoa<T, N>
+where
+    T: Soars,
+{
+    type Item = T::RefMut<'a>;
+
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref_mut().into_iter()
+    }
+}
+
+impl<T, const N: usize> Clone for SmallSoa<T, N>
+where
+    T: Soars + Clone,
+    T::Raw: SoaRawClone,
+{
+    fn clone(&self) -> Self {
+        let data = match &self.data {
+            Data::Inline(array) => Data::Inline(array.clone()),
+            Data::Heap(soa) => Data::Heap(soa.clone()),
+        };
+        Self { data }
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.reserve(lower);
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<T, const N: usize> From<Soa<T>> for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    /// Wraps `value` without moving its elements inline.
+    fn from(value: Soa<T>) -> Self {
+        Self {
+            data: Data::Heap(value),
+        }
+    }
+}
+
+impl<T, const N: usize> Debug for SmallSoa<T, N>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+impl<T, const N: usize> PartialOrd for SmallSoa<T, N>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.deref().partial_cmp(other.deref())
+    }
+}
+
+impl<T, const N: usize> Ord for SmallSoa<T, N>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deref().cmp(other.deref())
+    }
+}
+
+impl<T, const N: usize> Hash for SmallSoa<T, N>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref().hash(state)
+    }
+}
+
+impl<T, const N: usize> Default for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> AsRef<Slice<T>> for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    fn as_ref(&self) -> &Slice<T> {
+        match &self.data {
+            Data::Inline(array) => array.as_ref(),
+            Data::Heap(soa) => soa.as_ref(),
+        }
+    }
+}
+
+impl<T, const N: usize> AsMut<Slice<T>> for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    fn as_mut(&mut self) -> &mut Slice<T> {
+        match &mut self.data {
+            Data::Inline(array) => array.as_mut(),
+            Data::Heap(soa) => soa.as_mut(),
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    type Target = Slice<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut()
+    }
+}
+
+impl<T, const N: usize> Borrow<Slice<T>> for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    fn borrow(&self) -> &Slice<T> {
+        self.as_ref()
+    }
+}
+
+impl<T, const N: usize> BorrowMut<Slice<T>> for SmallSoa<T, N>
+where
+    T: Soars,
+{
+    fn borrow_mut(&mut self) -> &mut Slice<T> {
+        self.as_mut()
+    }
+}
+
+eq_impl::impl_for!(SmallSoa<T, M>, const M: usize);
+eq_impl::uni!(SmallSoa<T, M>, SmallSoa<T, K>, const M: usize, const K: usize);
+eq_impl::uni!(SmallSoa<T, M>, ArraySoa<T, K>, const M: usize, const K: usize);
@@ -1,14 +1,16 @@
 use crate::{
-    eq_impl, iter_raw::IterRaw, IntoIter, Iter, IterMut, Slice, SliceMut, SliceRef, SoaRaw, Soars,
+    eq_impl, index::slice_range, iter_raw::IterRaw, Allocator, Drain, ExtractIf, Global, IntoIter,
+    Iter, IterMut, Slice, SliceMut, SliceRef, SoaRaw, SoaRawClone, Soars, Splice, TryPushError,
+    TryReserveError, TryReserveErrorKind,
 };
-use std::{
+use core::{
     borrow::{Borrow, BorrowMut},
     cmp::Ordering,
     fmt::{self, Debug, Formatter},
     hash::{Hash, Hasher},
     marker::PhantomData,
     mem::{needs_drop, size_of, ManuallyDrop},
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, Range, RangeBounds},
 };
 
 /// A growable array type that stores the values for each field of `T`
@@ -19,27 +21,26 @@ use std::{
 /// - Does not allocate until elements are added
 /// - Never deallocates memory unless explicitly requested
 /// - Uses `usize::MAX` as the capacity for zero-sized types
+/// - Allocates from an [`Allocator`], which is [`Global`] by default
 ///
 /// See the top-level [`soa_rs`] docs for usage examples.
 ///
 /// [`soa_rs`]: crate
-pub struct Soa<T>
+pub struct Soa<T, A = Global>
 where
     T: Soars,
+    A: Allocator,
 {
     pub(crate) cap: usize,
     pub(crate) slice: Slice<T, ()>,
     pub(crate) len: usize,
+    pub(crate) alloc: A,
 }
 
 impl<T> Soa<T>
 where
     T: Soars,
 {
-    /// The capacity of the initial allocation. This is an optimization to avoid
-    /// excessive reallocation for small array sizes.
-    const SMALL_CAPACITY: usize = 4;
-
     /// Constructs a new, empty `Soa<T>`.
     ///
     /// The container will not allocate until elements are pushed onto it.
@@ -53,11 +54,7 @@ where
     /// let mut soa = Soa::<Foo>::new();
     /// ```
     pub fn new() -> Self {
-        Self {
-            cap: if size_of::<T>() == 0 { usize::MAX } else { 0 },
-            slice: Slice::empty(),
-            len: 0,
-        }
+        Self::new_in(Global)
     }
 
     /// Construct a new, empty `Soa<T>` with at least the specified capacity.
@@ -101,24 +98,35 @@ where
     /// assert_eq!(soa.capacity(), usize::MAX);
     /// ```
     pub fn with_capacity(capacity: usize) -> Self {
-        match capacity {
-            0 => Self::new(),
-            capacity => {
-                if size_of::<T>() == 0 {
-                    Self {
-                        cap: usize::MAX,
-                        slice: Slice::empty(),
-                        len: 0,
-                    }
-                } else {
-                    Self {
-                        cap: capacity,
-                        slice: Slice::with_raw(unsafe { T::Raw::alloc(capacity) }),
-                        len: 0,
-                    }
-                }
-            }
-        }
+        Self::with_capacity_in(capacity, Global)
+    }
+
+    /// Tries to construct a new, empty `Soa<T>` with at least the specified
+    /// capacity.
+    ///
+    /// Behaves like [`Soa::with_capacity`], but returns an error instead of
+    /// panicking or aborting if the allocation fails.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use soa_rs::{Soa, Soars, TryReserveErrorKind};
+    /// #[derive(Soars)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// struct Foo(u8, u8);
+    ///
+    /// let soa = Soa::<Foo>::try_with_capacity(10).unwrap();
+    /// assert_eq!(soa.capacity(), 10);
+    ///
+    /// let err = Soa::<Foo>::try_with_capacity(usize::MAX).unwrap_err();
+    /// assert_eq!(err.kind(), TryReserveErrorKind::CapacityOverflow);
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_in(capacity, Global)
     }
 
     /// Constructs a new `Soa<T>` with the given first element.
@@ -145,24 +153,41 @@ where
         out
     }
 
-    /// Returns the total number of elements the container can hold without
-    /// reallocating.
+    /// Constructs a new `Soa<T>` from clones of the elements of `src` at the
+    /// given indices, in order.
+    ///
+    /// Indices may repeat. The clones are written directly into the columns,
+    /// field by field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out-of-bounds.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use soa_rs::{Soa, Soars};
-    /// # #[derive(Soars)]
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq, Clone)]
     /// # #[soa_derive(Debug, PartialEq)]
     /// # struct Foo(usize);
-    /// let mut soa = Soa::<Foo>::new();
-    /// for i in 0..42 {
-    ///     assert!(soa.capacity() >= i);
-    ///     soa.push(Foo(i));
-    /// }
+    /// let soa = soa![Foo(10), Foo(20), Foo(30)];
+    /// let gathered = Soa::gather(&soa, &[2, 0, 2]);
+    /// assert_eq!(gathered, [Foo(30), Foo(10), Foo(30)]);
     /// ```
-    pub fn capacity(&self) -> usize {
-        self.cap
+    pub fn gather(src: &Slice<T>, indices: &[usize]) -> Self
+    where
+        T: Clone,
+        T::Raw: SoaRawClone,
+    {
+        let mut out = Self::with_capacity(indices.len());
+        for &index in indices {
+            assert!(index < src.len(), "index out of bounds");
+            // SAFETY: The source element is initialized and the destination
+            // is within the capacity and past the length.
+            unsafe { src.raw().offset(index).clone_to(out.raw().offset(out.len)) };
+            out.len += 1;
+        }
+        out
     }
 
     /// Decomposes a `Soa<T>` into its raw components.
@@ -208,9 +233,109 @@ where
             cap: capacity,
             slice: Slice::with_raw(unsafe { T::Raw::from_parts(ptr, capacity) }),
             len: length,
+            alloc: Global,
+        }
+    }
+}
+
+impl<T, A> Soa<T, A>
+where
+    T: Soars,
+    A: Allocator,
+{
+    /// The capacity of the initial allocation. This is an optimization to avoid
+    /// excessive reallocation for small array sizes.
+    const SMALL_CAPACITY: usize = 4;
+
+    /// Constructs a new, empty `Soa<T, A>` that allocates from `alloc`.
+    ///
+    /// The container will not allocate until elements are pushed onto it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use soa_rs::{Global, Soa, Soars};
+    /// # #[derive(Soars, Copy, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(u8);
+    /// let mut soa = Soa::<Foo, _>::new_in(Global);
+    /// soa.push(Foo(1));
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            cap: if size_of::<T>() == 0 { usize::MAX } else { 0 },
+            slice: Slice::empty(),
+            len: 0,
+            alloc,
         }
     }
 
+    /// Constructs a new, empty `Soa<T, A>` with at least the specified
+    /// capacity that allocates from `alloc`.
+    ///
+    /// See [`Soa::with_capacity`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// # use soa_rs::{Global, Soa, Soars};
+    /// # #[derive(Soars, Copy, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(u8);
+    /// let soa = Soa::<Foo, _>::with_capacity_in(10, Global);
+    /// assert_eq!(soa.capacity(), 10);
+    /// ```
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        if capacity == 0 || size_of::<T>() == 0 {
+            Self::new_in(alloc)
+        } else {
+            Self {
+                cap: capacity,
+                slice: Slice::with_raw(unsafe { T::Raw::alloc(capacity, &alloc) }),
+                len: 0,
+                alloc,
+            }
+        }
+    }
+
+    /// Tries to construct a new, empty `Soa<T, A>` with at least the specified
+    /// capacity that allocates from `alloc`.
+    ///
+    /// See [`Soa::try_with_capacity`] for details.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        let mut out = Self::new_in(alloc);
+        out.try_reserve_exact(capacity)?;
+        Ok(out)
+    }
+
+    /// Returns a reference to the underlying allocator.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Returns the total number of elements the container can hold without
+    /// reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars};
+    /// # #[derive(Soars)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = Soa::<Foo>::new();
+    /// for i in 0..42 {
+    ///     assert!(soa.capacity() >= i);
+    ///     soa.push(Foo(i));
+    /// }
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
     /// Appends an element to the back of a collection.
     ///
     /// # Examples
@@ -232,6 +357,36 @@ where
         self.len += 1;
     }
 
+    /// Appends an element to the back of a collection, returning an error
+    /// instead of panicking or aborting if growing the allocation fails.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned that holds both the allocation error and `element`,
+    /// which can be recovered with [`TryPushError::into_element`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2)];
+    /// soa.try_push(Foo(3)).unwrap();
+    /// assert_eq!(soa, [Foo(1), Foo(2), Foo(3)]);
+    /// ```
+    pub fn try_push(&mut self, element: T) -> Result<(), TryPushError<T>> {
+        if self.len == self.cap {
+            if let Err(error) = self.try_reserve(1) {
+                return Err(TryPushError::new(element, error));
+            }
+        }
+        self.push(element);
+        Ok(())
+    }
+
     /// Removes the last element from a vector and returns it, or [`None`] if it
     /// is empty.
     ///
@@ -286,6 +441,38 @@ where
         self.len += 1;
     }
 
+    /// Inserts the elements of `iter` at position `index`, shifting all
+    /// elements after it to the right.
+    ///
+    /// This behaves like [`Soa::splice`] with an empty range. The tail is
+    /// moved once per column using the lower bound of `iter`'s size hint and
+    /// only moved again if `iter` yields more elements than that. If `iter`
+    /// panics, the elements it yielded so far remain at `index`, followed by
+    /// the tail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(4)];
+    /// soa.insert_many(1, [Foo(2), Foo(3)]);
+    /// assert_eq!(soa, [Foo(1), Foo(2), Foo(3), Foo(4)]);
+    /// ```
+    pub fn insert_many<I>(&mut self, index: usize, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        assert!(index <= self.len, "index out of bounds");
+        drop(self.splice(index..index, iter));
+    }
+
     /// Removes and returns the element at position index within the vector,
     /// shifting all elements after it to the left.
     ///
@@ -329,14 +516,50 @@ where
     /// assert!(soa.capacity() >= 11);
     /// ```
     pub fn reserve(&mut self, additional: usize) {
-        let new_len = self.len + additional;
+        if let Err(err) = self.try_reserve(additional) {
+            err.handle();
+        }
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the given `Soa<T>`. The collection may reserve more space
+    /// to speculatively avoid frequent reallocations. After calling
+    /// `try_reserve`, capacity will be greater than or equal to
+    /// `self.len() + additional` if it returns `Ok(())`. Does nothing if
+    /// capacity is already sufficient. This method preserves the contents
+    /// even if an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1)];
+    /// soa.try_reserve(10).unwrap();
+    /// assert!(soa.capacity() >= 11);
+    /// assert!(soa.try_reserve(usize::MAX).is_err());
+    /// assert_eq!(soa, [Foo(1)]);
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let new_len = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveErrorKind::CapacityOverflow)?;
         if new_len > self.cap {
             let new_cap = new_len
                 // Ensure exponential growth
-                .max(self.cap * 2)
+                .max(self.cap.saturating_mul(2))
                 .max(Self::SMALL_CAPACITY);
-            self.grow(new_cap);
+            self.try_grow(new_cap)?;
         }
+        Ok(())
     }
 
     /// Reserves the minimum capacity for at least additional more elements to
@@ -358,10 +581,45 @@ where
     /// assert!(soa.capacity() == 11);
     /// ```
     pub fn reserve_exact(&mut self, additional: usize) {
-        let new_len = additional + self.len;
+        if let Err(err) = self.try_reserve_exact(additional) {
+            err.handle();
+        }
+    }
+
+    /// Tries to reserve the minimum capacity for at least `additional` more
+    /// elements to be inserted in the given `Soa<T>`. Unlike
+    /// [`Soa::try_reserve`], this will not deliberately over-allocate to
+    /// speculatively avoid frequent allocations. After calling
+    /// `try_reserve_exact`, capacity will be equal to `self.len() +
+    /// additional` if it returns `Ok(())`, or else `usize::MAX` if `T` is
+    /// zero-sized. Does nothing if the capacity is already sufficient. This
+    /// method preserves the contents even if an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1)];
+    /// soa.try_reserve_exact(10).unwrap();
+    /// assert_eq!(soa.capacity(), 11);
+    /// ```
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let new_len = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveErrorKind::CapacityOverflow)?;
         if new_len > self.cap {
-            self.grow(new_len);
+            self.try_grow(new_len)?;
         }
+        Ok(())
     }
 
     /// Shrinks the capacity of the container as much as possible.
@@ -496,6 +754,202 @@ where
         out
     }
 
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` for which `f(e)` returns
+    /// `false`. This method operates in place, visiting each element exactly
+    /// once in the original order, and preserves the order of the retained
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4)];
+    /// soa.retain(|foo| foo.0 % 2 == 0);
+    /// assert_eq!(soa, [Foo(2), Foo(4)]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T::Ref<'_>) -> bool,
+    {
+        self.retain_raw(|raw| f(unsafe { raw.get_ref() }));
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable
+    /// reference to it.
+    ///
+    /// In other words, remove all elements `e` such that `f(e)` returns
+    /// `false`. This method operates in place, visiting each element exactly
+    /// once in the original order, and preserves the order of the retained
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4)];
+    /// soa.retain_mut(|foo| {
+    ///     *foo.0 += 1;
+    ///     *foo.0 <= 3
+    /// });
+    /// assert_eq!(soa, [Foo(2), Foo(3)]);
+    /// ```
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T::RefMut<'_>) -> bool,
+    {
+        self.retain_raw(|raw| f(unsafe { raw.get_mut() }));
+    }
+
+    /// Retains only the elements for which the corresponding entry of `mask`
+    /// is `true`.
+    ///
+    /// This method operates in place and preserves the order of the retained
+    /// elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mask` and the container have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4)];
+    /// let mask: Vec<_> = soa.f0().iter().map(|x| x % 2 == 0).collect();
+    /// soa.retain_mask(&mask);
+    /// assert_eq!(soa, [Foo(2), Foo(4)]);
+    /// ```
+    pub fn retain_mask(&mut self, mask: &[bool]) {
+        assert_eq!(
+            mask.len(),
+            self.len,
+            "mask and container have different lengths"
+        );
+        let mut mask = mask.iter();
+        self.retain_raw(|_| *mask.next().unwrap());
+    }
+
+    /// Implements [`Soa::retain`], [`Soa::retain_mut`] and [`Soa::retain_mask`].
+    ///
+    /// The retained elements are shifted back over the removed ones in a
+    /// single pass. If `f` or a destructor panics, the guard shifts the
+    /// unvisited elements into place so that the container stays valid.
+    fn retain_raw<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T::Raw) -> bool,
+    {
+        struct Guard<'a, T, A>
+        where
+            T: Soars,
+            A: Allocator,
+        {
+            soa: &'a mut Soa<T, A>,
+            processed: usize,
+            deleted: usize,
+            original_len: usize,
+        }
+
+        impl<T, A> Drop for Guard<'_, T, A>
+        where
+            T: Soars,
+            A: Allocator,
+        {
+            fn drop(&mut self) {
+                if self.deleted > 0 {
+                    unsafe {
+                        let src = self.soa.raw().offset(self.processed);
+                        let dst = self.soa.raw().offset(self.processed - self.deleted);
+                        src.copy_to(dst, self.original_len - self.processed);
+                    }
+                }
+                self.soa.len = self.original_len - self.deleted;
+            }
+        }
+
+        let original_len = self.len;
+        // Avoid double drops if the guard is leaked
+        self.len = 0;
+        let mut guard = Guard {
+            soa: self,
+            processed: 0,
+            deleted: 0,
+            original_len,
+        };
+
+        while guard.processed < guard.original_len {
+            let cur = unsafe { guard.soa.raw().offset(guard.processed) };
+            if f(cur) {
+                if guard.deleted > 0 {
+                    unsafe {
+                        let dst = guard.soa.raw().offset(guard.processed - guard.deleted);
+                        cur.copy_to(dst, 1);
+                    }
+                }
+                guard.processed += 1;
+            } else {
+                // Advance first in case the destructor panics
+                guard.processed += 1;
+                guard.deleted += 1;
+                drop(unsafe { cur.get() });
+            }
+        }
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element in
+    /// the range should be removed.
+    ///
+    /// If the closure returns `true`, the element is removed from the
+    /// container and yielded. If the closure returns `false`, the element
+    /// remains in the container and will not be yielded by the iterator. Only
+    /// elements that fall in the provided range are considered for extraction,
+    /// but any elements after the range will still have to be moved if any
+    /// element has been extracted.
+    ///
+    /// If the returned iterator is not exhausted, e.g. because it is dropped
+    /// without iterating or the iteration short-circuits, then the remaining
+    /// elements will be retained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the
+    /// end point is greater than the length of the container.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4), Foo(5), Foo(6)];
+    /// let evens: Soa<_> = soa.extract_if(1.., |foo| *foo.0 % 2 == 0).collect();
+    /// assert_eq!(evens, [Foo(2), Foo(4), Foo(6)]);
+    /// assert_eq!(soa, [Foo(1), Foo(3), Foo(5)]);
+    ///
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4)];
+    /// assert_eq!(soa.extract_if(..3, |foo| *foo.0 > 1).next(), Some(Foo(2)));
+    /// assert_eq!(soa, [Foo(1), Foo(3), Foo(4)]);
+    /// ```
+    pub fn extract_if<F, R>(&mut self, range: R, filter: F) -> ExtractIf<'_, T, F, A>
+    where
+        F: FnMut(T::RefMut<'_>) -> bool,
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = slice_range(range, self.len);
+        ExtractIf::new(self, start, end, filter)
+    }
+
     /// Moves all the elements of other into self, leaving other empty.
     ///
     /// # Examples
@@ -517,7 +971,260 @@ where
             let element = unsafe { other.raw().offset(i).get() };
             self.push(element);
         }
-        other.clear();
+        // The elements were moved out, so they must not be dropped again
+        other.len = 0;
+    }
+
+    /// Splits the collection into two at the given index.
+    ///
+    /// Returns a newly allocated container containing the elements in the
+    /// range `[at, len)`. After the call, the original container will be left
+    /// containing the elements `[0, at)` with its previous capacity unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// let soa2 = soa.split_off(1);
+    /// assert_eq!(soa, [Foo(1)]);
+    /// assert_eq!(soa2, [Foo(2), Foo(3)]);
+    /// ```
+    #[must_use = "use `.truncate()` if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> Self
+    where
+        A: Clone,
+    {
+        assert!(
+            at <= self.len,
+            "`at` split index (is {at}) should be <= len (is {})",
+            self.len
+        );
+        let count = self.len - at;
+        let mut other = Self::with_capacity_in(count, self.alloc.clone());
+        unsafe {
+            self.raw().offset(at).copy_to(other.raw(), count);
+        }
+        self.len = at;
+        other.len = count;
+        other
+    }
+
+    /// Resizes the container in place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the container is extended by the
+    /// difference, with each additional slot filled with `value`. If `new_len`
+    /// is less than `len`, the container is simply truncated.
+    ///
+    /// If you need more flexibility, use [`Soa::resize_with`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1)];
+    /// soa.resize(3, Foo(2));
+    /// assert_eq!(soa, [Foo(1), Foo(2), Foo(2)]);
+    ///
+    /// soa.resize(2, Foo(3));
+    /// assert_eq!(soa, [Foo(1), Foo(2)]);
+    /// ```
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        if new_len > self.len {
+            self.reserve(new_len - self.len);
+            while self.len + 1 < new_len {
+                self.push(value.clone());
+            }
+            self.push(value);
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    /// Resizes the container in place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the container is extended by the
+    /// difference, with each additional slot filled with the result of calling
+    /// the closure `f`. The return values from `f` will end up in the container
+    /// in the order they have been generated. If `new_len` is less than `len`,
+    /// the container is simply truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1)];
+    /// let mut i = 1;
+    /// soa.resize_with(4, || {
+    ///     i *= 2;
+    ///     Foo(i)
+    /// });
+    /// assert_eq!(soa, [Foo(1), Foo(2), Foo(4), Foo(8)]);
+    /// ```
+    pub fn resize_with<F>(&mut self, new_len: usize, mut f: F)
+    where
+        F: FnMut() -> T,
+    {
+        if new_len > self.len {
+            self.reserve(new_len - self.len);
+            while self.len < new_len {
+                self.push(f());
+            }
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    /// Clones and appends all elements in a slice to the container.
+    ///
+    /// Iterates over `other`, clones each element, and then appends it to this
+    /// container. The clones are written directly into the columns, field by
+    /// field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq, Clone)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1)];
+    /// let other = soa![Foo(2), Foo(3)];
+    /// soa.extend_from_slice(&other);
+    /// assert_eq!(soa, [Foo(1), Foo(2), Foo(3)]);
+    /// ```
+    pub fn extend_from_slice(&mut self, other: &Slice<T>)
+    where
+        T: Clone,
+        T::Raw: SoaRawClone,
+    {
+        self.reserve(other.len());
+        for i in 0..other.len() {
+            unsafe {
+                other.raw().offset(i).clone_to(self.raw().offset(self.len));
+            }
+            self.len += 1;
+        }
+    }
+
+    /// Removes the specified range from the container in bulk, returning all
+    /// removed elements as an iterator. If the iterator is dropped before being
+    /// fully consumed, it drops the remaining removed elements.
+    ///
+    /// The returned iterator keeps a mutable borrow on the container to
+    /// optimize its implementation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the
+    /// end point is greater than the length of the container.
+    ///
+    /// # Leaking
+    ///
+    /// If the returned iterator goes out of scope without being dropped (due to
+    /// [`mem::forget`], for example), the container may have lost and leaked
+    /// elements arbitrarily, including elements outside the range.
+    ///
+    /// [`mem::forget`]: std::mem::forget
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3)];
+    /// let drained: Soa<_> = soa.drain(1..).collect();
+    /// assert_eq!(soa, [Foo(1)]);
+    /// assert_eq!(drained, [Foo(2), Foo(3)]);
+    ///
+    /// // A full range clears the container, like `clear()` does
+    /// soa.drain(..);
+    /// assert_eq!(soa, []);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = slice_range(range, self.len);
+        let len = self.len;
+        let iter_raw = IterRaw {
+            slice: Slice::with_raw(unsafe { self.raw().offset(start) }),
+            len: end - start,
+            adapter: PhantomData,
+        };
+        // Elements from start on are owned by the Drain until it is dropped,
+        // so only the head is kept in case the Drain is leaked.
+        self.len = start;
+        Drain {
+            iter_raw,
+            soa: self,
+            tail_start: end,
+            tail_len: len - end,
+        }
+    }
+
+    /// Creates a splicing iterator that replaces the specified range in the
+    /// container with the given `replace_with` iterator and yields the removed
+    /// items. `replace_with` does not need to be the same length as `range`.
+    ///
+    /// `range` is removed even if the iterator is not consumed until the end.
+    /// It is unspecified how many elements are removed from the container if
+    /// the [`Splice`] value is leaked.
+    ///
+    /// The input iterator `replace_with` is only consumed when the [`Splice`]
+    /// value is dropped.
+    ///
+    /// This is optimal if the tail (elements in the container after `range`)
+    /// is empty or if `replace_with` yields no more elements than `range`'s
+    /// length. Otherwise, the tail is moved once using the lower bound of
+    /// `replace_with`'s size hint. If more elements remain, the tail is moved
+    /// again with a gap that doubles each time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the
+    /// end point is greater than the length of the container.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{Soa, Soars, soa};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut soa = soa![Foo(1), Foo(2), Foo(3), Foo(4)];
+    /// let new = [Foo(7), Foo(8), Foo(9)];
+    /// let removed: Soa<_> = soa.splice(1..3, new).collect();
+    /// assert_eq!(soa, [Foo(1), Foo(7), Foo(8), Foo(9), Foo(4)]);
+    /// assert_eq!(removed, [Foo(2), Foo(3)]);
+    /// ```
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, A>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        Splice {
+            drain: self.drain(range),
+            replace_with: replace_with.into_iter(),
+        }
     }
 
     /// Clears the vector, removing all values.
@@ -599,14 +1306,16 @@ where
         if new_cap == 0 {
             debug_assert!(self.cap > 0);
             unsafe {
-                self.raw().dealloc(self.cap);
+                self.raw().dealloc(self.cap, &self.alloc);
             }
             self.raw = T::Raw::dangling();
         } else {
             debug_assert!(new_cap < self.cap);
             debug_assert!(self.len <= new_cap);
             unsafe {
-                self.raw = self.raw().realloc_shrink(self.cap, new_cap, self.len);
+                self.raw = self
+                    .raw()
+                    .realloc_shrink(self.cap, new_cap, self.len, &self.alloc);
             }
         }
 
@@ -615,26 +1324,38 @@ where
 
     /// Grows the allocated capacity.
     fn grow(&mut self, new_cap: usize) {
+        if let Err(err) = self.try_grow(new_cap) {
+            err.handle();
+        }
+    }
+
+    /// Grows the allocated capacity, leaving the container unchanged if
+    /// allocation fails.
+    fn try_grow(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
         debug_assert!(size_of::<T>() > 0);
         debug_assert!(new_cap > self.cap);
 
         if self.cap == 0 {
             debug_assert!(new_cap > 0);
-            self.raw = unsafe { T::Raw::alloc(new_cap) };
+            self.raw = unsafe { T::Raw::try_alloc(new_cap, &self.alloc)? };
         } else {
             debug_assert!(self.len <= self.cap);
             unsafe {
-                self.raw = self.raw().realloc_grow(self.cap, new_cap, self.len);
+                self.raw = self
+                    .raw()
+                    .try_realloc_grow(self.cap, new_cap, self.len, &self.alloc)?;
             }
         }
 
         self.cap = new_cap;
+        Ok(())
     }
 }
 
-impl<T> Drop for Soa<T>
+impl<T, A> Drop for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn drop(&mut self) {
         if needs_drop::<T>() {
@@ -643,19 +1364,35 @@ where
 
         if size_of::<T>() > 0 && self.cap > 0 {
             unsafe {
-                self.raw().dealloc(self.cap);
+                self.raw().dealloc(self.cap, &self.alloc);
             }
         }
     }
 }
 
-impl<T> IntoIterator for Soa<T>
+// SAFETY: Soa owns its elements, so it is Send and Sync under the same
+// conditions as Vec.
+unsafe impl<T, A> Send for Soa<T, A>
+where
+    T: Send + Soars,
+    A: Send + Allocator,
+{
+}
+unsafe impl<T, A> Sync for Soa<T, A>
+where
+    T: Sync + Soars,
+    A: Sync + Allocator,
+{
+}
+
+impl<T, A> IntoIterator for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     type Item = T;
 
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
 
     fn into_iter(self) -> Self::IntoIter {
         let soa = ManuallyDrop::new(self);
@@ -667,13 +1404,17 @@ where
             },
             ptr: soa.raw().into_parts(),
             cap: soa.cap,
+            // SAFETY: soa is never dropped, so the allocator is moved out
+            // exactly once.
+            alloc: unsafe { core::ptr::read(&soa.alloc) },
         }
     }
 }
 
-impl<'a, T> IntoIterator for &'a Soa<T>
+impl<'a, T, A> IntoIterator for &'a Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     type Item = T::Ref<'a>;
 
@@ -684,9 +1425,10 @@ where
     }
 }
 
-impl<'a, T> IntoIterator for &'a mut Soa<T>
+impl<'a, T, A> IntoIterator for &'a mut Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     type Item = T::RefMut<'a>;
 
@@ -697,18 +1439,23 @@ where
     }
 }
 
-// NOTE: Copy is the required bound because calling Clone::clone on a
-// stack-allocated element is unsound in the presence of interior mutability
-// unless the fields are written back, which we also can't do because of &self.
-impl<T> Clone for Soa<T>
+// NOTE: Elements are cloned field by field rather than with Clone::clone on a
+// stack-allocated copy of the element, which would be unsound in the presence
+// of interior mutability unless the fields are written back, which we also
+// can't do because of &self.
+impl<T, A> Clone for Soa<T, A>
 where
-    T: Soars + Copy,
+    T: Soars + Clone,
+    A: Allocator + Clone,
+    T::Raw: SoaRawClone,
 {
     fn clone(&self) -> Self {
-        let mut out = Self::with_capacity(self.len);
+        let mut out = Self::with_capacity_in(self.len, self.alloc.clone());
         for i in 0..self.len {
-            let el = unsafe { self.raw.offset(i).get() };
-            out.push(el);
+            // SAFETY: The source element is initialized and the destination
+            // is within the capacity and past the length.
+            unsafe { self.raw.offset(i).clone_to(out.raw.offset(i)) };
+            out.len += 1;
         }
         out
     }
@@ -717,15 +1464,18 @@ where
         self.clear();
         self.reserve_exact(source.len);
         for i in 0..source.len {
-            let el = unsafe { source.raw.offset(i).get() };
-            self.push(el);
+            // SAFETY: The source element is initialized and the destination
+            // is within the capacity and past the length.
+            unsafe { source.raw.offset(i).clone_to(self.raw.offset(i)) };
+            self.len += 1;
         }
     }
 }
 
-impl<T> Extend<T> for Soa<T>
+impl<T, A> Extend<T> for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         for item in iter {
@@ -800,9 +1550,10 @@ where
     }
 }
 
-impl<T> Debug for Soa<T>
+impl<T, A> Debug for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
     for<'a> T::Ref<'a>: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -810,9 +1561,10 @@ where
     }
 }
 
-impl<T> PartialOrd for Soa<T>
+impl<T, A> PartialOrd for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
     for<'a> T::Ref<'a>: PartialOrd,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -820,9 +1572,10 @@ where
     }
 }
 
-impl<T> Ord for Soa<T>
+impl<T, A> Ord for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
     for<'a> T::Ref<'a>: Ord,
 {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -830,9 +1583,10 @@ where
     }
 }
 
-impl<T> Hash for Soa<T>
+impl<T, A> Hash for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
     for<'a> T::Ref<'a>: Hash,
 {
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -849,45 +1603,50 @@ where
     }
 }
 
-impl<T> AsRef<Slice<T>> for Soa<T>
+impl<T, A> AsRef<Slice<T>> for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn as_ref(&self) -> &Slice<T> {
         unsafe { self.slice.as_unsized(self.len) }
     }
 }
 
-impl<T> AsMut<Slice<T>> for Soa<T>
+impl<T, A> AsMut<Slice<T>> for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn as_mut(&mut self) -> &mut Slice<T> {
         unsafe { self.slice.as_unsized_mut(self.len) }
     }
 }
 
-impl<T> AsRef<Self> for Soa<T>
+impl<T, A> AsRef<Self> for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn as_ref(&self) -> &Self {
         self
     }
 }
 
-impl<T> AsMut<Self> for Soa<T>
+impl<T, A> AsMut<Self> for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn as_mut(&mut self) -> &mut Self {
         self
     }
 }
 
-impl<T> Deref for Soa<T>
+impl<T, A> Deref for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     type Target = Slice<T>;
 
@@ -896,31 +1655,34 @@ where
     }
 }
 
-impl<T> DerefMut for Soa<T>
+impl<T, A> DerefMut for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.as_mut()
     }
 }
 
-impl<T> Borrow<Slice<T>> for Soa<T>
+impl<T, A> Borrow<Slice<T>> for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn borrow(&self) -> &Slice<T> {
         self.as_ref()
     }
 }
 
-impl<T> BorrowMut<Slice<T>> for Soa<T>
+impl<T, A> BorrowMut<Slice<T>> for Soa<T, A>
 where
     T: Soars,
+    A: Allocator,
 {
     fn borrow_mut(&mut self) -> &mut Slice<T> {
         self.as_mut()
     }
 }
 
-eq_impl::impl_for!(Soa<T>);
+eq_impl::impl_for!(Soa<T, A>, A: Allocator);
@@ -0,0 +1,700 @@
+use crate::{Global, Iter, IterMut, Slice, SliceMut, SliceRef, Soa, SoaRaw, SoaRawClone, Soars};
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::Chain,
+    mem::{needs_drop, size_of, ManuallyDrop},
+};
+
+/// A double-ended queue implemented with a growable ring buffer that stores
+/// the values for each field of `T` contiguously.
+///
+/// What [`Soa`] is to `Vec<T>`, `SoaDeque` is to `VecDeque<T>`. Because the
+/// elements may wrap around the end of the buffer, the contents are exposed as
+/// a pair of slices. Use [`SoaDeque::make_contiguous`] to get a single slice.
+///
+/// For the same reason, `SoaDeque` does not dereference to [`Slice`], so the
+/// field getters generated by [`Soars`], such as `f0`, are not available on
+/// the deque itself. Call them on either half from [`SoaDeque::as_slices`],
+/// or use [`SoaDeque::slices`] to get the field slices of both halves.
+///
+/// # Examples
+///
+/// ```
+/// # use soa_rs::{SoaDeque, Soars};
+/// # #[derive(Soars, Debug, PartialEq)]
+/// # #[soa_derive(Debug, PartialEq)]
+/// # struct Foo(usize);
+/// let mut deque = SoaDeque::new();
+/// deque.push_back(Foo(2));
+/// deque.push_back(Foo(3));
+/// deque.push_front(Foo(1));
+/// let (front, back) = deque.as_slices();
+/// assert_eq!(front.f0().len() + back.f0().len(), 3);
+/// assert_eq!(deque.pop_front(), Some(Foo(1)));
+/// assert_eq!(deque.make_contiguous().f0(), [2, 3]);
+/// ```
+pub struct SoaDeque<T>
+where
+    T: Soars,
+{
+    raw: T::Raw,
+    cap: usize,
+    head: usize,
+    len: usize,
+}
+
+impl<T> SoaDeque<T>
+where
+    T: Soars,
+{
+    /// The capacity of the initial allocation, matching [`Soa`].
+    const SMALL_CAPACITY: usize = 4;
+
+    /// Constructs a new, empty `SoaDeque<T>`.
+    ///
+    /// The container will not allocate until elements are pushed onto it.
+    pub fn new() -> Self {
+        Self {
+            raw: T::Raw::dangling(),
+            cap: if size_of::<T>() == 0 { usize::MAX } else { 0 },
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Constructs a new, empty `SoaDeque<T>` with at least the specified
+    /// capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let deque = SoaDeque::<Foo>::with_capacity(10);
+    /// assert!(deque.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut out = Self::new();
+        out.reserve_exact(capacity);
+        out
+    }
+
+    /// Returns the total number of elements the container can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the number of elements in the container.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the container contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends an element to the back of the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut deque = SoaDeque::new();
+    /// deque.push_back(Foo(1));
+    /// deque.push_back(Foo(2));
+    /// assert_eq!(deque.back(), Some(FooRef(&2)));
+    /// ```
+    pub fn push_back(&mut self, element: T) {
+        self.maybe_grow();
+        unsafe {
+            self.physical(self.len).set(element);
+        }
+        self.len += 1;
+    }
+
+    /// Prepends an element to the front of the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut deque = SoaDeque::new();
+    /// deque.push_front(Foo(1));
+    /// deque.push_front(Foo(2));
+    /// assert_eq!(deque.front(), Some(FooRef(&2)));
+    /// ```
+    pub fn push_front(&mut self, element: T) {
+        self.maybe_grow();
+        self.head = self.wrap_sub(self.head, 1);
+        self.len += 1;
+        unsafe {
+            self.physical(0).set(element);
+        }
+    }
+
+    /// Removes the last element and returns it, or [`None`] if the deque is
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut deque: SoaDeque<_> = [Foo(1), Foo(2)].into_iter().collect();
+    /// assert_eq!(deque.pop_back(), Some(Foo(2)));
+    /// assert_eq!(deque.pop_back(), Some(Foo(1)));
+    /// assert_eq!(deque.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            Some(unsafe { self.physical(self.len).get() })
+        }
+    }
+
+    /// Removes the first element and returns it, or [`None`] if the deque is
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut deque: SoaDeque<_> = [Foo(1), Foo(2)].into_iter().collect();
+    /// assert_eq!(deque.pop_front(), Some(Foo(1)));
+    /// assert_eq!(deque.pop_front(), Some(Foo(2)));
+    /// assert_eq!(deque.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            let out = unsafe { self.physical(0).get() };
+            self.head = self.wrap_add(self.head, 1);
+            self.len -= 1;
+            Some(out)
+        }
+    }
+
+    /// Returns a reference to the element at `index`, where the front of the
+    /// deque is at index 0, or [`None`] if out of bounds.
+    pub fn get(&self, index: usize) -> Option<T::Ref<'_>> {
+        if index < self.len {
+            Some(unsafe { self.physical(index).get_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, where the front
+    /// of the deque is at index 0, or [`None`] if out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<T::RefMut<'_>> {
+        if index < self.len {
+            Some(unsafe { self.physical(index).get_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the front element, or [`None`] if the deque is
+    /// empty.
+    pub fn front(&self) -> Option<T::Ref<'_>> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the front element, or [`None`] if the
+    /// deque is empty.
+    pub fn front_mut(&mut self) -> Option<T::RefMut<'_>> {
+        self.get_mut(0)
+    }
+
+    /// Returns a reference to the back element, or [`None`] if the deque is
+    /// empty.
+    pub fn back(&self) -> Option<T::Ref<'_>> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    /// Returns a mutable reference to the back element, or [`None`] if the
+    /// deque is empty.
+    pub fn back_mut(&mut self) -> Option<T::RefMut<'_>> {
+        self.get_mut(self.len.wrapping_sub(1))
+    }
+
+    /// Swaps the elements at indices `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        assert!(a < self.len && b < self.len, "index out of bounds");
+        unsafe {
+            let a = self.physical(a);
+            let b = self.physical(b);
+            let tmp = a.get();
+            b.copy_to(a, 1);
+            b.set(tmp);
+        }
+    }
+
+    /// Shortens the deque, keeping the first `len` elements and dropping the
+    /// rest.
+    pub fn truncate(&mut self, len: usize) {
+        while len < self.len {
+            self.pop_back();
+        }
+    }
+
+    /// Clears the deque, removing all values.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = 0;
+    }
+
+    /// Returns a front-to-back iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut deque = SoaDeque::new();
+    /// deque.push_back(Foo(2));
+    /// deque.push_front(Foo(1));
+    /// let sum: usize = deque.iter().map(|foo| foo.0).sum();
+    /// assert_eq!(sum, 3);
+    /// ```
+    pub fn iter(&self) -> Chain<Iter<'_, T>, Iter<'_, T>> {
+        let (a, b) = self.as_slices();
+        a.into_iter().chain(b)
+    }
+
+    /// Returns a front-to-back iterator that returns mutable references.
+    pub fn iter_mut(&mut self) -> Chain<IterMut<'_, T>, IterMut<'_, T>> {
+        let (a, b) = self.as_mut_slices();
+        a.into_iter().chain(b)
+    }
+
+    /// Returns a pair of slices which contain, in order, the contents of the
+    /// deque.
+    ///
+    /// If [`SoaDeque::make_contiguous`] was previously called, all elements of
+    /// the deque will be in the first slice and the second slice will be
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut deque = SoaDeque::new();
+    /// deque.push_back(Foo(1));
+    /// deque.push_back(Foo(2));
+    /// deque.push_front(Foo(0));
+    /// let (a, b) = deque.as_slices();
+    /// assert_eq!(a.len() + b.len(), 3);
+    /// ```
+    pub fn as_slices(&self) -> (SliceRef<'_, T>, SliceRef<'_, T>) {
+        let ((a, a_len), (b, b_len)) = self.slice_ranges();
+        unsafe {
+            (
+                SliceRef::from_slice(Slice::with_raw(a), a_len),
+                SliceRef::from_slice(Slice::with_raw(b), b_len),
+            )
+        }
+    }
+
+    /// Returns a pair of mutable slices which contain, in order, the contents
+    /// of the deque.
+    pub fn as_mut_slices(&mut self) -> (SliceMut<'_, T>, SliceMut<'_, T>) {
+        let ((a, a_len), (b, b_len)) = self.slice_ranges();
+        unsafe {
+            (
+                SliceMut::from_slice(Slice::with_raw(a), a_len),
+                SliceMut::from_slice(Slice::with_raw(b), b_len),
+            )
+        }
+    }
+
+    /// Returns the field slices of each half of the deque, as
+    /// [`SoaDeque::as_slices`] would split it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Sample {
+    /// #     time: u32,
+    /// #     value: f32,
+    /// # }
+    /// let mut deque = SoaDeque::new();
+    /// deque.push_back(Sample { time: 1, value: 0.5 });
+    /// deque.push_back(Sample { time: 2, value: 1.5 });
+    /// let (front, back) = deque.slices();
+    /// assert_eq!(front.time.len() + back.time.len(), 2);
+    /// ```
+    pub fn slices(&self) -> (T::Slices<'_>, T::Slices<'_>) {
+        let ((a, a_len), (b, b_len)) = self.slice_ranges();
+        unsafe { (a.slices(a_len), b.slices(b_len)) }
+    }
+
+    /// Returns the mutable field slices of each half of the deque, as
+    /// [`SoaDeque::as_mut_slices`] would split it.
+    pub fn slices_mut(&mut self) -> (T::SlicesMut<'_>, T::SlicesMut<'_>) {
+        let ((a, a_len), (b, b_len)) = self.slice_ranges();
+        unsafe { (a.slices_mut(a_len), b.slices_mut(b_len)) }
+    }
+
+    /// Rearranges the internal storage of this deque so it is one contiguous
+    /// slice, which is then returned.
+    ///
+    /// This does not allocate or change the order of the elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaDeque, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut deque = SoaDeque::new();
+    /// for i in 0..3 {
+    ///     deque.push_back(Foo(i + 3));
+    ///     deque.push_front(Foo(2 - i));
+    /// }
+    /// assert_eq!(deque.make_contiguous().f0(), [0, 1, 2, 3, 4, 5]);
+    /// assert_eq!(deque.as_slices().1.len(), 0);
+    /// ```
+    pub fn make_contiguous(&mut self) -> SliceMut<'_, T> {
+        if size_of::<T>() == 0 {
+            self.head = 0;
+        } else if self.head + self.len > self.cap {
+            // The elements wrap around: [back (b) | free | front (a)]
+            let a = self.cap - self.head;
+            let b = self.len - a;
+            let free = self.cap - self.len;
+            unsafe {
+                if a <= free {
+                    // Shift the back right and move the front before it
+                    self.raw.copy_to(self.raw.offset(a), b);
+                    self.raw.offset(self.head).copy_to(self.raw, a);
+                    self.head = 0;
+                } else {
+                    // Close the gap, then rotate the occupied region so that
+                    // the front comes first
+                    self.raw.copy_to(self.raw.offset(free), b);
+                    self.head = free;
+                    self.rotate_left_raw(free, self.len, b);
+                }
+            }
+        }
+        let (a, _) = self.as_mut_slices();
+        a
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let new_len = self.len.checked_add(additional).expect("capacity overflow");
+        if new_len > self.cap {
+            let new_cap = new_len
+                // Ensure exponential growth
+                .max(self.cap.saturating_mul(2))
+                .max(Self::SMALL_CAPACITY);
+            self.grow(new_cap);
+        }
+    }
+
+    /// Reserves the minimum capacity for at least `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let new_len = self.len.checked_add(additional).expect("capacity overflow");
+        if new_len > self.cap {
+            self.grow(new_len);
+        }
+    }
+
+    /// Returns the raw pointers for the element at logical `index`.
+    unsafe fn physical(&self, index: usize) -> T::Raw {
+        self.raw.offset(self.wrap_add(self.head, index))
+    }
+
+    /// Returns the start and length of the two contiguous regions that make up
+    /// the deque.
+    fn slice_ranges(&self) -> ((T::Raw, usize), (T::Raw, usize)) {
+        let a_len = self.len.min(self.cap - self.head);
+        unsafe {
+            (
+                (self.raw.offset(self.head), a_len),
+                (self.raw, self.len - a_len),
+            )
+        }
+    }
+
+    fn wrap_add(&self, index: usize, addend: usize) -> usize {
+        let logical = index.wrapping_add(addend);
+        if logical >= self.cap {
+            logical.wrapping_sub(self.cap)
+        } else {
+            logical
+        }
+    }
+
+    fn wrap_sub(&self, index: usize, subtrahend: usize) -> usize {
+        self.wrap_add(index, self.cap.wrapping_sub(subtrahend))
+    }
+
+    /// Rotates the `len` initialized elements starting at physical index
+    /// `start` to the left by `mid` places.
+    unsafe fn rotate_left_raw(&self, start: usize, len: usize, mid: usize) {
+        let reverse = |lo: usize, hi: usize| {
+            let (mut lo, mut hi) = (lo, hi);
+            while lo + 1 < hi {
+                hi -= 1;
+                let a = self.raw.offset(lo);
+                let b = self.raw.offset(hi);
+                let tmp = a.get();
+                b.copy_to(a, 1);
+                b.set(tmp);
+                lo += 1;
+            }
+        };
+        reverse(start, start + mid);
+        reverse(start + mid, start + len);
+        reverse(start, start + len);
+    }
+
+    /// Grows the allocated capacity if `len == cap`.
+    fn maybe_grow(&mut self) {
+        if self.len == self.cap {
+            self.reserve(1);
+        }
+    }
+
+    /// Grows the allocated capacity and unwraps the elements if needed so
+    /// that they stay in order.
+    fn grow(&mut self, new_cap: usize) {
+        debug_assert!(size_of::<T>() > 0);
+        let old_cap = self.cap;
+        if old_cap == 0 {
+            self.raw = unsafe { T::Raw::alloc(new_cap, &Global) };
+            self.cap = new_cap;
+            return;
+        }
+
+        // Move every slot since the elements may wrap around
+        unsafe {
+            self.raw = self.raw.realloc_grow(old_cap, new_cap, old_cap, &Global);
+        }
+        self.cap = new_cap;
+
+        if self.head + self.len > old_cap {
+            let a = old_cap - self.head;
+            let b = self.len - a;
+            unsafe {
+                if b <= new_cap - old_cap {
+                    // Move the back after the old end
+                    self.raw.copy_to(self.raw.offset(old_cap), b);
+                } else {
+                    // Move the front to the new end
+                    let new_head = new_cap - a;
+                    self.raw
+                        .offset(self.head)
+                        .copy_to(self.raw.offset(new_head), a);
+                    self.head = new_head;
+                }
+            }
+        }
+    }
+}
+
+impl<T> Drop for SoaDeque<T>
+where
+    T: Soars,
+{
+    fn drop(&mut self) {
+        if needs_drop::<T>() {
+            while self.pop_back().is_some() {}
+        }
+
+        if size_of::<T>() > 0 && self.cap > 0 {
+            unsafe {
+                self.raw.dealloc(self.cap, &Global);
+            }
+        }
+    }
+}
+
+// SAFETY: SoaDeque owns its elements, so it is Send and Sync under the same
+// conditions as VecDeque.
+unsafe impl<T> Send for SoaDeque<T> where T: Send + Soars {}
+unsafe impl<T> Sync for SoaDeque<T> where T: Sync + Soars {}
+
+impl<'a, T> IntoIterator for &'a SoaDeque<T>
+where
+    T: Soars,
+{
+    type Item = T::Ref<'a>;
+
+    type IntoIter = Chain<Iter<'a, T>, Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SoaDeque<T>
+where
+    T: Soars,
+{
+    type Item = T::RefMut<'a>;
+
+    type IntoIter = Chain<IterMut<'a, T>, IterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// NOTE: Elements are cloned field by field for the same reasons as in the
+// Clone implementation for Soa.
+impl<T> Clone for SoaDeque<T>
+where
+    T: Soars + Clone,
+    T::Raw: SoaRawClone,
+{
+    fn clone(&self) -> Self {
+        let mut out = Self::with_capacity(self.len);
+        for i in 0..self.len {
+            // SAFETY: The source element is initialized and the destination
+            // is within the capacity and past the length.
+            unsafe { self.physical(i).clone_to(out.raw.offset(i)) };
+            out.len += 1;
+        }
+        out
+    }
+}
+
+impl<T> Extend<T> for SoaDeque<T>
+where
+    T: Soars,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for SoaDeque<T>
+where
+    T: Soars,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<T> From<Soa<T>> for SoaDeque<T>
+where
+    T: Soars,
+{
+    /// Turns a [`Soa<T>`] into a [`SoaDeque<T>`] without reallocating.
+    fn from(value: Soa<T>) -> Self {
+        let soa = ManuallyDrop::new(value);
+        Self {
+            raw: soa.raw(),
+            cap: soa.cap,
+            head: 0,
+            len: soa.len,
+        }
+    }
+}
+
+impl<T> From<SoaDeque<T>> for Soa<T>
+where
+    T: Soars,
+{
+    /// Turns a [`SoaDeque<T>`] into a [`Soa<T>`] without reallocating,
+    /// moving the elements to the start of the buffer if needed.
+    fn from(mut value: SoaDeque<T>) -> Self {
+        value.make_contiguous();
+        if value.head > 0 && size_of::<T>() > 0 {
+            unsafe {
+                value.raw.offset(value.head).copy_to(value.raw, value.len);
+            }
+        }
+        let deque = ManuallyDrop::new(value);
+        Soa {
+            cap: deque.cap,
+            slice: Slice::with_raw(deque.raw),
+            len: deque.len,
+            alloc: Global,
+        }
+    }
+}
+
+impl<T> Debug for SoaDeque<T>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for SoaDeque<T>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for SoaDeque<T>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Eq,
+{
+}
+
+impl<T> Default for SoaDeque<T>
+where
+    T: Soars,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,4 +1,5 @@
-use crate::Soars;
+use crate::{Allocator, Soars, TryReserveError};
+use core::alloc::{Layout, LayoutError};
 
 /// A low-level utility providing fundamental operations needed by [`Soa`].
 ///
@@ -34,6 +35,12 @@ pub unsafe trait SoaRaw: Copy + Clone {
     /// allocating memory.
     fn dangling() -> Self;
 
+    /// Returns the layout of an allocation with room for `capacity` elements.
+    ///
+    /// This is used to place the columns of a nested [`Soars`] type within the
+    /// allocation of the containing type.
+    fn layout(capacity: usize) -> Result<Layout, LayoutError>;
+
     /// Construct a new [`SoaRaw`] with the given pointer and capacity.
     ///
     /// # Safety
@@ -52,7 +59,16 @@ pub unsafe trait SoaRaw: Copy + Clone {
     /// [`from_parts`]: SoaRaw::from_parts
     fn into_parts(self) -> *mut u8;
 
-    /// Allocates room for `capacity` elements.
+    /// Constructs a [`SoaRaw`] that points into the columns of a SoA array
+    /// rather than into an allocation.
+    ///
+    /// # Safety
+    ///
+    /// `array` must be non-null and properly aligned. The array need not be
+    /// initialized.
+    unsafe fn from_array<const N: usize>(array: *mut <Self::Item as Soars>::Array<N>) -> Self;
+
+    /// Allocates room for `capacity` elements using `allocator`.
     ///
     /// # Safety
     ///
@@ -62,7 +78,23 @@ pub unsafe trait SoaRaw: Copy + Clone {
     /// - `capacity > 0`
     /// - `PREV_CAP == 0` (Otherwise use [`SoaRaw::realloc_grow`])
     #[must_use]
-    unsafe fn alloc(capacity: usize) -> Self;
+    unsafe fn alloc<A: Allocator>(capacity: usize, allocator: &A) -> Self {
+        match Self::try_alloc(capacity, allocator) {
+            Ok(raw) => raw,
+            Err(err) => err.handle(),
+        }
+    }
+
+    /// Allocates room for `capacity` elements, returning an error if the
+    /// capacity overflows or the allocator fails.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as [`SoaRaw::alloc`] apply.
+    unsafe fn try_alloc<A: Allocator>(
+        capacity: usize,
+        allocator: &A,
+    ) -> Result<Self, TryReserveError>;
 
     /// Grows the allocation with room for `old_capacity` elements to fit
     /// `new_capacity` elements and moves `length` number of array elements to
@@ -76,13 +108,35 @@ pub unsafe trait SoaRaw: Copy + Clone {
     /// - `new_capacity > old_capacity`
     /// - `length <= old_capacity`
     /// - `old_capacity > 0` (Otherwise use [`SoaRaw::alloc`])
+    /// - `allocator` is the allocator used for the previous allocation
     #[must_use]
-    unsafe fn realloc_grow(
+    unsafe fn realloc_grow<A: Allocator>(
         &mut self,
         old_capacity: usize,
         new_capacity: usize,
         length: usize,
-    ) -> Self;
+        allocator: &A,
+    ) -> Self {
+        match self.try_realloc_grow(old_capacity, new_capacity, length, allocator) {
+            Ok(raw) => raw,
+            Err(err) => err.handle(),
+        }
+    }
+
+    /// Grows the allocation like [`SoaRaw::realloc_grow`], returning an error
+    /// if the capacity overflows or the allocator fails. On error, the
+    /// original allocation and its contents are left unchanged.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as [`SoaRaw::realloc_grow`] apply.
+    unsafe fn try_realloc_grow<A: Allocator>(
+        &mut self,
+        old_capacity: usize,
+        new_capacity: usize,
+        length: usize,
+        allocator: &A,
+    ) -> Result<Self, TryReserveError>;
 
     /// Shrinks the allocation with room for `old_capacity` elements to fit
     /// `new_capacity` elements and moves `length` number of array elements to
@@ -96,12 +150,14 @@ pub unsafe trait SoaRaw: Copy + Clone {
     /// - `new_capacity < old_capacity`
     /// - `length <= new_capacity`
     /// - `old_capacity > 0` (Otherwise use [`SoaRaw::dealloc`])
+    /// - `allocator` is the allocator used for the previous allocation
     #[must_use]
-    unsafe fn realloc_shrink(
+    unsafe fn realloc_shrink<A: Allocator>(
         &mut self,
         old_capacity: usize,
         new_capacity: usize,
         length: usize,
+        allocator: &A,
     ) -> Self;
 
     /// Deallocates the allocation with room for `capacity` elements. The state
@@ -113,7 +169,8 @@ pub unsafe trait SoaRaw: Copy + Clone {
     ///
     /// - `size_of::<T>() > 0`
     /// - `old_capacity > 0`
-    unsafe fn dealloc(self, old_capacity: usize);
+    /// - `allocator` is the allocator used for the previous allocation
+    unsafe fn dealloc<A: Allocator>(self, old_capacity: usize, allocator: &A);
 
     /// Copies `count` elements from `src` index to `dst` index in each of the
     /// arrays.
@@ -128,6 +185,17 @@ pub unsafe trait SoaRaw: Copy + Clone {
     /// - `dst + count <= PREV_CAP`
     unsafe fn copy_to(self, dst: Self, count: usize);
 
+    /// Like [`SoaRaw::copy_to`], but copies the arrays in reverse order.
+    ///
+    /// This is needed when moving the arrays to higher addresses within the
+    /// same allocation, where an array could otherwise overwrite the contents
+    /// of the next one before they are moved.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`SoaRaw::copy_to`].
+    unsafe fn copy_to_rev(self, dst: Self, count: usize);
+
     /// Sets the element at `index` to `element`.
     ///
     /// # Safety
@@ -184,4 +252,21 @@ pub unsafe trait SoaRaw: Copy + Clone {
     unsafe fn slices<'a>(self, len: usize) -> <Self::Item as Soars>::Slices<'a>;
 
     unsafe fn slices_mut<'a>(self, len: usize) -> <Self::Item as Soars>::SlicesMut<'a>;
+
+    /// Gets the `N` elements starting at `index` as arrays.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that
+    ///
+    /// - `index + N <= PREV_CAP`
+    /// - the elements are initialized
+    unsafe fn chunk<'a, const N: usize>(self) -> <Self::Item as Soars>::Chunk<'a, N>;
+
+    /// Gets the `N` elements starting at `index` as mutable arrays.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`SoaRaw::chunk`].
+    unsafe fn chunk_mut<'a, const N: usize>(self) -> <Self::Item as Soars>::ChunkMut<'a, N>;
 }
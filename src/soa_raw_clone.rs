@@ -0,0 +1,28 @@
+use crate::SoaRaw;
+
+/// Clones the elements of a [`SoaRaw`] field by field.
+///
+/// This is implemented by the derive macro when each of the fields implements
+/// [`Clone`]. It allows [`Soa`] to implement [`Clone`] without creating a
+/// bitwise copy of an element on the stack, which would be unsound to call
+/// [`Clone::clone`] on in the presence of interior mutability.
+///
+/// # Safety
+///
+/// [`SoaRawClone::clone_to`] must leave `dst` holding a clone of the element
+/// at `self`.
+///
+/// [`Soa`]: crate::Soa
+#[doc(hidden)]
+pub unsafe trait SoaRawClone: SoaRaw {
+    /// Writes a clone of the element at `self` to `dst`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that
+    ///
+    /// - `self` points to an initialized element
+    /// - `dst` points to an uninitialized element, which is overwritten without
+    ///   being dropped
+    unsafe fn clone_to(self, dst: Self);
+}
@@ -0,0 +1,392 @@
+use crate::{Iter, IterMut, Slice, Soa, SoaRawClone, Soars};
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::Zip,
+    ops::Deref,
+    slice,
+};
+
+/// A stable handle to an element of a [`SoaSlotMap`].
+///
+/// A key stays valid until its element is removed. After that, the key is
+/// never valid again, even if its slot is reused for a new element.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SlotKey {
+    index: u32,
+    generation: u32,
+}
+
+/// Maps a [`SlotKey`] to the element's position in the dense storage.
+///
+/// The generation is odd while the slot is occupied, so vacant slots never
+/// match a key.
+#[derive(Copy, Clone, Debug)]
+struct Slot {
+    generation: u32,
+    dense: u32,
+}
+
+/// A slot map that stores its elements densely in a [`Soa`].
+///
+/// Inserting an element returns a [`SlotKey`] that can later be used to access
+/// or remove it. The elements are kept contiguous by moving the last element
+/// into the place of a removed one, so the field slices never have holes.
+/// [`SoaSlotMap::keys`] gives the key for each element in the same order as
+/// the dense storage.
+///
+/// # Examples
+///
+/// ```
+/// # use soa_rs::{SoaSlotMap, Soars};
+/// # #[derive(Soars, Debug, PartialEq)]
+/// # #[soa_derive(Debug, PartialEq)]
+/// # struct Position {
+/// #     x: f32,
+/// #     y: f32,
+/// # }
+/// let mut map = SoaSlotMap::<Position>::new();
+/// let a = map.insert(Position { x: 1.0, y: 2.0 });
+/// let b = map.insert(Position { x: 3.0, y: 4.0 });
+/// assert_eq!(map.x(), [1.0, 3.0]);
+///
+/// assert_eq!(map.remove(a), Some(Position { x: 1.0, y: 2.0 }));
+/// assert_eq!(map.get(a), None);
+/// assert_eq!(map.get(b), Some(PositionRef { x: &3.0, y: &4.0 }));
+/// assert_eq!(map.x(), [3.0]);
+/// ```
+pub struct SoaSlotMap<T>
+where
+    T: Soars,
+{
+    dense: Soa<T>,
+    keys: Vec<SlotKey>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl<T> SoaSlotMap<T>
+where
+    T: Soars,
+{
+    /// Constructs a new, empty `SoaSlotMap<T>`.
+    pub fn new() -> Self {
+        Self {
+            dense: Soa::new(),
+            keys: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Constructs a new, empty `SoaSlotMap<T>` with room for at least
+    /// `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            dense: Soa::with_capacity(capacity),
+            keys: Vec::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+        }
+    }
+
+    /// Inserts an element and returns its key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map would need more than `u32::MAX` slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaSlotMap, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut map = SoaSlotMap::<Foo>::new();
+    /// let key = map.insert(Foo(10));
+    /// assert_eq!(map.get(key), Some(FooRef(&10)));
+    /// ```
+    pub fn insert(&mut self, element: T) -> SlotKey {
+        let dense = u32::try_from(self.dense.len()).expect("too many slots");
+        let index = match self.free.last() {
+            Some(&index) => index,
+            None => u32::try_from(self.slots.len()).expect("too many slots"),
+        };
+
+        // Store the element before updating the slots so that a panic while
+        // allocating leaves the map unchanged.
+        self.keys.reserve(1);
+        if self.free.is_empty() {
+            self.slots.reserve(1);
+        }
+        self.dense.push(element);
+
+        if self.free.pop().is_none() {
+            self.slots.push(Slot {
+                generation: 0,
+                dense: 0,
+            });
+        }
+        let slot = &mut self.slots[index as usize];
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.dense = dense;
+        let key = SlotKey {
+            index,
+            generation: slot.generation,
+        };
+        self.keys.push(key);
+        key
+    }
+
+    /// Removes the element for `key` and returns it, or [`None`] if the key is
+    /// no longer valid.
+    ///
+    /// The last element is moved into the place of the removed one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaSlotMap, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut map = SoaSlotMap::<Foo>::new();
+    /// let a = map.insert(Foo(1));
+    /// let b = map.insert(Foo(2));
+    /// let c = map.insert(Foo(3));
+    /// assert_eq!(map.remove(a), Some(Foo(1)));
+    /// assert_eq!(map.remove(a), None);
+    /// assert_eq!(map.f0(), [3, 2]);
+    /// assert_eq!(map.keys(), [c, b]);
+    /// ```
+    pub fn remove(&mut self, key: SlotKey) -> Option<T> {
+        let dense = self.index_of(key)?;
+        let out = self.dense.swap_remove(dense);
+        self.keys.swap_remove(dense);
+        if let Some(moved) = self.keys.get(dense) {
+            self.slots[moved.index as usize].dense = dense as u32;
+        }
+
+        let slot = &mut self.slots[key.index as usize];
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.index);
+        Some(out)
+    }
+
+    /// Returns `true` if the map contains an element for `key`.
+    pub fn contains_key(&self, key: SlotKey) -> bool {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns the position of the element for `key` in the dense storage, or
+    /// [`None`] if the key is no longer valid.
+    ///
+    /// The position changes when other elements are removed.
+    pub fn index_of(&self, key: SlotKey) -> Option<usize> {
+        let slot = self.slots.get(key.index as usize)?;
+        (slot.generation == key.generation).then_some(slot.dense as usize)
+    }
+
+    /// Returns a reference to the element for `key`, or [`None`] if the key is
+    /// no longer valid.
+    pub fn get(&self, key: SlotKey) -> Option<T::Ref<'_>> {
+        let index = self.index_of(key)?;
+        Some(self.dense.idx(index))
+    }
+
+    /// Returns a mutable reference to the element for `key`, or [`None`] if
+    /// the key is no longer valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaSlotMap, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut map = SoaSlotMap::<Foo>::new();
+    /// let key = map.insert(Foo(10));
+    /// *map.get_mut(key).unwrap().0 += 1;
+    /// assert_eq!(map.f0(), [11]);
+    /// ```
+    pub fn get_mut(&mut self, key: SlotKey) -> Option<T::RefMut<'_>> {
+        let index = self.index_of(key)?;
+        Some(self.dense.idx_mut(index))
+    }
+
+    /// Returns the keys of the elements, in the same order as the dense
+    /// storage.
+    pub fn keys(&self) -> &[SlotKey] {
+        &self.keys
+    }
+
+    /// Returns an iterator over the keys and elements, in the order of the
+    /// dense storage.
+    pub fn iter(&self) -> Zip<slice::Iter<'_, SlotKey>, Iter<'_, T>> {
+        self.keys.iter().zip(self.dense.iter())
+    }
+
+    /// Returns an iterator over the keys and mutable references to the
+    /// elements, in the order of the dense storage.
+    pub fn iter_mut(&mut self) -> Zip<slice::Iter<'_, SlotKey>, IterMut<'_, T>> {
+        self.keys.iter().zip(self.dense.iter_mut())
+    }
+
+    /// Returns mutable slices of the fields of the dense storage.
+    ///
+    /// The elements can't be reordered through the slices without breaking
+    /// the association with their keys, so only the field values should be
+    /// modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaSlotMap, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Particle {
+    /// #     pos: f32,
+    /// #     vel: f32,
+    /// # }
+    /// let mut map = SoaSlotMap::<Particle>::new();
+    /// map.insert(Particle { pos: 0.0, vel: 1.0 });
+    /// map.insert(Particle { pos: 5.0, vel: -1.0 });
+    /// let slices = map.slices_mut();
+    /// for (pos, vel) in slices.pos.iter_mut().zip(slices.vel.iter()) {
+    ///     *pos += vel;
+    /// }
+    /// assert_eq!(map.pos(), [1.0, 4.0]);
+    /// ```
+    pub fn slices_mut(&mut self) -> T::SlicesMut<'_> {
+        self.dense.slices_mut()
+    }
+
+    /// Removes all elements and invalidates their keys.
+    pub fn clear(&mut self) {
+        for key in self.keys.drain(..) {
+            let slot = &mut self.slots[key.index as usize];
+            slot.generation = slot.generation.wrapping_add(1);
+            self.free.push(key.index);
+        }
+        self.dense.clear();
+    }
+
+    /// Retains only the elements specified by the predicate, invalidating the
+    /// keys of the removed elements.
+    ///
+    /// This does not preserve the order of the retained elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use soa_rs::{SoaSlotMap, Soars};
+    /// # #[derive(Soars, Debug, PartialEq)]
+    /// # #[soa_derive(Debug, PartialEq)]
+    /// # struct Foo(usize);
+    /// let mut map = SoaSlotMap::<Foo>::new();
+    /// let keys: Vec<_> = (0..4).map(|i| map.insert(Foo(i))).collect();
+    /// map.retain(|_, foo| *foo.0 % 2 == 1);
+    /// assert!(!map.contains_key(keys[0]));
+    /// assert_eq!(map.get(keys[3]), Some(FooRef(&3)));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(SlotKey, T::RefMut<'_>) -> bool,
+    {
+        let mut i = 0;
+        while i < self.dense.len() {
+            let key = self.keys[i];
+            if f(key, self.dense.idx_mut(i)) {
+                i += 1;
+            } else {
+                self.remove(key);
+            }
+        }
+    }
+
+    /// Consumes the map and returns the dense storage.
+    pub fn into_soa(self) -> Soa<T> {
+        self.dense
+    }
+}
+
+impl<T> Deref for SoaSlotMap<T>
+where
+    T: Soars,
+{
+    type Target = Slice<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.dense.as_slice()
+    }
+}
+
+impl<T> AsRef<Slice<T>> for SoaSlotMap<T>
+where
+    T: Soars,
+{
+    fn as_ref(&self) -> &Slice<T> {
+        self.dense.as_slice()
+    }
+}
+
+impl<T> Clone for SoaSlotMap<T>
+where
+    T: Soars + Clone,
+    T::Raw: SoaRawClone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            dense: self.dense.clone(),
+            keys: self.keys.clone(),
+            slots: self.slots.clone(),
+            free: self.free.clone(),
+        }
+    }
+}
+
+impl<T> Debug for SoaSlotMap<T>
+where
+    T: Soars,
+    for<'a> T::Ref<'a>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Default for SoaSlotMap<T>
+where
+    T: Soars,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SoaSlotMap<T>
+where
+    T: Soars,
+{
+    type Item = (&'a SlotKey, T::Ref<'a>);
+
+    type IntoIter = Zip<slice::Iter<'a, SlotKey>, Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SoaSlotMap<T>
+where
+    T: Soars,
+{
+    type Item = (&'a SlotKey, T::RefMut<'a>);
+
+    type IntoIter = Zip<slice::Iter<'a, SlotKey>, IterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
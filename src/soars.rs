@@ -61,4 +61,24 @@ pub unsafe trait Soars: AsSoaRef<Item = Self> {
     type SlicesMut<'a>
     where
         Self: 'a;
+
+    /// A fixed-size chunk of a [`Slice`].
+    ///
+    /// For each field with type `T`, this type has a field with type
+    /// `&[T; N]`.
+    ///
+    /// [`Slice`]: crate::Slice
+    type Chunk<'a, const N: usize>
+    where
+        Self: 'a;
+
+    /// A mutable fixed-size chunk of a [`Slice`].
+    ///
+    /// For each field with type `T`, this type has a field with type
+    /// `&mut [T; N]`.
+    ///
+    /// [`Slice`]: crate::Slice
+    type ChunkMut<'a, const N: usize>
+    where
+        Self: 'a;
 }
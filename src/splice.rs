@@ -0,0 +1,128 @@
+use crate::{Allocator, Drain, Global, Soars};
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::{self, FusedIterator},
+};
+
+/// A splicing iterator for [`Soa`].
+///
+/// This struct is created by [`Soa::splice`]. See its documentation for more.
+///
+/// [`Soa`]: crate::Soa
+/// [`Soa::splice`]: crate::Soa::splice
+pub struct Splice<'a, I, A = Global>
+where
+    I: Iterator,
+    I::Item: 'a + Soars,
+    A: Allocator,
+{
+    pub(crate) drain: Drain<'a, I::Item, A>,
+    pub(crate) replace_with: I,
+}
+
+impl<I, A> Iterator for Splice<'_, I, A>
+where
+    I: Iterator,
+    I::Item: Soars,
+    A: Allocator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.drain.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<I, A> DoubleEndedIterator for Splice<'_, I, A>
+where
+    I: Iterator,
+    I::Item: Soars,
+    A: Allocator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<I, A> FusedIterator for Splice<'_, I, A>
+where
+    I: Iterator,
+    I::Item: Soars,
+    A: Allocator,
+{
+}
+
+impl<I, A> ExactSizeIterator for Splice<'_, I, A>
+where
+    I: Iterator,
+    I::Item: Soars,
+    A: Allocator,
+{
+}
+
+impl<I, A> Debug for Splice<'_, I, A>
+where
+    I: Iterator + Debug,
+    I::Item: Soars,
+    for<'b> <I::Item as Soars>::Ref<'b>: Debug,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Splice")
+            .field("drain", &self.drain)
+            .field("replace_with", &self.replace_with)
+            .finish()
+    }
+}
+
+impl<I, A> Drop for Splice<'_, I, A>
+where
+    I: Iterator,
+    I::Item: Soars,
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        self.drain.by_ref().for_each(drop);
+
+        unsafe {
+            if self.drain.tail_len == 0 {
+                self.drain.soa.extend(self.replace_with.by_ref());
+                return;
+            }
+
+            // First fill the range left by drain()
+            if !self.drain.fill(&mut self.replace_with) {
+                return;
+            }
+
+            // There may be more elements. Use the lower bound as an estimate.
+            let (lower_bound, _) = self.replace_with.size_hint();
+            if lower_bound > 0 {
+                self.drain.move_tail(lower_bound);
+                if !self.drain.fill(&mut self.replace_with) {
+                    return;
+                }
+            }
+
+            // The estimate was too low. Make room for the remaining elements
+            // in place, doubling the gap so that the tail is only moved a
+            // logarithmic number of times.
+            let mut additional = lower_bound;
+            while let Some(element) = self.replace_with.next() {
+                let (lower_bound, _) = self.replace_with.size_hint();
+                additional = additional
+                    .saturating_mul(2)
+                    .max(lower_bound.saturating_add(1));
+                self.drain.move_tail(additional);
+                let mut rest = iter::once(element).chain(self.replace_with.by_ref());
+                if !self.drain.fill(&mut rest) {
+                    return;
+                }
+            }
+        }
+    }
+}
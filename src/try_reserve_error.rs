@@ -0,0 +1,124 @@
+use alloc::alloc::handle_alloc_error;
+use core::{
+    alloc::{Layout, LayoutError},
+    fmt::{self, Debug, Display, Formatter},
+};
+
+/// The error type for `try_reserve` methods.
+///
+/// This mirrors [`std::collections::TryReserveError`], which cannot be
+/// constructed outside the standard library.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TryReserveError {
+    kind: TryReserveErrorKind,
+}
+
+impl TryReserveError {
+    /// Details about the allocation that caused the error.
+    pub fn kind(&self) -> TryReserveErrorKind {
+        self.kind.clone()
+    }
+
+    /// Panics or aborts the same way an infallible allocation would.
+    pub(crate) fn handle(self) -> ! {
+        match self.kind {
+            TryReserveErrorKind::CapacityOverflow => panic!("capacity overflow"),
+            TryReserveErrorKind::AllocError { layout } => handle_alloc_error(layout),
+        }
+    }
+}
+
+/// Details of the allocation that caused a [`TryReserveError`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TryReserveErrorKind {
+    /// Error due to the computed capacity exceeding the collection's maximum
+    /// (usually `isize::MAX` bytes).
+    CapacityOverflow,
+
+    /// The memory allocator returned an error.
+    AllocError {
+        /// The layout of the allocation request that failed.
+        layout: Layout,
+    },
+}
+
+impl From<TryReserveErrorKind> for TryReserveError {
+    fn from(kind: TryReserveErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl From<LayoutError> for TryReserveError {
+    fn from(_: LayoutError) -> Self {
+        TryReserveErrorKind::CapacityOverflow.into()
+    }
+}
+
+impl Display for TryReserveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        let reason = match self.kind {
+            TryReserveErrorKind::CapacityOverflow => {
+                " because the computed capacity exceeded the collection's maximum"
+            }
+            TryReserveErrorKind::AllocError { .. } => {
+                " because the memory allocator returned an error"
+            }
+        };
+        f.write_str(reason)
+    }
+}
+
+// `core::error::Error` requires Rust 1.81
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {}
+
+/// The error type for [`Soa::try_push`], which gives back the element that
+/// could not be pushed.
+///
+/// [`Soa::try_push`]: crate::Soa::try_push
+#[derive(Clone, PartialEq, Eq)]
+pub struct TryPushError<T> {
+    element: T,
+    error: TryReserveError,
+}
+
+impl<T> TryPushError<T> {
+    pub(crate) fn new(element: T, error: TryReserveError) -> Self {
+        Self { element, error }
+    }
+
+    /// The allocation error that prevented the push.
+    pub fn error(&self) -> &TryReserveError {
+        &self.error
+    }
+
+    /// Returns the element that could not be pushed.
+    pub fn into_element(self) -> T {
+        self.element
+    }
+
+    /// Returns the element that could not be pushed and the allocation error.
+    pub fn into_parts(self) -> (T, TryReserveError) {
+        (self.element, self.error)
+    }
+}
+
+// Implemented by hand so that the element isn't required to be Debug, as with
+// the error types of std::sync::mpsc.
+impl<T> Debug for TryPushError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryPushError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Display for TryPushError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for TryPushError<T> {}
@@ -0,0 +1,84 @@
+use crate::{Slice, SliceRef, SoaRaw, Soars};
+use core::{iter::FusedIterator, marker::PhantomData};
+
+/// An iterator over overlapping subslices of length `size`.
+///
+/// This struct is created by the [`windows`] method.
+///
+/// [`windows`]: Slice::windows
+pub struct Windows<'a, T>
+where
+    T: 'a + Soars,
+{
+    slice: Slice<T, ()>,
+    len: usize,
+    size: usize,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Windows<'a, T>
+where
+    T: Soars,
+{
+    pub(crate) fn new(slice: &'a Slice<T>, size: usize) -> Self {
+        Self {
+            len: slice.len(),
+            slice: unsafe { slice.as_sized() },
+            size,
+            marker: PhantomData,
+        }
+    }
+}
+
+// SAFETY: Windows is Send and Sync under the same conditions as &[T].
+unsafe impl<'a, T> Send for Windows<'a, T> where T: 'a + Sync + Soars {}
+unsafe impl<'a, T> Sync for Windows<'a, T> where T: 'a + Sync + Soars {}
+
+impl<'a, T> Iterator for Windows<'a, T>
+where
+    T: Soars,
+{
+    type Item = SliceRef<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.size > self.len {
+            None
+        } else {
+            let out = SliceRef {
+                slice: self.slice,
+                len: self.size,
+                marker: PhantomData,
+            };
+            self.len -= 1;
+            self.slice.raw = unsafe { self.slice.raw().offset(1) };
+            Some(out)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = (self.len + 1).saturating_sub(self.size);
+        (n, Some(n))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Windows<'a, T>
+where
+    T: Soars,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.size > self.len {
+            None
+        } else {
+            let offset = self.len - self.size;
+            self.len -= 1;
+            Some(SliceRef {
+                slice: Slice::with_raw(unsafe { self.slice.raw().offset(offset) }),
+                len: self.size,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for Windows<'a, T> where T: Soars {}
+impl<'a, T> ExactSizeIterator for Windows<'a, T> where T: Soars {}